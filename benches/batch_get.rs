@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Compares `TraciClient::execute_batch` with one round trip per GET command.
+//!
+//! A loopback server answers every vehicle GET with a double, so the numbers
+//! measure the client and the round trips rather than SUMO itself. Run with
+//! `cargo bench --bench batch_get`.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use traci_rs::{constants::*, CommandBatch, TraciClient, VehicleScope};
+
+/// Answer every command of every incoming message with an OK status, plus a
+/// double value for vehicle GETs and a version response for `CMD_GETVERSION`.
+fn serve(mut stream: TcpStream) {
+    stream.set_nodelay(true).unwrap();
+    let mut header = [0u8; 4];
+    while stream.read_exact(&mut header).is_ok() {
+        let mut payload = vec![0u8; u32::from_be_bytes(header) as usize - 4];
+        if stream.read_exact(&mut payload).is_err() {
+            return;
+        }
+        let mut reply = vec![0u8; 4];
+        let mut pos = 0;
+        while pos < payload.len() {
+            // A command starts with its length: one byte, or 0 and an i32.
+            let (len, body) = match payload[pos] {
+                0 => {
+                    let len = i32::from_be_bytes(payload[pos + 1..pos + 5].try_into().unwrap());
+                    (len as usize, pos + 5)
+                }
+                len => (len as usize, pos + 1),
+            };
+            let cmd = payload[body];
+            reply.extend([7, cmd, RTYPE_OK, 0, 0, 0, 0]);
+            if cmd == CMD_GET_VEHICLE_VARIABLE {
+                // Echo variable and object id, then answer with a double.
+                let var_and_id = &payload[body + 1..pos + len];
+                reply.push((2 + var_and_id.len() + 9) as u8);
+                reply.push(RESPONSE_GET_VEHICLE_VARIABLE);
+                reply.extend(var_and_id);
+                reply.push(TYPE_DOUBLE);
+                reply.extend(13.9f64.to_be_bytes());
+            } else if cmd == CMD_GETVERSION {
+                reply.extend([10, CMD_GETVERSION, 0, 0, 0, 21, 0, 0, 0, 0]);
+            }
+            pos += len;
+        }
+        let total = reply.len() as u32;
+        reply[..4].copy_from_slice(&total.to_be_bytes());
+        if stream.write_all(&reply).is_err() {
+            return;
+        }
+    }
+}
+
+fn connect() -> TraciClient {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || serve(listener.accept().unwrap().0));
+    TraciClient::connect("127.0.0.1", port).unwrap()
+}
+
+/// Speed, angle and lane position of every vehicle.
+fn get_kinematics(c: &mut Criterion) {
+    let mut client = connect();
+    let vehicle = VehicleScope::default();
+
+    let mut group = c.benchmark_group("get_kinematics");
+    for fleet in [10, 100, 500] {
+        let ids: Vec<String> = (0..fleet).map(|i| format!("veh{i}")).collect();
+        group.bench_with_input(BenchmarkId::new("execute_batch", fleet), &ids, |b, ids| {
+            b.iter(|| {
+                let mut batch = CommandBatch::new();
+                for id in ids {
+                    batch
+                        .get(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, id)
+                        .get(CMD_GET_VEHICLE_VARIABLE, VAR_ANGLE, id)
+                        .get(CMD_GET_VEHICLE_VARIABLE, VAR_LANEPOSITION, id);
+                }
+                client.execute_batch(&batch).unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("one_by_one", fleet), &ids, |b, ids| {
+            b.iter(|| {
+                for id in ids {
+                    vehicle.get_speed(&mut client, id).unwrap();
+                    vehicle.get_angle(&mut client, id).unwrap();
+                    vehicle.get_lane_position(&mut client, id).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, get_kinematics);
+criterion_main!(benches);
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Compares `TraciClient::batch_set` with one round trip per SET command.
+//!
+//! A loopback server acknowledges every command it receives, so the numbers
+//! measure the client and the round trips rather than SUMO itself. Run with
+//! `cargo bench --bench batch_set`.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use traci_rs::{constants::*, storage::Storage, TraciClient, VehicleScope};
+
+/// Answer every command of every incoming message with an OK status, plus a
+/// version response for `CMD_GETVERSION`.
+fn serve(mut stream: TcpStream) {
+    stream.set_nodelay(true).unwrap();
+    let mut header = [0u8; 4];
+    while stream.read_exact(&mut header).is_ok() {
+        let mut payload = vec![0u8; u32::from_be_bytes(header) as usize - 4];
+        if stream.read_exact(&mut payload).is_err() {
+            return;
+        }
+        let mut reply = vec![0u8; 4];
+        let mut pos = 0;
+        while pos < payload.len() {
+            // A command starts with its length: one byte, or 0 and an i32.
+            let (len, cmd) = match payload[pos] {
+                0 => {
+                    let len = i32::from_be_bytes(payload[pos + 1..pos + 5].try_into().unwrap());
+                    (len as usize, payload[pos + 5])
+                }
+                len => (len as usize, payload[pos + 1]),
+            };
+            reply.extend([7, cmd, RTYPE_OK, 0, 0, 0, 0]);
+            if cmd == CMD_GETVERSION {
+                reply.extend([10, CMD_GETVERSION, 0, 0, 0, 21, 0, 0, 0, 0]);
+            }
+            pos += len;
+        }
+        let total = reply.len() as u32;
+        reply[..4].copy_from_slice(&total.to_be_bytes());
+        if stream.write_all(&reply).is_err() {
+            return;
+        }
+    }
+}
+
+fn connect() -> TraciClient {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || serve(listener.accept().unwrap().0));
+    TraciClient::connect("127.0.0.1", port).unwrap()
+}
+
+fn set_speed(c: &mut Criterion) {
+    let mut client = connect();
+    let mut speed = Storage::new();
+    speed.write_u8(TYPE_DOUBLE);
+    speed.write_f64(13.9);
+
+    let mut group = c.benchmark_group("set_speed");
+    for fleet in [10, 100, 500] {
+        let ids: Vec<String> = (0..fleet).map(|i| format!("veh{i}")).collect();
+        group.bench_with_input(BenchmarkId::new("batch_set", fleet), &ids, |b, ids| {
+            b.iter(|| {
+                let requests: Vec<_> = ids
+                    .iter()
+                    .map(|id| (CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, id.as_str(), speed.clone()))
+                    .collect();
+                client.batch_set(&requests).unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("one_by_one", fleet), &ids, |b, ids| {
+            b.iter(|| {
+                for id in ids {
+                    VehicleScope::default().set_speed(&mut client, id, 13.9).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, set_speed);
+criterion_main!(benches);
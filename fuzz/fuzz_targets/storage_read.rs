@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Feed arbitrary bytes to the `Storage` readers and the typed value
+//! decoder. Malformed input must surface as a `TraciError`, never a panic.
+//!
+//! Run with `cargo fuzz run storage_read` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use traci_rs::storage::Storage;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = traci_rs::client::fuzz_read_typed_value(data);
+
+    // The first byte picks a sequence of reads over the rest of the input.
+    let Some((&ops, rest)) = data.split_first() else { return };
+    let mut s = Storage::from_bytes(rest.to_vec());
+    let mut op = ops;
+    while s.valid_pos() {
+        let result = match op % 10 {
+            0 => s.read_u8().map(drop),
+            1 => s.read_byte().map(drop),
+            2 => s.read_i16().map(drop),
+            3 => s.read_i32().map(drop),
+            4 => s.read_f32().map(drop),
+            5 => s.read_f64().map(drop),
+            6 => s.read_string().map(drop),
+            7 => s.read_string_list().map(drop),
+            8 => s.read_f64_list().map(drop),
+            _ => s.read_u8_list().map(drop),
+        };
+        if result.is_err() {
+            break;
+        }
+        op = op.rotate_left(3).wrapping_add(1);
+    }
+});
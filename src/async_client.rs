@@ -0,0 +1,474 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Asynchronous TraCI client built on tokio (feature `tokio`).
+//!
+//! [`AsyncTraciClient`] speaks the same protocol as the blocking
+//! [`TraciClient`] and shares its wire encoding —
+//! [`Storage`], the [`constants`](crate::constants) and the [`TraciValue`]
+//! decoding — so only the transport differs. Instead of the per-domain scopes
+//! it offers typed getters such as [`get_double`](AsyncTraciClient::get_double),
+//! the generic [`get`](AsyncTraciClient::get) and
+//! [`set`](AsyncTraciClient::set), all addressed with the same
+//! `CMD_*` / `VAR_*` constants the scopes use.
+//!
+//! ```no_run
+//! use traci_rs::{constants::*, AsyncTraciClient};
+//!
+//! # async fn run() -> Result<(), traci_rs::TraciError> {
+//! let mut client = AsyncTraciClient::connect("127.0.0.1", 8813).await?;
+//! while client.simulation_step(0.0).await? {
+//!     for id in client.get_id_list(CMD_GET_VEHICLE_VARIABLE).await? {
+//!         let speed = client.get_double(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, &id).await?;
+//!         println!("{id}: {speed} m/s");
+//!     }
+//! }
+//! client.close().await
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::{
+    client::{domain_map, read_subscription_response, DomainId, SubscriptionResponse, TraciClient},
+    constants::*,
+    error::TraciError,
+    socket::AsyncTraciSocket,
+    storage::Storage,
+    types::{ContextSubscriptionResults, SubscriptionResults, TraciColor, TraciPosition, TraciValue, Version},
+};
+
+/// Asynchronous counterpart of [`TraciClient`].
+pub struct AsyncTraciClient {
+    socket: Option<AsyncTraciSocket>,
+    // Domain map: response-subscribe command id → domain name (for dispatch)
+    domains: HashMap<u8, DomainId>,
+    // Versions reported by the server when connecting
+    version: Version,
+    // Subscription results of the last step, per domain
+    subscription_results: HashMap<DomainId, SubscriptionResults>,
+    context_subscription_results: HashMap<DomainId, ContextSubscriptionResults>,
+}
+
+impl AsyncTraciClient {
+    // -----------------------------------------------------------------------
+    // Connection
+    // -----------------------------------------------------------------------
+
+    /// Connect to a SUMO server and return a ready client; see
+    /// [`TraciClient::connect`].
+    ///
+    /// A refused connection is retried [`DEFAULT_NUM_RETRIES`] times,
+    /// [`DEFAULT_RETRY_DELAY_MS`] apart, and the versions are queried once
+    /// connected.
+    pub async fn connect(host: &str, port: u16) -> Result<Self, TraciError> {
+        Self::connect_with_retries(host, port, DEFAULT_NUM_RETRIES, Duration::from_millis(DEFAULT_RETRY_DELAY_MS)).await
+    }
+
+    /// Connect to a SUMO server, retrying up to `retries` more times,
+    /// `delay` apart, while the connection is refused; see
+    /// [`TraciClient::connect_with_retries`].
+    pub async fn connect_with_retries(host: &str, port: u16, retries: i32, delay: Duration) -> Result<Self, TraciError> {
+        let socket = AsyncTraciSocket::connect_with_retries(host, port, retries, delay).await?;
+        let mut client = Self {
+            socket: Some(socket),
+            domains: domain_map(),
+            version: Version::default(),
+            subscription_results: HashMap::new(),
+            context_subscription_results: HashMap::new(),
+        };
+        client.get_version().await?;
+        Ok(client)
+    }
+
+    /// Set the client order; see [`TraciClient::set_order`].
+    pub async fn set_order(&mut self, order: i32) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        msg.write_u8(1 + 1 + 4);
+        msg.write_u8(CMD_SETORDER);
+        msg.write_i32(order);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, CMD_SETORDER, false, None)
+    }
+
+    /// Return the TraCI API version and the SUMO version string.
+    pub async fn get_version(&mut self) -> Result<(i32, String), TraciError> {
+        let mut msg = Storage::new();
+        msg.write_u8(2);
+        msg.write_u8(CMD_GETVERSION);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, CMD_GETVERSION, false, None)?;
+        in_msg.read_u8()?; // msg length
+        in_msg.read_u8()?; // CMD_GETVERSION echo
+        let version = in_msg.read_i32()?;
+        let sumo_version = in_msg.read_string()?;
+        self.version = Version { api: version, sumo: sumo_version.clone() };
+        Ok((version, sumo_version))
+    }
+
+    /// Return the server versions queried when connecting, without a round
+    /// trip; see [`TraciClient::version`].
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Advance the simulation; see [`TraciClient::simulation_step`].
+    ///
+    /// Returns `Ok(false)` once SUMO signals end-of-simulation, and fails
+    /// with [`TraciError::SimulationEnd`] if the server has already closed
+    /// the connection cleanly. Subscription results are replaced by the ones
+    /// received with this step.
+    pub async fn simulation_step(&mut self, time: f64) -> Result<bool, TraciError> {
+        let mut msg = Storage::new();
+        msg.write_u8(1 + 1 + 8);
+        msg.write_u8(CMD_SIMSTEP);
+        msg.write_f64(time);
+        let mut in_msg = match self.round_trip(&msg).await {
+            Ok(in_msg) => in_msg,
+            Err(TraciError::Connection(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                self.socket = None;
+                return Err(TraciError::SimulationEnd);
+            }
+            Err(e) => return Err(e),
+        };
+        TraciClient::check_result_state_static(&mut in_msg, CMD_SIMSTEP, false, None)?;
+
+        self.subscription_results.clear();
+        self.context_subscription_results.clear();
+
+        let num_subs = in_msg.read_i32()?;
+        for _ in 0..num_subs {
+            match read_subscription_response(&self.domains, &mut in_msg)? {
+                SubscriptionResponse::Close => return Ok(false),
+                SubscriptionResponse::Variable { domain, object_id, results } => {
+                    self.subscription_results.entry(domain).or_default().insert(object_id, results);
+                }
+                SubscriptionResponse::Context { domain: Some(domain), context_id, results } => {
+                    self.context_subscription_results.entry(domain).or_default().insert(context_id, results);
+                }
+                SubscriptionResponse::Context { domain: None, .. } => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// Send the close command and shut down the socket.
+    ///
+    /// Closing an already closed client is a no-op; the socket is released
+    /// even if the close handshake fails, and the result is that of the
+    /// handshake.
+    pub async fn close(&mut self) -> Result<(), TraciError> {
+        if self.socket.is_none() {
+            return Ok(());
+        }
+        let mut msg = Storage::new();
+        msg.write_u8(1 + 1);
+        msg.write_u8(CMD_CLOSE);
+        let result = match self.round_trip(&msg).await {
+            Ok(mut in_msg) => TraciClient::check_result_state_static(&mut in_msg, CMD_CLOSE, false, None),
+            Err(e) => Err(e),
+        };
+        if let Some(mut socket) = self.socket.take() {
+            // SUMO may already have closed its end after acknowledging.
+            let _ = socket.close().await;
+        }
+        result
+    }
+
+    // -----------------------------------------------------------------------
+    // Typed getters
+    //
+    // The counterparts of the `scope_get_*` helpers behind the scope getters:
+    // `get_double(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, id)` returns what
+    // `VehicleScope::get_speed` does. A value of another type is a
+    // `TraciError::Protocol`.
+    // -----------------------------------------------------------------------
+
+    /// Return the ids of all objects of a domain, e.g.
+    /// `get_id_list(CMD_GET_VEHICLE_VARIABLE)`.
+    pub async fn get_id_list(&mut self, cmd_id: u8) -> Result<Vec<String>, TraciError> {
+        self.get_string_list(cmd_id, TRACI_ID_LIST, "").await
+    }
+
+    /// Return the number of objects of a domain.
+    pub async fn get_id_count(&mut self, cmd_id: u8) -> Result<i32, TraciError> {
+        self.get_int(cmd_id, ID_COUNT, "").await
+    }
+
+    pub async fn get_double(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<f64, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_DOUBLE, Storage::read_f64).await
+    }
+
+    pub async fn get_int(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<i32, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_INTEGER, Storage::read_i32).await
+    }
+
+    pub async fn get_string(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<String, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_STRING, Storage::read_string).await
+    }
+
+    pub async fn get_string_list(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<Vec<String>, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_STRINGLIST, Storage::read_string_list).await
+    }
+
+    pub async fn get_double_list(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<Vec<f64>, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_DOUBLELIST, Storage::read_f64_list).await
+    }
+
+    /// Return a 2-D position, e.g. `VAR_POSITION`.
+    pub async fn get_position(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<TraciPosition, TraciError> {
+        self.query(cmd_id, var_id, obj_id, POSITION_2D, |in_msg| {
+            let x = in_msg.read_f64()?;
+            let y = in_msg.read_f64()?;
+            Ok(TraciPosition::new_2d(x, y))
+        })
+        .await
+    }
+
+    /// Return a 3-D position, e.g. `VAR_POSITION3D`.
+    pub async fn get_position_3d(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<TraciPosition, TraciError> {
+        self.query(cmd_id, var_id, obj_id, POSITION_3D, |in_msg| {
+            let x = in_msg.read_f64()?;
+            let y = in_msg.read_f64()?;
+            let z = in_msg.read_f64()?;
+            Ok(TraciPosition::new_3d(x, y, z))
+        })
+        .await
+    }
+
+    pub async fn get_color(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<TraciColor, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_COLOR, |in_msg| {
+            let r = in_msg.read_u8()?;
+            let g = in_msg.read_u8()?;
+            let b = in_msg.read_u8()?;
+            let a = in_msg.read_u8()?;
+            Ok(TraciColor::new(r, g, b, a))
+        })
+        .await
+    }
+
+    /// Return a shape, e.g. `VAR_SHAPE` of a lane or polygon.
+    pub async fn get_polygon(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> Result<Vec<TraciPosition>, TraciError> {
+        self.query(cmd_id, var_id, obj_id, TYPE_POLYGON, |in_msg| {
+            // Point count is a ubyte; 0 announces a following i32 count.
+            let n = match in_msg.read_u8()? {
+                0 => in_msg.read_i32()?.max(0) as usize,
+                n => n as usize,
+            };
+            let mut points = Vec::new();
+            for _ in 0..n {
+                let x = in_msg.read_f64()?;
+                let y = in_msg.read_f64()?;
+                points.push(TraciPosition::new_2d(x, y));
+            }
+            Ok(points)
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Generic commands
+    // -----------------------------------------------------------------------
+
+    /// Query one variable, e.g.
+    /// `get(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", None)`.
+    ///
+    /// `add` carries the type-tagged parameter of parameterised variables.
+    pub async fn get(
+        &mut self,
+        cmd_id: u8,
+        var_id: u8,
+        obj_id: &str,
+        add: Option<&Storage>,
+    ) -> Result<TraciValue, TraciError> {
+        let mut msg = Storage::new();
+        TraciClient::write_command(&mut msg, cmd_id, var_id, obj_id, add);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, cmd_id, false, None)?;
+        let (_, span) = TraciClient::check_command_get_result_static(&mut in_msg, cmd_id, None, false)?;
+        in_msg.read_u8()?; // variable id
+        in_msg.read_string()?; // object id
+        let type_id = in_msg.read_u8()?;
+        let value = TraciClient::read_typed_value(&mut in_msg, type_id)?;
+        TraciClient::check_response_end(&in_msg, cmd_id, &span)?;
+        Ok(value)
+    }
+
+    /// Change one variable; `value` holds the type-tagged payload exactly as
+    /// a scope setter would build it.
+    pub async fn set(&mut self, cmd_id: u8, var_id: u8, obj_id: &str, value: &Storage) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        TraciClient::write_command(&mut msg, cmd_id, var_id, obj_id, Some(value));
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, cmd_id, false, None)
+    }
+
+    // -----------------------------------------------------------------------
+    // Subscriptions
+    // -----------------------------------------------------------------------
+
+    /// Subscribe an object to receive variable updates every simulation step;
+    /// see [`TraciClient::subscribe_object_variable`].
+    pub async fn subscribe_object_variable(
+        &mut self,
+        dom_id: u8,
+        obj_id: &str,
+        begin_time: f64,
+        end_time: f64,
+        vars: &[u8],
+    ) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        TraciClient::write_subscribe_command(&mut msg, dom_id, obj_id, begin_time, end_time, None, vars);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, dom_id, false, None)
+    }
+
+    /// Subscribe a context (range around object) to receive variable updates;
+    /// see [`TraciClient::subscribe_object_context`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn subscribe_object_context(
+        &mut self,
+        dom_id: u8,
+        obj_id: &str,
+        begin_time: f64,
+        end_time: f64,
+        domain: u8,
+        range: f64,
+        vars: &[u8],
+    ) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        TraciClient::write_subscribe_command(&mut msg, dom_id, obj_id, begin_time, end_time, Some((domain, range)), vars);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, dom_id, false, None)
+    }
+
+    /// Return the variable subscription results of `domain` from the last step.
+    pub fn subscription_results(&self, domain: DomainId) -> Option<&SubscriptionResults> {
+        self.subscription_results.get(&domain)
+    }
+
+    /// Return the context subscription results of `domain` from the last step.
+    pub fn context_subscription_results(&self, domain: DomainId) -> Option<&ContextSubscriptionResults> {
+        self.context_subscription_results.get(&domain)
+    }
+
+    // -----------------------------------------------------------------------
+    // Low-level helpers
+    // -----------------------------------------------------------------------
+
+    /// Send a GET without parameters, check that the value is tagged
+    /// `expected_type` and decode it with `read`, which must consume exactly
+    /// the length the response declares.
+    async fn query<T>(
+        &mut self,
+        cmd_id: u8,
+        var_id: u8,
+        obj_id: &str,
+        expected_type: u8,
+        read: impl FnOnce(&mut Storage) -> Result<T, TraciError>,
+    ) -> Result<T, TraciError> {
+        let mut msg = Storage::new();
+        TraciClient::write_command(&mut msg, cmd_id, var_id, obj_id, None);
+        let mut in_msg = self.round_trip(&msg).await?;
+        TraciClient::check_result_state_static(&mut in_msg, cmd_id, false, None)?;
+        let (_, span) = TraciClient::check_command_get_result_static(&mut in_msg, cmd_id, Some(expected_type), false)?;
+        let value = read(&mut in_msg)?;
+        TraciClient::check_response_end(&in_msg, cmd_id, &span)?;
+        Ok(value)
+    }
+
+    async fn round_trip(&mut self, msg: &Storage) -> Result<Storage, TraciError> {
+        let socket = self.socket.as_mut().ok_or_else(|| {
+            TraciError::Connection(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "TraCI socket is not connected",
+            ))
+        })?;
+        socket.send_exact(msg).await?;
+        socket.receive_exact().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{self, MockServer, Reply};
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    /// Start a mock server whose script begins with the version handshake.
+    fn server(replies: Vec<Reply>) -> MockServer {
+        let mut script = vec![mock::version_reply(21, "SUMO mock").into()];
+        script.extend(replies);
+        MockServer::start(script)
+    }
+
+    #[test]
+    fn connect_step_get_and_close() {
+        let sub = mock::subscription(RESPONSE_SUBSCRIBE_VEHICLE_VARIABLE, "veh0", &[(VAR_SPEED, mock::double(13.9))]);
+        let server = server(vec![
+            mock::step_reply(&[sub]).into(),
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", mock::string_list(&["veh0"])).into(),
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh0", mock::pos_2d(1.0, 2.0)).into(),
+            mock::status(CMD_CLOSE).into(),
+        ]);
+        let port = server.port();
+
+        block_on(async {
+            let mut client = AsyncTraciClient::connect("127.0.0.1", port).await.unwrap();
+            assert_eq!(client.version(), &Version { api: 21, sumo: "SUMO mock".into() });
+            assert!(client.simulation_step(0.0).await.unwrap());
+            let results = client.subscription_results(DomainId::Vehicle).unwrap();
+            assert_eq!(results["veh0"][&VAR_SPEED], TraciValue::Double(13.9));
+            assert_eq!(client.get_id_list(CMD_GET_VEHICLE_VARIABLE).await.unwrap(), vec!["veh0"]);
+            let pos = client.get_position(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh0").await.unwrap();
+            assert_eq!(pos, TraciPosition::new_2d(1.0, 2.0));
+            client.close().await.unwrap();
+        });
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 5);
+        assert_eq!(requests[2], mock::command(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", None));
+        assert_eq!(requests[3], mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh0", None));
+    }
+
+    #[test]
+    fn a_value_of_another_type_is_a_protocol_error() {
+        let server = server(vec![mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", mock::int(3)).into()]);
+        let port = server.port();
+
+        let err = block_on(async {
+            let mut client = AsyncTraciClient::connect("127.0.0.1", port).await.unwrap();
+            client.get_double(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0").await.unwrap_err()
+        });
+        assert!(matches!(err, TraciError::Protocol(_)), "{err:?}");
+    }
+
+    #[test]
+    fn simulation_step_after_a_clean_shutdown_is_simulation_end() {
+        let server = server(vec![Reply::Close]);
+        let port = server.port();
+
+        let err = block_on(async {
+            let mut client = AsyncTraciClient::connect("127.0.0.1", port).await.unwrap();
+            client.simulation_step(0.0).await.unwrap_err()
+        });
+        assert!(matches!(err, TraciError::SimulationEnd), "{err:?}");
+    }
+
+    #[test]
+    fn close_returns_the_handshake_result() {
+        let server = server(vec![mock::error_status(CMD_CLOSE, "cannot close").into()]);
+        let port = server.port();
+
+        let err = block_on(async {
+            let mut client = AsyncTraciClient::connect("127.0.0.1", port).await.unwrap();
+            let err = client.close().await.unwrap_err();
+            // The socket is released regardless, so closing again is a no-op.
+            client.close().await.unwrap();
+            err
+        });
+        assert_eq!(err.server_message(), Some("cannot close"));
+    }
+}
@@ -7,10 +7,14 @@
 //! reference to the client itself (passed through each scope method call).
 
 use std::collections::HashMap;
+use std::ops::Range;
+use std::process::{Child, Command};
+use std::time::Duration;
 
 use crate::{
     constants::*,
     error::TraciError,
+    projection::CoordinateConverter,
     socket::TraciSocket,
     storage::Storage,
     types::*,
@@ -21,6 +25,15 @@ use crate::{
 // ============================================================================
 pub const DEFAULT_VIEW: &str = "View #0";
 
+/// Deepest nesting of `TYPE_COMPOUND` values `read_typed_value` decodes.
+/// SUMO itself nests compounds at most two levels deep; the limit keeps a
+/// malformed message from exhausting the stack.
+const MAX_COMPOUND_DEPTH: usize = 8;
+
+/// How long [`TraciClient::close`] waits for a SUMO process it launched to
+/// exit after the close handshake before killing it.
+const SUMO_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
 // ============================================================================
 // TraciClient
 // ============================================================================
@@ -31,23 +44,37 @@ pub const DEFAULT_VIEW: &str = "View #0";
 /// interact with the simulation:
 ///
 /// ```no_run
-/// # use sumo_traci::TraciClient;
+/// # use traci_rs::TraciClient;
 /// let mut client = TraciClient::connect("localhost", 8813)?;
 /// client.set_order(1)?;
 /// loop {
 ///     client.simulation_step(0.0)?;
-///     let ids = client.vehicle.get_id_list(&mut client)?;
+///     let ids = client.vehicle_get_id_list()?;
 ///     // ...
 /// }
-/// # Ok::<(), sumo_traci::TraciError>(())
+/// # Ok::<(), traci_rs::TraciError>(())
 /// ```
 pub struct TraciClient {
     socket: Option<TraciSocket>,
     // Reusable output / input staging buffers (mirrors myOutput / myInput in C++)
     output: Storage,
     input: Storage,
+    // Command and byte range of the GET response held in `input`
+    get_response: (u8, Range<usize>),
     // Domain map: response-subscribe command id → domain name (for dispatch)
     domains: HashMap<u8, DomainId>,
+    // Geo-conversion parameters, fetched on first use
+    coordinate_converter: Option<CoordinateConverter>,
+    // Server versions, queried when connecting
+    version: Version,
+    // Set once SUMO has signalled the end of the simulation
+    finished: bool,
+    // Whether simulation_step_with_events also fetches person events
+    person_step_events: bool,
+    // Subscriptions and filters issued so far, replayed after a reconnect
+    subscriptions: Vec<SubscriptionRecord>,
+    // SUMO process launched by `start`, terminated with the client
+    sumo_process: Option<Child>,
 
     // -----------------------------------------------------------------------
     // Public scopes — each wraps CMD_GET_*, CMD_SET_*, CMD_SUBSCRIBE_* ids
@@ -71,7 +98,8 @@ pub struct TraciClient {
     pub vehicle_type:     crate::scopes::vehicle_type::VehicleTypeScope,
 }
 
-/// Internal identifier used to route subscription responses back to the right scope.
+/// Identifier of a TraCI domain, used to route subscription responses back to
+/// the right scope (or, for `AsyncTraciClient`, the right results map).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DomainId {
     Edge, Gui, InductionLoop, Junction, Lane, LaneArea,
@@ -79,6 +107,164 @@ pub enum DomainId {
     RouteProbe, Simulation, TrafficLight, Vehicle, VehicleType,
 }
 
+/// A subscription request recorded by the client so that it can be re-issued
+/// by [`TraciClient::replay_subscriptions`].
+#[derive(Debug, Clone)]
+enum SubscriptionRecord {
+    Variable { dom_id: u8, obj_id: String, begin: f64, end: f64, vars: Vec<u8> },
+    Context { dom_id: u8, obj_id: String, begin: f64, end: f64, domain: u8, range: f64, vars: Vec<u8> },
+    /// A filter applies to the context subscription recorded just before it.
+    Filter { filter_type: u8, add: Option<Storage> },
+}
+
+/// A sequence of GET and SET commands sent to SUMO in a single message by
+/// [`TraciClient::execute_batch`].
+///
+/// Commands are addressed with the same `CMD_*` / `VAR_*` constants the
+/// scopes use; SET values and GET parameters carry the type-tagged payload
+/// exactly as a scope method would build it.
+///
+/// ```no_run
+/// # use traci_rs::{constants::*, storage::Storage, CommandBatch, TraciClient};
+/// # let mut client = TraciClient::connect("localhost", 8813)?;
+/// let mut speed = Storage::new();
+/// speed.write_u8(TYPE_DOUBLE);
+/// speed.write_f64(10.0);
+///
+/// let mut batch = CommandBatch::new();
+/// batch
+///     .get(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh0")
+///     .set(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", &speed)
+///     .get(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0");
+/// let results = client.execute_batch(&batch)?;
+/// # Ok::<(), traci_rs::TraciError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandBatch {
+    output: Storage,
+    // (cmd_id, obj_id, is_get) per command, in send order
+    commands: Vec<(u8, String, bool)>,
+}
+
+impl CommandBatch {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a GET of variable `var_id` of `obj_id`.
+    pub fn get(&mut self, cmd_id: u8, var_id: u8, obj_id: &str) -> &mut Self {
+        TraciClient::write_command(&mut self.output, cmd_id, var_id, obj_id, None);
+        self.commands.push((cmd_id, obj_id.to_owned(), true));
+        self
+    }
+
+    /// Queue a GET of a parameterised variable; `add` holds the type-tagged
+    /// parameter.
+    pub fn get_with(&mut self, cmd_id: u8, var_id: u8, obj_id: &str, add: &Storage) -> &mut Self {
+        TraciClient::write_command(&mut self.output, cmd_id, var_id, obj_id, Some(add));
+        self.commands.push((cmd_id, obj_id.to_owned(), true));
+        self
+    }
+
+    /// Queue a SET of variable `var_id` of `obj_id` to `value`.
+    pub fn set(&mut self, cmd_id: u8, var_id: u8, obj_id: &str, value: &Storage) -> &mut Self {
+        TraciClient::write_command(&mut self.output, cmd_id, var_id, obj_id, Some(value));
+        self.commands.push((cmd_id, obj_id.to_owned(), false));
+        self
+    }
+
+    /// Return the number of queued commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Return `true` if no command is queued.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Remove every queued command so the batch can be reused.
+    pub fn clear(&mut self) {
+        self.output.reset();
+        self.commands.clear();
+    }
+}
+
+/// Map every variable-subscription response id to the domain it belongs to.
+/// Context responses use the same id minus `0x50`.
+pub(crate) fn domain_map() -> HashMap<u8, DomainId> {
+    let mut domains = HashMap::new();
+    domains.insert(RESPONSE_SUBSCRIBE_EDGE_VARIABLE,           DomainId::Edge);
+    domains.insert(RESPONSE_SUBSCRIBE_GUI_VARIABLE,            DomainId::Gui);
+    domains.insert(RESPONSE_SUBSCRIBE_INDUCTIONLOOP_VARIABLE,  DomainId::InductionLoop);
+    domains.insert(RESPONSE_SUBSCRIBE_JUNCTION_VARIABLE,       DomainId::Junction);
+    domains.insert(RESPONSE_SUBSCRIBE_LANE_VARIABLE,           DomainId::Lane);
+    domains.insert(RESPONSE_SUBSCRIBE_LANEAREA_VARIABLE,       DomainId::LaneArea);
+    domains.insert(RESPONSE_SUBSCRIBE_MULTIENTRYEXIT_VARIABLE, DomainId::MultiEntryExit);
+    domains.insert(RESPONSE_SUBSCRIBE_PERSON_VARIABLE,         DomainId::Person);
+    domains.insert(RESPONSE_SUBSCRIBE_POI_VARIABLE,            DomainId::Poi);
+    domains.insert(RESPONSE_SUBSCRIBE_POLYGON_VARIABLE,        DomainId::Polygon);
+    domains.insert(RESPONSE_SUBSCRIBE_REROUTER_VARIABLE,       DomainId::Rerouter);
+    domains.insert(RESPONSE_SUBSCRIBE_ROUTE_VARIABLE,          DomainId::Route);
+    domains.insert(RESPONSE_SUBSCRIBE_ROUTEPROBE_VARIABLE,     DomainId::RouteProbe);
+    domains.insert(RESPONSE_SUBSCRIBE_SIM_VARIABLE,            DomainId::Simulation);
+    domains.insert(RESPONSE_SUBSCRIBE_TL_VARIABLE,             DomainId::TrafficLight);
+    domains.insert(RESPONSE_SUBSCRIBE_VEHICLE_VARIABLE,        DomainId::Vehicle);
+    domains.insert(RESPONSE_SUBSCRIBE_VEHICLETYPE_VARIABLE,    DomainId::VehicleType);
+    domains
+}
+
+/// Map the `CMD_GET_*_VARIABLE` id of a domain to its [`DomainId`].
+pub(crate) fn object_domain(domains: &HashMap<u8, DomainId>, get_cmd: u8) -> Option<DomainId> {
+    // RESPONSE_SUBSCRIBE_*_VARIABLE = CMD_GET_*_VARIABLE + 0x40
+    domains.get(&get_cmd.wrapping_add(0x40)).copied()
+}
+
+/// One subscription response of a `CMD_SIMSTEP` reply, as decoded by
+/// [`read_subscription_response`].
+pub(crate) enum SubscriptionResponse {
+    /// The subscribed variables of one object.
+    Variable { domain: DomainId, object_id: String, results: TraciResults },
+    /// The subscribed variables of the objects around `context_id`;
+    /// `domain` is `None` for a response id the client does not know.
+    Context { domain: Option<DomainId>, context_id: String, results: SubscriptionResults },
+    /// SUMO's end-of-simulation marker, sent as the last block of the reply.
+    Close,
+}
+
+/// Read the next subscription response of a `CMD_SIMSTEP` reply. Shared by
+/// [`TraciClient`] and `AsyncTraciClient`, which only differ in where the
+/// results are stored.
+pub(crate) fn read_subscription_response(
+    domains: &HashMap<u8, DomainId>,
+    in_msg: &mut Storage,
+) -> Result<SubscriptionResponse, TraciError> {
+    let (cmd_id, _) = TraciClient::check_command_get_result_static(in_msg, 0, None, true)?;
+    if cmd_id == CMD_CLOSE {
+        return Ok(SubscriptionResponse::Close);
+    }
+    // A response id registered in the domain map is a variable subscription;
+    // anything else is a context subscription.
+    if let Some(&domain) = domains.get(&cmd_id) {
+        let object_id = in_msg.read_string()?;
+        let var_count = in_msg.read_u8()? as usize;
+        let results = TraciClient::read_variables_static(in_msg, var_count, Some(domain))?;
+        return Ok(SubscriptionResponse::Variable { domain, object_id, results });
+    }
+    let context_id = in_msg.read_string()?;
+    let object_domain = object_domain(domains, in_msg.read_u8()?); // context domain
+    let var_count = in_msg.read_u8()? as usize;
+    let num_objects = in_msg.read_i32()?;
+    let mut results: SubscriptionResults = HashMap::new();
+    for _ in 0..num_objects {
+        let object_id = in_msg.read_string()?;
+        results.insert(object_id, TraciClient::read_variables_static(in_msg, var_count, object_domain)?);
+    }
+    let domain = domains.get(&cmd_id.wrapping_add(0x50)).copied();
+    Ok(SubscriptionResponse::Context { domain, context_id, results })
+}
+
 impl TraciClient {
     // -----------------------------------------------------------------------
     // Connection
@@ -86,33 +272,108 @@ impl TraciClient {
 
     /// Connect to a SUMO server and return a fully initialised client.
     ///
+    /// A refused connection is retried [`DEFAULT_NUM_RETRIES`] times,
+    /// [`DEFAULT_RETRY_DELAY_MS`] apart, like the Python `traci.connect`.
+    /// The server versions are queried once and cached for [`Self::version`].
+    ///
     /// Equivalent to `TraCIAPI::connect` + the constructor initialiser list.
     pub fn connect(host: &str, port: u16) -> Result<Self, TraciError> {
-        let socket = TraciSocket::connect(host, port)?;
-        let mut domains = HashMap::new();
-        domains.insert(RESPONSE_SUBSCRIBE_EDGE_VARIABLE,           DomainId::Edge);
-        domains.insert(RESPONSE_SUBSCRIBE_GUI_VARIABLE,            DomainId::Gui);
-        domains.insert(RESPONSE_SUBSCRIBE_INDUCTIONLOOP_VARIABLE,  DomainId::InductionLoop);
-        domains.insert(RESPONSE_SUBSCRIBE_JUNCTION_VARIABLE,       DomainId::Junction);
-        domains.insert(RESPONSE_SUBSCRIBE_LANE_VARIABLE,           DomainId::Lane);
-        domains.insert(RESPONSE_SUBSCRIBE_LANEAREA_VARIABLE,       DomainId::LaneArea);
-        domains.insert(RESPONSE_SUBSCRIBE_MULTIENTRYEXIT_VARIABLE, DomainId::MultiEntryExit);
-        domains.insert(RESPONSE_SUBSCRIBE_PERSON_VARIABLE,         DomainId::Person);
-        domains.insert(RESPONSE_SUBSCRIBE_POI_VARIABLE,            DomainId::Poi);
-        domains.insert(RESPONSE_SUBSCRIBE_POLYGON_VARIABLE,        DomainId::Polygon);
-        domains.insert(RESPONSE_SUBSCRIBE_REROUTER_VARIABLE,       DomainId::Rerouter);
-        domains.insert(RESPONSE_SUBSCRIBE_ROUTE_VARIABLE,          DomainId::Route);
-        domains.insert(RESPONSE_SUBSCRIBE_ROUTEPROBE_VARIABLE,     DomainId::RouteProbe);
-        domains.insert(RESPONSE_SUBSCRIBE_SIM_VARIABLE,            DomainId::Simulation);
-        domains.insert(RESPONSE_SUBSCRIBE_TL_VARIABLE,             DomainId::TrafficLight);
-        domains.insert(RESPONSE_SUBSCRIBE_VEHICLE_VARIABLE,        DomainId::Vehicle);
-        domains.insert(RESPONSE_SUBSCRIBE_VEHICLETYPE_VARIABLE,    DomainId::VehicleType);
-
-        Ok(Self {
+        Self::connect_with_retries(
+            host,
+            port,
+            DEFAULT_NUM_RETRIES,
+            Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
+        )
+    }
+
+    /// Connect to a SUMO server, retrying up to `retries` more times,
+    /// `delay` apart, while the connection is refused. Useful when SUMO is
+    /// launched in parallel with the client.
+    ///
+    /// After the last attempt the error is a [`TraciError::Connection`]
+    /// carrying the last OS error.
+    pub fn connect_with_retries(host: &str, port: u16, retries: i32, delay: Duration) -> Result<Self, TraciError> {
+        let socket = TraciSocket::connect_with_retries(host, port, retries, delay)?;
+        let mut client = Self::with_socket(socket);
+        client.get_version()?;
+        Ok(client)
+    }
+
+    /// Launch `sumo_binary` (e.g. `"sumo"` or `"sumo-gui"`) with `args` plus
+    /// `--remote-port` on a free local port, and connect to it.
+    ///
+    /// A refused connection is retried like [`Self::connect`] for as long as
+    /// the process is running. The process is owned by the client:
+    /// [`Self::close`] waits a few seconds for it to exit before killing it,
+    /// and dropping the client kills it.
+    ///
+    /// A binary that cannot be found fails with a [`TraciError::Connection`]
+    /// of kind [`NotFound`](std::io::ErrorKind::NotFound) naming it.
+    pub fn start(sumo_binary: &str, args: &[String]) -> Result<Self, TraciError> {
+        // Let the OS pick a port, then release it for SUMO.
+        let port = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .and_then(|listener| listener.local_addr())
+            .map_err(TraciError::Connection)?
+            .port();
+
+        let mut child = Command::new(sumo_binary)
+            .args(args)
+            .arg("--remote-port")
+            .arg(port.to_string())
+            .spawn()
+            .map_err(|e| {
+                let msg = if e.kind() == std::io::ErrorKind::NotFound {
+                    format!("SUMO binary '{sumo_binary}' not found")
+                } else {
+                    format!("cannot start SUMO binary '{sumo_binary}': {e}")
+                };
+                TraciError::Connection(std::io::Error::new(e.kind(), msg))
+            })?;
+
+        let delay = Duration::from_millis(DEFAULT_RETRY_DELAY_MS);
+        let mut attempt = 0;
+        let socket = loop {
+            match TraciSocket::connect("127.0.0.1", port) {
+                Ok(socket) => break socket,
+                Err(TraciError::Connection(e))
+                    if e.kind() == std::io::ErrorKind::ConnectionRefused && attempt < DEFAULT_NUM_RETRIES =>
+                {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        return Err(TraciError::Connection(std::io::Error::new(
+                            std::io::ErrorKind::ConnectionRefused,
+                            format!("SUMO exited before accepting a connection ({status})"),
+                        )));
+                    }
+                    attempt += 1;
+                    std::thread::sleep(delay);
+                }
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e);
+                }
+            }
+        };
+
+        let mut client = Self::with_socket(socket);
+        client.sumo_process = Some(child);
+        client.get_version()?;
+        Ok(client)
+    }
+
+    fn with_socket(socket: TraciSocket) -> Self {
+        Self {
             socket: Some(socket),
             output: Storage::new(),
             input: Storage::new(),
-            domains,
+            get_response: (0, 0..0),
+            domains: domain_map(),
+            coordinate_converter: None,
+            version: Version::default(),
+            finished: false,
+            person_step_events: false,
+            subscriptions: Vec::new(),
+            sumo_process: None,
             edge:             crate::scopes::edge::EdgeScope::default(),
             gui:              crate::scopes::gui::GuiScope::default(),
             induction_loop:   crate::scopes::induction_loop::InductionLoopScope::default(),
@@ -130,13 +391,47 @@ impl TraciClient {
             traffic_lights:   crate::scopes::traffic_light::TrafficLightScope::default(),
             vehicle:          crate::scopes::vehicle::VehicleScope::default(),
             vehicle_type:     crate::scopes::vehicle_type::VehicleTypeScope::default(),
-        })
+        }
     }
 
     // -----------------------------------------------------------------------
     // Top-level API
     // -----------------------------------------------------------------------
 
+    /// Set the read/write timeout of the connection; `None` blocks forever
+    /// (the default).
+    ///
+    /// A command that runs into the timeout fails with [`TraciError::Timeout`].
+    /// The reply may still arrive later, so the connection should be closed
+    /// rather than reused after a timeout.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), TraciError> {
+        self.socket_mut()?.set_timeout(timeout)
+    }
+
+    /// Run `f` with the socket read/write timeout temporarily set to `timeout`,
+    /// then restore the previous timeout, whether or not `f` succeeded.
+    ///
+    /// Useful for slow commands such as `load` or `save_state` when the rest
+    /// of the session runs with a tight timeout.
+    pub fn with_timeout<T>(
+        &mut self,
+        timeout: Duration,
+        f: impl FnOnce(&mut Self) -> Result<T, TraciError>,
+    ) -> Result<T, TraciError> {
+        let previous = self.socket_mut()?.timeout()?;
+        self.socket_mut()?.set_timeout(Some(timeout))?;
+        let result = f(self);
+        // The closure may have closed the connection; only restore if still open.
+        // An error from `f` takes precedence over a failure to restore.
+        if let Some(sock) = self.socket.as_mut() {
+            let restored = sock.set_timeout(previous);
+            if result.is_ok() {
+                restored?;
+            }
+        }
+        result
+    }
+
     /// Set the client execution order (priority among co-simulating clients).
     pub fn set_order(&mut self, order: i32) -> Result<(), TraciError> {
         let mut msg = Storage::new();
@@ -154,12 +449,22 @@ impl TraciClient {
     /// Returns `Ok(true)` each step while the simulation is running.
     /// Returns `Ok(false)` when SUMO signals end-of-simulation via `CMD_CLOSE`,
     /// at which point the caller should break its loop and call `close()`.
+    /// If SUMO has already shut down and closed the connection cleanly, the
+    /// step fails with [`TraciError::SimulationEnd`]; a reset or broken
+    /// connection is still reported as [`TraciError::Connection`].
     ///
     /// After every step all stale subscription results are cleared and the new
     /// ones received from the server are parsed into the scope caches.
     pub fn simulation_step(&mut self, time: f64) -> Result<bool, TraciError> {
-        self.send_simulation_step(time)?;
-        let mut in_msg = self.socket_mut()?.receive_exact()?;
+        let mut in_msg = match self.send_simulation_step(time).and_then(|_| self.socket_mut()?.receive_exact()) {
+            Ok(msg) => msg,
+            Err(TraciError::Connection(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                self.finished = true;
+                self.close_socket();
+                return Err(TraciError::SimulationEnd);
+            }
+            Err(e) => return Err(e),
+        };
         Self::check_result_state_static(&mut in_msg, CMD_SIMSTEP, false, None)?;
 
         // Clear stale subscription results
@@ -183,23 +488,93 @@ impl TraciClient {
 
         let num_subs = in_msg.read_i32()?;
         for _ in 0..num_subs {
-            let cmd_id = Self::check_command_get_result_static(&mut in_msg, 0, None, true)?;
-            // SUMO signals end-of-simulation by appending CMD_CLOSE (0x7F) as
-            // the last block in the simulation_step response.
-            if cmd_id == CMD_CLOSE {
-                return Ok(false);
-            }
-            // Use the pre-built domains map: if cmd_id is registered there it is
-            // a variable subscription; otherwise treat it as a context subscription.
-            if self.domains.contains_key(&cmd_id) {
-                self.read_variable_subscription(cmd_id, &mut in_msg)?;
-            } else {
-                self.read_context_subscription(cmd_id.wrapping_add(0x50), &mut in_msg)?;
+            match read_subscription_response(&self.domains, &mut in_msg)? {
+                // SUMO signals end-of-simulation by appending CMD_CLOSE (0x7F)
+                // as the last block in the simulation_step response.
+                SubscriptionResponse::Close => {
+                    self.finished = true;
+                    return Ok(false);
+                }
+                SubscriptionResponse::Variable { domain, object_id, results } => {
+                    self.subscription_cache(domain).insert(object_id, results);
+                }
+                SubscriptionResponse::Context { domain: Some(domain), context_id, results } => {
+                    self.context_subscription_cache(domain).insert(context_id, results);
+                }
+                SubscriptionResponse::Context { domain: None, .. } => {}
             }
         }
         Ok(true)
     }
 
+    /// Run only the move phase of the next step (up to `time` if > 0), e.g. to
+    /// exchange vehicle positions with a co-simulator before the step is
+    /// completed.
+    ///
+    /// The step is not finished: call [`Self::simulation_step`] afterwards to
+    /// complete it. Subscription results are left untouched until then.
+    pub fn execute_move(&mut self, time: f64) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        msg.write_u8(1 + 1 + 8);
+        msg.write_u8(CMD_EXECUTEMOVE);
+        msg.write_f64(time);
+        self.socket_mut()?.send_exact(&msg)?;
+        let mut in_msg = self.socket_mut()?.receive_exact()?;
+        Self::check_result_state_static(&mut in_msg, CMD_EXECUTEMOVE, false, None)
+    }
+
+    /// Advance the simulation like [`Self::simulation_step`], then fetch the
+    /// vehicles that departed, arrived, started or ended a teleport, or
+    /// collided during the step in a single batched request.
+    ///
+    /// Departed and arrived persons are included in the same request when
+    /// enabled with [`Self::set_person_step_events`].
+    ///
+    /// Returns [`TraciError::SimulationEnd`] when SUMO signals end-of-simulation.
+    pub fn simulation_step_with_events(&mut self, time: f64) -> Result<StepEvents, TraciError> {
+        if !self.simulation_step(time)? {
+            return Err(TraciError::SimulationEnd);
+        }
+        let mut requests = vec![
+            (CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_IDS, ""),
+            (CMD_GET_SIM_VARIABLE, VAR_ARRIVED_VEHICLES_IDS, ""),
+            (CMD_GET_SIM_VARIABLE, VAR_TELEPORT_STARTING_VEHICLES_IDS, ""),
+            (CMD_GET_SIM_VARIABLE, VAR_TELEPORT_ENDING_VEHICLES_IDS, ""),
+            (CMD_GET_SIM_VARIABLE, VAR_COLLIDING_VEHICLES_IDS, ""),
+        ];
+        if self.person_step_events {
+            requests.push((CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_IDS, ""));
+            requests.push((CMD_GET_SIM_VARIABLE, VAR_ARRIVED_PERSONS_IDS, ""));
+        }
+        let mut lists = Vec::with_capacity(requests.len());
+        for value in self.batch_get(&requests)? {
+            match value {
+                TraciValue::StringList(ids) => lists.push(ids),
+                other => return Err(TraciError::Protocol(format!("Expected a string list but got {other:?}"))),
+            }
+        }
+        // batch_get returns one value per request; the person lists are
+        // missing when person events are disabled.
+        let mut lists = lists.into_iter();
+        let mut next = || lists.next().unwrap_or_default();
+        Ok(StepEvents {
+            departed: next(),
+            arrived: next(),
+            teleport_started: next(),
+            teleport_ended: next(),
+            collisions: next(),
+            departed_persons: next(),
+            arrived_persons: next(),
+        })
+    }
+
+    /// Enable or disable fetching departed and arrived persons in
+    /// [`Self::simulation_step_with_events`]. Disabled by default to avoid the
+    /// extra traffic in simulations without pedestrians.
+    pub fn set_person_step_events(&mut self, enabled: bool) {
+        self.person_step_events = enabled;
+    }
+
     /// Tell SUMO to load a new simulation with the given command-line arguments.
     pub fn load(&mut self, args: &[String]) -> Result<(), TraciError> {
         let num_chars: usize = args.iter().map(|s| s.len()).sum();
@@ -210,14 +585,28 @@ impl TraciClient {
         content.write_i32((total + 4) as i32);
         content.write_u8(CMD_LOAD);
         content.write_u8(TYPE_STRINGLIST);
-        content.write_string_list(&args.iter().cloned().collect::<Vec<_>>());
+        content.write_string_list(args);
         self.socket_mut()?.send_exact(&content)?;
         let mut in_msg = self.socket_mut()?.receive_exact()?;
         Self::check_result_state_static(&mut in_msg, CMD_LOAD, false, None)?;
+        self.finished = false;
+        self.coordinate_converter = None;
         Ok(())
     }
 
-    /// Return the (TraCI version number, SUMO version string) pair.
+    /// Return the [`CoordinateConverter`] for the loaded network, fetching the
+    /// projection parameters from SUMO on first use.
+    pub fn coordinate_converter(&mut self) -> Result<CoordinateConverter, TraciError> {
+        if let Some(converter) = self.coordinate_converter {
+            return Ok(converter);
+        }
+        let converter = CoordinateConverter::fetch(self)?;
+        self.coordinate_converter = Some(converter);
+        Ok(converter)
+    }
+
+    /// Query the (TraCI version number, SUMO version string) pair from the
+    /// server and refresh the cached [`Self::version`].
     pub fn get_version(&mut self) -> Result<(i32, String), TraciError> {
         let mut content = Storage::new();
         content.write_u8(2);
@@ -229,15 +618,72 @@ impl TraciClient {
         in_msg.read_u8()?; // CMD_GETVERSION echo
         let version = in_msg.read_i32()?;
         let sumo_version = in_msg.read_string()?;
+        self.version = Version { api: version, sumo: sumo_version.clone() };
         Ok((version, sumo_version))
     }
 
+    /// Return the server versions queried when connecting, without a round
+    /// trip. Useful to enable variables only newer API versions support:
+    ///
+    /// ```no_run
+    /// # let mut client = traci_rs::TraciClient::connect("localhost", 8813)?;
+    /// if client.version().api >= 21 {
+    ///     // ...
+    /// }
+    /// # Ok::<(), traci_rs::TraciError>(())
+    /// ```
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Return `true` once the simulation is over: SUMO has signalled
+    /// end-of-simulation, closed the connection, or has no vehicles or
+    /// persons left to simulate (`get_min_expected_number() == 0`).
+    ///
+    /// The server is only queried while no end signal has been seen yet.
+    pub fn is_finished(&mut self) -> Result<bool, TraciError> {
+        if !self.finished {
+            self.create_command(CMD_GET_SIM_VARIABLE, VAR_MIN_EXPECTED_VEHICLES, "", None);
+            self.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            self.finished = self.read_int_from_input()? == 0;
+        }
+        Ok(self.finished)
+    }
+
     /// Send the close command and shut down the socket.
+    /// Recorded subscriptions are discarded; a SUMO process launched by
+    /// [`Self::start`] is given a few seconds to exit and killed otherwise.
+    ///
+    /// Calling `close` on a client that is already closed is a no-op, and a
+    /// server that has already hung up is not an error. The socket is released
+    /// even if the close handshake fails, so a later `close` or `Drop` does no
+    /// further I/O.
     pub fn close(&mut self) -> Result<(), TraciError> {
-        self.send_close()?;
-        let mut in_msg = self.socket_mut()?.receive_exact()?;
-        Self::check_result_state_static(&mut in_msg, CMD_CLOSE, false, None)?;
+        if self.socket.is_none() {
+            return Ok(());
+        }
+        let result = match self.close_handshake() {
+            Err(TraciError::Connection(e)) if is_closed_by_peer(&e) => Ok(()),
+            other => other,
+        };
+        self.close_socket();
+        self.subscriptions.clear();
+        if let Some(mut child) = self.sumo_process.take() {
+            wait_or_kill(&mut child, SUMO_EXIT_TIMEOUT).map_err(TraciError::Connection)?;
+        }
+        result
+    }
+
+    /// Drop the current connection, if any, and connect to a SUMO server again.
+    ///
+    /// Recorded subscriptions are kept; call [`Self::replay_subscriptions`]
+    /// afterwards to re-issue them on the new server.
+    pub fn reconnect(&mut self, host: &str, port: u16) -> Result<(), TraciError> {
         self.close_socket();
+        self.socket = Some(TraciSocket::connect(host, port)?);
+        self.finished = false;
+        self.coordinate_converter = None;
+        self.get_version()?;
         Ok(())
     }
 
@@ -363,12 +809,11 @@ impl TraciClient {
         type_id: &str,
     ) -> Result<(), TraciError> {
         use crate::constants::{
-            CMD_SET_VEHICLE_VARIABLE, ADD_FULL, TYPE_COMPOUND, TYPE_STRING, TYPE_INTEGER,
+            CMD_SET_VEHICLE_VARIABLE, ADD_FULL, TYPE_STRING, TYPE_INTEGER,
         };
         use crate::storage::Storage;
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(14);
+        add.begin_compound(14);
         add.write_u8(TYPE_STRING); add.write_string(route_id);   // routeID
         add.write_u8(TYPE_STRING); add.write_string(type_id);    // typeID
         add.write_u8(TYPE_STRING); add.write_string("now");      // depart
@@ -393,6 +838,9 @@ impl TraciClient {
     // -----------------------------------------------------------------------
 
     /// Subscribe an object to receive variable updates every simulation step.
+    ///
+    /// The subscription is recorded so that [`Self::replay_subscriptions`]
+    /// can restore it; an empty `vars` list unsubscribes and drops the record.
     pub fn subscribe_object_variable(
         &mut self,
         dom_id: u8,
@@ -401,26 +849,32 @@ impl TraciClient {
         end_time: f64,
         vars: &[u8],
     ) -> Result<(), TraciError> {
-        let mut msg = Storage::new();
-        let var_no = vars.len();
-        // payload = cmd(1) + begin(8) + end(8) + string(4+len) + var_count(1) + vars(var_no)
-        let payload = 1 + 8 + 8 + 4 + obj_id.len() + 1 + var_no;
-        if payload + 1 <= 255 {
-            // short form: 1-byte length field includes itself
-            msg.write_u8((payload + 1) as u8);
-        } else {
-            // long form: 0x00 sentinel + 4-byte length; length includes the 5-byte header
-            msg.write_u8(0);
-            msg.write_i32((payload + 5) as i32);
-        }
-        msg.write_u8(dom_id);
-        msg.write_f64(begin_time);
-        msg.write_f64(end_time);
-        msg.write_string(obj_id);
-        msg.write_u8(var_no as u8);
-        for &v in vars {
-            msg.write_u8(v);
+        self.send_subscribe_variable(dom_id, obj_id, begin_time, end_time, vars)?;
+        self.subscriptions.retain(|r| {
+            !matches!(r, SubscriptionRecord::Variable { dom_id: d, obj_id: o, .. } if *d == dom_id && o == obj_id)
+        });
+        if !vars.is_empty() {
+            self.subscriptions.push(SubscriptionRecord::Variable {
+                dom_id,
+                obj_id: obj_id.to_owned(),
+                begin: begin_time,
+                end: end_time,
+                vars: vars.to_vec(),
+            });
         }
+        Ok(())
+    }
+
+    fn send_subscribe_variable(
+        &mut self,
+        dom_id: u8,
+        obj_id: &str,
+        begin_time: f64,
+        end_time: f64,
+        vars: &[u8],
+    ) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        Self::write_subscribe_command(&mut msg, dom_id, obj_id, begin_time, end_time, None, vars);
         self.socket_mut()?.send_exact(&msg)?;
 
         // Consume the STATUS_RESPONSE acknowledgement SUMO sends immediately.
@@ -433,6 +887,11 @@ impl TraciClient {
     }
 
     /// Subscribe a context (range around object) to receive variable updates.
+    ///
+    /// The subscription is recorded so that [`Self::replay_subscriptions`]
+    /// can restore it; an empty `vars` list unsubscribes and drops the record
+    /// together with its filters.
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_object_context(
         &mut self,
         dom_id: u8,
@@ -443,26 +902,82 @@ impl TraciClient {
         range: f64,
         vars: &[u8],
     ) -> Result<(), TraciError> {
-        let mut msg = Storage::new();
-        let var_no = vars.len();
-        // payload = cmd(1) + begin(8) + end(8) + string(4+len) + domain(1) + range(8) + var_count(1) + vars(var_no)
-        let payload = 1 + 8 + 8 + 4 + obj_id.len() + 1 + 8 + 1 + var_no;
-        if payload + 1 <= 255 {
-            msg.write_u8((payload + 1) as u8);
-        } else {
-            msg.write_u8(0);
-            msg.write_i32((payload + 5) as i32);
-        }
-        msg.write_u8(dom_id);
-        msg.write_f64(begin_time);
-        msg.write_f64(end_time);
-        msg.write_string(obj_id);
-        msg.write_u8(domain);
-        msg.write_f64(range);
-        msg.write_u8(var_no as u8);
-        for &v in vars {
-            msg.write_u8(v);
+        self.send_subscribe_context(dom_id, obj_id, begin_time, end_time, domain, range, vars)?;
+        self.forget_context_subscription(dom_id, obj_id, domain);
+        if !vars.is_empty() {
+            self.subscriptions.push(SubscriptionRecord::Context {
+                dom_id,
+                obj_id: obj_id.to_owned(),
+                begin: begin_time,
+                end: end_time,
+                domain,
+                range,
+                vars: vars.to_vec(),
+            });
         }
+        Ok(())
+    }
+
+    /// Add a filter to the last context subscription and record it for replay.
+    pub(crate) fn add_subscription_filter(&mut self, filter_type: u8, add: Option<&Storage>) -> Result<(), TraciError> {
+        self.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, filter_type, add);
+        self.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
+        self.subscriptions.push(SubscriptionRecord::Filter { filter_type, add: add.cloned() });
+        Ok(())
+    }
+
+    /// Re-issue every recorded subscription and filter, in the original order.
+    ///
+    /// Server-side subscriptions are lost when SUMO restarts or reloads; call
+    /// this after [`Self::reconnect`] or [`Self::load`] to restore them.
+    pub fn replay_subscriptions(&mut self) -> Result<(), TraciError> {
+        let records = self.subscriptions.clone();
+        for record in &records {
+            match record {
+                SubscriptionRecord::Variable { dom_id, obj_id, begin, end, vars } => {
+                    self.send_subscribe_variable(*dom_id, obj_id, *begin, *end, vars)?;
+                }
+                SubscriptionRecord::Context { dom_id, obj_id, begin, end, domain, range, vars } => {
+                    self.send_subscribe_context(*dom_id, obj_id, *begin, *end, *domain, *range, vars)?;
+                }
+                SubscriptionRecord::Filter { filter_type, add } => {
+                    self.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, *filter_type, add.as_ref());
+                    self.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the recorded context subscription matching the given key, along
+    /// with the filters that were added to it.
+    fn forget_context_subscription(&mut self, dom_id: u8, obj_id: &str, domain: u8) {
+        let mut dropping = false;
+        self.subscriptions.retain(|r| {
+            match r {
+                SubscriptionRecord::Context { dom_id: d, obj_id: o, domain: c, .. } => {
+                    dropping = *d == dom_id && o == obj_id && *c == domain;
+                }
+                SubscriptionRecord::Variable { .. } => dropping = false,
+                SubscriptionRecord::Filter { .. } => {}
+            }
+            !dropping
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn send_subscribe_context(
+        &mut self,
+        dom_id: u8,
+        obj_id: &str,
+        begin_time: f64,
+        end_time: f64,
+        domain: u8,
+        range: f64,
+        vars: &[u8],
+    ) -> Result<(), TraciError> {
+        let mut msg = Storage::new();
+        Self::write_subscribe_command(&mut msg, dom_id, obj_id, begin_time, end_time, Some((domain, range)), vars);
         self.socket_mut()?.send_exact(&msg)?;
 
         // Consume the STATUS_RESPONSE acknowledgement SUMO sends immediately.
@@ -472,6 +987,112 @@ impl TraciClient {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // Batched commands
+    // -----------------------------------------------------------------------
+
+    /// Send several GET commands in a single message and decode every result.
+    ///
+    /// Each request is `(cmd_id, var_id, obj_id)`, e.g.
+    /// `(CMD_GET_EDGE_VARIABLE, VAR_CO2EMISSION, "e0")`. SUMO answers all
+    /// commands in one response, so this costs one round trip regardless of
+    /// how many variables are queried. Values are returned in request order.
+    pub fn batch_get(&mut self, requests: &[(u8, u8, &str)]) -> Result<Vec<TraciValue>, TraciError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut out = Storage::new();
+        for &(cmd_id, var_id, obj_id) in requests {
+            Self::write_command(&mut out, cmd_id, var_id, obj_id, None);
+        }
+        let sock = self.socket_mut()?;
+        sock.send_exact(&out)?;
+        let mut in_msg = sock.receive_exact()?;
+        let mut values = Vec::with_capacity(requests.len());
+        for &(cmd_id, _, _) in requests {
+            Self::check_result_state_static(&mut in_msg, cmd_id, false, None)?;
+            let (_, span) = Self::check_command_get_result_static(&mut in_msg, cmd_id, None, false)?;
+            in_msg.read_u8()?; // variable id
+            in_msg.read_string()?; // object id
+            let type_id = in_msg.read_u8()?;
+            values.push(Self::read_typed_value(&mut in_msg, type_id)?);
+            Self::check_response_end(&in_msg, cmd_id, &span)?;
+        }
+        Ok(values)
+    }
+
+    /// Send several SET commands in a single message and validate every
+    /// status response in order.
+    ///
+    /// Each request is `(cmd_id, var_id, obj_id, value)` where `value` holds
+    /// the type-tagged payload exactly as a scope setter would build it.
+    ///
+    /// SUMO executes every command even if an earlier one fails, so the
+    /// result of each command is returned in request order. The outer error
+    /// is reserved for connection and protocol failures that abort the batch.
+    pub fn batch_set(&mut self, requests: &[(u8, u8, &str, Storage)]) -> Result<Vec<Result<(), TraciError>>, TraciError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut out = Storage::new();
+        for (cmd_id, var_id, obj_id, add) in requests {
+            Self::write_command(&mut out, *cmd_id, *var_id, obj_id, Some(add));
+        }
+        let sock = self.socket_mut()?;
+        sock.send_exact(&out)?;
+        let mut in_msg = sock.receive_exact()?;
+        let mut results = Vec::with_capacity(requests.len());
+        for (cmd_id, _, obj_id, _) in requests {
+            match Self::check_result_state_static(&mut in_msg, *cmd_id, false, None) {
+                Ok(()) => results.push(Ok(())),
+                Err(e @ (TraciError::SimulationError { .. } | TraciError::NotImplemented(_))) => {
+                    results.push(Err(e.with_context(format!("batch_set('{obj_id}')"))))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Send every command of `batch` in a single message and decode the
+    /// responses in order.
+    ///
+    /// Each entry is `Ok(Some(value))` for a GET, `Ok(None)` for a SET, or
+    /// the error SUMO reported for that command; SUMO executes the remaining
+    /// commands regardless. The outer error is reserved for connection and
+    /// protocol failures that abort the batch.
+    pub fn execute_batch(
+        &mut self,
+        batch: &CommandBatch,
+    ) -> Result<Vec<Result<Option<TraciValue>, TraciError>>, TraciError> {
+        if batch.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sock = self.socket_mut()?;
+        sock.send_exact(&batch.output)?;
+        let mut in_msg = sock.receive_exact()?;
+        let mut results = Vec::with_capacity(batch.len());
+        for (cmd_id, obj_id, is_get) in &batch.commands {
+            match Self::check_result_state_static(&mut in_msg, *cmd_id, false, None) {
+                Ok(()) if *is_get => {
+                    let (_, span) = Self::check_command_get_result_static(&mut in_msg, *cmd_id, None, false)?;
+                    in_msg.read_u8()?; // variable id
+                    in_msg.read_string()?; // object id
+                    let type_id = in_msg.read_u8()?;
+                    let value = Self::read_typed_value(&mut in_msg, type_id)?;
+                    Self::check_response_end(&in_msg, *cmd_id, &span)?;
+                    results.push(Ok(Some(value)));
+                }
+                Ok(()) => results.push(Ok(None)),
+                Err(e @ (TraciError::SimulationError { .. } | TraciError::NotImplemented(_))) => {
+                    results.push(Err(e.with_context(format!("execute_batch('{obj_id}')"))))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(results)
+    }
+
     // -----------------------------------------------------------------------
     // Internal protocol helpers (pub(crate) so scopes can use them)
     // -----------------------------------------------------------------------
@@ -485,19 +1106,62 @@ impl TraciClient {
         add: Option<&Storage>,
     ) {
         self.output.reset();
+        Self::write_command(&mut self.output, cmd_id, var_id, obj_id, add);
+    }
+
+    /// Append one GET or SET command to `out` without resetting it, so several
+    /// commands can share a single outgoing message.
+    pub(crate) fn write_command(out: &mut Storage, cmd_id: u8, var_id: u8, obj_id: &str, add: Option<&Storage>) {
         let extra = add.map_or(0, |s| s.len());
         let length = 1 + 1 + 1 + 4 + obj_id.len() + extra;
         if length <= 255 {
-            self.output.write_u8(length as u8);
+            out.write_u8(length as u8);
         } else {
-            self.output.write_u8(0);
-            self.output.write_i32((length + 4) as i32);
+            out.write_u8(0);
+            out.write_i32((length + 4) as i32);
         }
-        self.output.write_u8(cmd_id);
-        self.output.write_u8(var_id);
-        self.output.write_string(obj_id);
+        out.write_u8(cmd_id);
+        out.write_u8(var_id);
+        out.write_string(obj_id);
         if let Some(s) = add {
-            self.output.write_packet(s.as_bytes());
+            out.write_packet(s.as_bytes());
+        }
+    }
+
+    /// Append a variable subscription command to `out`, or a context
+    /// subscription if `context` holds the `(domain, range)` to watch.
+    pub(crate) fn write_subscribe_command(
+        out: &mut Storage,
+        dom_id: u8,
+        obj_id: &str,
+        begin_time: f64,
+        end_time: f64,
+        context: Option<(u8, f64)>,
+        vars: &[u8],
+    ) {
+        let var_no = vars.len();
+        // payload = cmd(1) + begin(8) + end(8) + string(4+len) + [domain(1) + range(8)] + var_count(1) + vars(var_no)
+        let context_len = if context.is_some() { 1 + 8 } else { 0 };
+        let payload = 1 + 8 + 8 + 4 + obj_id.len() + context_len + 1 + var_no;
+        if payload < 255 {
+            // short form: 1-byte length field includes itself
+            out.write_u8((payload + 1) as u8);
+        } else {
+            // long form: 0x00 sentinel + 4-byte length; length includes the 5-byte header
+            out.write_u8(0);
+            out.write_i32((payload + 5) as i32);
+        }
+        out.write_u8(dom_id);
+        out.write_f64(begin_time);
+        out.write_f64(end_time);
+        out.write_string(obj_id);
+        if let Some((domain, range)) = context {
+            out.write_u8(domain);
+            out.write_f64(range);
+        }
+        out.write_u8(var_no as u8);
+        for &v in vars {
+            out.write_u8(v);
         }
     }
 
@@ -532,13 +1196,29 @@ impl TraciClient {
             sock.send_exact(&out)?;
             self.input = sock.receive_exact()?;
             Self::check_result_state_static(&mut self.input, command, false, None)?;
-            Self::check_command_get_result_static(&mut self.input, command, expected_type, false)?;
+            let (_, span) = Self::check_command_get_result_static(&mut self.input, command, expected_type, false)?;
+            // The response is the last command of the message.
+            if span.end != self.input.len() {
+                return Err(TraciError::Protocol(format!(
+                    "Response to command 0x{command:02x} declares {} bytes but {} were received",
+                    span.len(),
+                    self.input.len() - span.start
+                )));
+            }
+            self.get_response = (command, span);
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    /// Check that the value of the response read by the last [`Self::process_get`]
+    /// has been read up to the end its length declares.
+    pub(crate) fn finish_get(&self) -> Result<(), TraciError> {
+        let (command, span) = &self.get_response;
+        Self::check_response_end(&self.input, *command, span)
+    }
+
     /// Send the SET command built in `self.output` and validate the response.
     pub(crate) fn process_set(&mut self, command: u8) -> Result<bool, TraciError> {
         let out_bytes = self.output.as_bytes().to_vec();
@@ -560,7 +1240,7 @@ impl TraciClient {
         ignore_command_id: bool,
         acknowledgement: Option<&mut String>,
     ) -> Result<(), TraciError> {
-        let cmd_len  = in_msg.read_u8()? as usize;
+        let (cmd_start, cmd_len) = Self::read_command_length(in_msg, command)?;
         let cmd_id   = in_msg.read_u8()?;
         if !ignore_command_id && cmd_id != command {
             return Err(TraciError::Protocol(format!(
@@ -569,8 +1249,13 @@ impl TraciClient {
         }
         let result_type = in_msg.read_u8()?;
         let msg = in_msg.read_string()?;
-        // cmd_len includes the length byte itself, so consumed = 1 (len) + 1 (id) + 1 (type) + 4+msg.len()
-        let _ = cmd_len; // length already validated by Storage bounds checking
+        // cmd_len includes the length field itself
+        let consumed = in_msg.position() - cmd_start;
+        if consumed != cmd_len {
+            return Err(TraciError::Protocol(format!(
+                "Status of command 0x{command:02x} declares {cmd_len} bytes but {consumed} were read"
+            )));
+        }
         match result_type {
             RTYPE_OK => {
                 if let Some(ack) = acknowledgement {
@@ -581,30 +1266,61 @@ impl TraciClient {
             RTYPE_NOTIMPLEMENTED => Err(TraciError::NotImplemented(format!(
                 "Command 0x{command:02x} not implemented: {msg}"
             ))),
-            RTYPE_ERR => Err(TraciError::SimulationError(format!(
-                "Command 0x{command:02x} failed: {msg}"
-            ))),
+            RTYPE_ERR => Err(TraciError::SimulationError { command, message: msg, context: String::new() }),
             other => Err(TraciError::Protocol(format!(
                 "Unknown result type 0x{other:02x} for command 0x{command:02x}: {msg}"
             ))),
         }
     }
 
+    /// Read the length field of a command (one byte, or a zero byte followed
+    /// by an i32 for long commands) and check that the declared length fits
+    /// in the message. Returns the command's start position and its length,
+    /// which includes the length field itself.
+    fn read_command_length(in_msg: &mut Storage, command: u8) -> Result<(usize, usize), TraciError> {
+        let start = in_msg.position();
+        let length = match in_msg.read_u8()? {
+            0 => {
+                let extended = in_msg.read_i32()?;
+                usize::try_from(extended).map_err(|_| {
+                    TraciError::Protocol(format!("Response to command 0x{command:02x} declares length {extended}"))
+                })?
+            }
+            short => short as usize,
+        };
+        let available = in_msg.len() - start;
+        if length > available {
+            return Err(TraciError::Protocol(format!(
+                "Response to command 0x{command:02x} declares {length} bytes but only {available} were received"
+            )));
+        }
+        Ok((start, length))
+    }
+
+    /// Check that a response occupying `span` of `in_msg` has been read
+    /// exactly up to its end.
+    pub(crate) fn check_response_end(in_msg: &Storage, command: u8, span: &Range<usize>) -> Result<(), TraciError> {
+        let consumed = in_msg.position() - span.start;
+        if consumed != span.len() {
+            return Err(TraciError::Protocol(format!(
+                "Response to command 0x{command:02x} declares {} bytes but {consumed} were read",
+                span.len()
+            )));
+        }
+        Ok(())
+    }
+
     /// Validate and advance past a GET-variable response header.
-    /// Returns the actual response command id.
+    /// Returns the actual response command id and the byte range the
+    /// response occupies in `in_msg`, to be checked with
+    /// [`Self::check_response_end`] once the value has been read.
     pub(crate) fn check_command_get_result_static(
         in_msg: &mut Storage,
         command: u8,
         expected_type: Option<u8>,
         ignore_command_id: bool,
-    ) -> Result<u8, TraciError> {
-        let length = in_msg.read_u8()?;
-        let length = if length == 0 {
-            in_msg.read_i32()? as usize
-        } else {
-            length as usize
-        };
-        let _ = length;
+    ) -> Result<(u8, Range<usize>), TraciError> {
+        let (start, length) = Self::read_command_length(in_msg, command)?;
         let cmd_id = in_msg.read_u8()?;
         if !ignore_command_id && cmd_id != command.wrapping_add(0x10) {
             return Err(TraciError::Protocol(format!(
@@ -622,13 +1338,19 @@ impl TraciClient {
                 )));
             }
         }
-        Ok(cmd_id)
+        Ok((cmd_id, start..start + length))
     }
 
     // -----------------------------------------------------------------------
     // Reading typed values out of `self.input` (used by scope helper methods)
     // -----------------------------------------------------------------------
 
+    /// Direct access to the response buffer, for decoders shared with
+    /// subscription parsing.
+    pub(crate) fn input_mut(&mut self) -> &mut Storage {
+        &mut self.input
+    }
+
     pub(crate) fn read_double_from_input(&mut self) -> Result<f64, TraciError> {
         self.input.read_f64()
     }
@@ -688,89 +1410,102 @@ impl TraciClient {
     // Subscription parsing
     // -----------------------------------------------------------------------
 
-    fn read_variable_subscription(
-        &mut self,
-        cmd_id: u8,
-        in_msg: &mut Storage,
-    ) -> Result<(), TraciError> {
-        let object_id = in_msg.read_string()?;
-        let var_count = in_msg.read_u8()? as usize;
-        let results = Self::read_variables_static(in_msg, var_count)?;
+    /// Empty the variable and context subscription results of every scope,
+    /// e.g. after loading a saved state made them stale.
+    pub(crate) fn clear_subscription_results(&mut self) {
+        self.edge.subscription_results.clear();
+        self.edge.context_subscription_results.clear();
+        self.gui.subscription_results.clear();
+        self.gui.context_subscription_results.clear();
+        self.induction_loop.subscription_results.clear();
+        self.induction_loop.context_subscription_results.clear();
+        self.junction.subscription_results.clear();
+        self.junction.context_subscription_results.clear();
+        self.lane.subscription_results.clear();
+        self.lane.context_subscription_results.clear();
+        self.lane_area.subscription_results.clear();
+        self.lane_area.context_subscription_results.clear();
+        self.multi_entry_exit.subscription_results.clear();
+        self.multi_entry_exit.context_subscription_results.clear();
+        self.person.subscription_results.clear();
+        self.person.context_subscription_results.clear();
+        self.poi.subscription_results.clear();
+        self.poi.context_subscription_results.clear();
+        self.polygon.subscription_results.clear();
+        self.polygon.context_subscription_results.clear();
+        self.rerouter.subscription_results.clear();
+        self.rerouter.context_subscription_results.clear();
+        self.route.subscription_results.clear();
+        self.route.context_subscription_results.clear();
+        self.route_probe.subscription_results.clear();
+        self.route_probe.context_subscription_results.clear();
+        self.simulation.subscription_results.clear();
+        self.simulation.context_subscription_results.clear();
+        self.traffic_lights.subscription_results.clear();
+        self.traffic_lights.context_subscription_results.clear();
+        self.vehicle.subscription_results.clear();
+        self.vehicle.context_subscription_results.clear();
+        self.vehicle_type.subscription_results.clear();
+        self.vehicle_type.context_subscription_results.clear();
+    }
 
-        // Route to the correct scope cache
+    /// The variable subscription results of the scope of `domain`.
+    pub(crate) fn subscription_cache(&mut self, domain: DomainId) -> &mut SubscriptionResults {
         use DomainId::*;
-        if let Some(domain) = self.domains.get(&cmd_id).copied() {
-            let cache: &mut SubscriptionResults = match domain {
-                Edge           => &mut self.edge.subscription_results,
-                Gui            => &mut self.gui.subscription_results,
-                InductionLoop  => &mut self.induction_loop.subscription_results,
-                Junction       => &mut self.junction.subscription_results,
-                Lane           => &mut self.lane.subscription_results,
-                LaneArea       => &mut self.lane_area.subscription_results,
-                MultiEntryExit => &mut self.multi_entry_exit.subscription_results,
-                Person         => &mut self.person.subscription_results,
-                Poi            => &mut self.poi.subscription_results,
-                Polygon        => &mut self.polygon.subscription_results,
-                Rerouter       => &mut self.rerouter.subscription_results,
-                Route          => &mut self.route.subscription_results,
-                RouteProbe     => &mut self.route_probe.subscription_results,
-                Simulation     => &mut self.simulation.subscription_results,
-                TrafficLight   => &mut self.traffic_lights.subscription_results,
-                Vehicle        => &mut self.vehicle.subscription_results,
-                VehicleType    => &mut self.vehicle_type.subscription_results,
-            };
-            cache.insert(object_id, results);
+        match domain {
+            Edge           => &mut self.edge.subscription_results,
+            Gui            => &mut self.gui.subscription_results,
+            InductionLoop  => &mut self.induction_loop.subscription_results,
+            Junction       => &mut self.junction.subscription_results,
+            Lane           => &mut self.lane.subscription_results,
+            LaneArea       => &mut self.lane_area.subscription_results,
+            MultiEntryExit => &mut self.multi_entry_exit.subscription_results,
+            Person         => &mut self.person.subscription_results,
+            Poi            => &mut self.poi.subscription_results,
+            Polygon        => &mut self.polygon.subscription_results,
+            Rerouter       => &mut self.rerouter.subscription_results,
+            Route          => &mut self.route.subscription_results,
+            RouteProbe     => &mut self.route_probe.subscription_results,
+            Simulation     => &mut self.simulation.subscription_results,
+            TrafficLight   => &mut self.traffic_lights.subscription_results,
+            Vehicle        => &mut self.vehicle.subscription_results,
+            VehicleType    => &mut self.vehicle_type.subscription_results,
         }
-        Ok(())
     }
 
-    fn read_context_subscription(
-        &mut self,
-        cmd_id: u8,
-        in_msg: &mut Storage,
-    ) -> Result<(), TraciError> {
-        let context_id = in_msg.read_string()?;
-        in_msg.read_u8()?; // context domain
-        let var_count  = in_msg.read_u8()? as usize;
-        let num_objects = in_msg.read_i32()?;
-        let mut ctx_results: SubscriptionResults = HashMap::new();
-        for _ in 0..num_objects {
-            let object_id = in_msg.read_string()?;
-            let results   = Self::read_variables_static(in_msg, var_count)?;
-            ctx_results.insert(object_id, results);
-        }
-
+    /// The context subscription results of the scope of `domain`.
+    pub(crate) fn context_subscription_cache(&mut self, domain: DomainId) -> &mut ContextSubscriptionResults {
         use DomainId::*;
-        if let Some(domain) = self.domains.get(&cmd_id).copied() {
-            let cache: &mut ContextSubscriptionResults = match domain {
-                Edge           => &mut self.edge.context_subscription_results,
-                Gui            => &mut self.gui.context_subscription_results,
-                InductionLoop  => &mut self.induction_loop.context_subscription_results,
-                Junction       => &mut self.junction.context_subscription_results,
-                Lane           => &mut self.lane.context_subscription_results,
-                LaneArea       => &mut self.lane_area.context_subscription_results,
-                MultiEntryExit => &mut self.multi_entry_exit.context_subscription_results,
-                Person         => &mut self.person.context_subscription_results,
-                Poi            => &mut self.poi.context_subscription_results,
-                Polygon        => &mut self.polygon.context_subscription_results,
-                Rerouter       => &mut self.rerouter.context_subscription_results,
-                Route          => &mut self.route.context_subscription_results,
-                RouteProbe     => &mut self.route_probe.context_subscription_results,
-                Simulation     => &mut self.simulation.context_subscription_results,
-                TrafficLight   => &mut self.traffic_lights.context_subscription_results,
-                Vehicle        => &mut self.vehicle.context_subscription_results,
-                VehicleType    => &mut self.vehicle_type.context_subscription_results,
-            };
-            cache.insert(context_id, ctx_results);
+        match domain {
+            Edge           => &mut self.edge.context_subscription_results,
+            Gui            => &mut self.gui.context_subscription_results,
+            InductionLoop  => &mut self.induction_loop.context_subscription_results,
+            Junction       => &mut self.junction.context_subscription_results,
+            Lane           => &mut self.lane.context_subscription_results,
+            LaneArea       => &mut self.lane_area.context_subscription_results,
+            MultiEntryExit => &mut self.multi_entry_exit.context_subscription_results,
+            Person         => &mut self.person.context_subscription_results,
+            Poi            => &mut self.poi.context_subscription_results,
+            Polygon        => &mut self.polygon.context_subscription_results,
+            Rerouter       => &mut self.rerouter.context_subscription_results,
+            Route          => &mut self.route.context_subscription_results,
+            RouteProbe     => &mut self.route_probe.context_subscription_results,
+            Simulation     => &mut self.simulation.context_subscription_results,
+            TrafficLight   => &mut self.traffic_lights.context_subscription_results,
+            Vehicle        => &mut self.vehicle.context_subscription_results,
+            VehicleType    => &mut self.vehicle_type.context_subscription_results,
         }
-        Ok(())
     }
 
     /// Parse `var_count` typed variable responses from `in_msg`.
     /// Mirrors `TraCIAPI::readVariables` in the C++ implementation.
-    fn read_variables_static(
+    ///
+    /// `domain` selects dedicated decoders for compound variables the generic
+    /// decoder cannot represent, such as traffic-light logics and links.
+    pub(crate) fn read_variables_static(
         in_msg: &mut Storage,
         var_count: usize,
+        domain: Option<DomainId>,
     ) -> Result<TraciResults, TraciError> {
         let mut results = TraciResults::new();
         for _ in 0..var_count {
@@ -782,7 +1517,18 @@ impl TraciClient {
                     "Subscription variable 0x{var_id:02x} returned status 0x{status:02x}"
                 )));
             }
-            let value = Self::read_typed_value(in_msg, type_id)?;
+            let value = match (domain, var_id, type_id) {
+                (Some(DomainId::TrafficLight), TL_COMPLETE_DEFINITION_RYG, TYPE_COMPOUND) => {
+                    TraciValue::LogicList(crate::scopes::traffic_light::read_logic_list(in_msg)?)
+                }
+                (Some(DomainId::TrafficLight), TL_CONTROLLED_LINKS, TYPE_COMPOUND) => {
+                    TraciValue::LinkList(crate::scopes::traffic_light::read_link_list(in_msg)?)
+                }
+                (Some(DomainId::Vehicle), VAR_BEST_LANES, TYPE_COMPOUND) => {
+                    TraciValue::BestLanesList(crate::scopes::vehicle::read_best_lanes(in_msg)?)
+                }
+                _ => Self::read_typed_value(in_msg, type_id)?,
+            };
             results.insert(var_id, value);
         }
         Ok(results)
@@ -792,6 +1538,16 @@ impl TraciClient {
     pub(crate) fn read_typed_value(
         in_msg: &mut Storage,
         type_id: u8,
+    ) -> Result<TraciValue, TraciError> {
+        Self::read_nested_value(in_msg, type_id, 0)
+    }
+
+    /// [`read_typed_value`](Self::read_typed_value) for a value nested
+    /// `depth` compounds deep.
+    fn read_nested_value(
+        in_msg: &mut Storage,
+        type_id: u8,
+        depth: usize,
     ) -> Result<TraciValue, TraciError> {
         match type_id {
             TYPE_DOUBLE => Ok(TraciValue::Double(in_msg.read_f64()?)),
@@ -817,18 +1573,87 @@ impl TraciClient {
                 let z = in_msg.read_f64()?;
                 Ok(TraciValue::Pos3D { x, y, z })
             }
+            POSITION_LON_LAT => {
+                let lon = in_msg.read_f64()?;
+                let lat = in_msg.read_f64()?;
+                Ok(TraciValue::LonLat { lon, lat })
+            }
+            POSITION_LON_LAT_ALT => {
+                let lon = in_msg.read_f64()?;
+                let lat = in_msg.read_f64()?;
+                let alt = in_msg.read_f64()?;
+                Ok(TraciValue::LonLatAlt { lon, lat, alt })
+            }
+            TYPE_POLYGON => {
+                // Point count is a ubyte; 0 announces a following i32 count
+                // for shapes with 256 or more points.
+                let n = match in_msg.read_u8()? {
+                    0 => in_msg.read_i32()?.max(0) as usize,
+                    n => n as usize,
+                };
+                let mut points = Vec::new();
+                for _ in 0..n {
+                    let x = in_msg.read_f64()?;
+                    let y = in_msg.read_f64()?;
+                    points.push(TraciPosition::new_2d(x, y));
+                }
+                Ok(TraciValue::Polygon(points))
+            }
+            TYPE_COMPOUND => {
+                if depth >= MAX_COMPOUND_DEPTH {
+                    return Err(TraciError::Protocol(format!(
+                        "Compound values nested more than {MAX_COMPOUND_DEPTH} levels deep"
+                    )));
+                }
+                let n = in_msg.read_i32()?;
+                let mut items = Vec::new();
+                for _ in 0..n {
+                    let item_type = in_msg.read_u8()?;
+                    items.push(Self::read_nested_value(in_msg, item_type, depth + 1)?);
+                }
+                Ok(TraciValue::Compound(items))
+            }
             TYPE_UBYTE => {
                 // Read as Int for uniformity (matches TraCIInt in C++ with traciType=TYPE_UBYTE)
                 Ok(TraciValue::Int(in_msg.read_u8()? as i32))
             }
+            TYPE_BYTE => Ok(TraciValue::Int(in_msg.read_byte()?)),
             other => {
-                // Forward-compatible: capture remaining bytes if we don't know the type.
-                // For now store the raw single byte tag and an empty payload.
-                Ok(TraciValue::Unknown { type_id: other, raw: Vec::new() })
+                // Forward-compatible: keep the undecoded payload so the cursor
+                // stays aligned with the next value.
+                let raw = Self::read_raw_value(in_msg, other)?;
+                Ok(TraciValue::Unknown { type_id: other, raw })
             }
         }
     }
 
+    /// Consume the payload of a value tagged `type_id` that
+    /// `read_typed_value` does not decode and return its exact bytes.
+    ///
+    /// Fails for tags whose payload length cannot be determined, since the
+    /// rest of the message could no longer be parsed reliably.
+    fn read_raw_value(in_msg: &mut Storage, type_id: u8) -> Result<Vec<u8>, TraciError> {
+        let start = in_msg.position();
+        match type_id {
+            TYPE_BOUNDINGBOX => {
+                for _ in 0..4 {
+                    in_msg.read_f64()?; // xmin, ymin, xmax, ymax
+                }
+            }
+            POSITION_ROADMAP => {
+                in_msg.read_string()?; // edge id
+                in_msg.read_f64()?;    // position
+                in_msg.read_u8()?;     // lane index
+            }
+            other => {
+                return Err(TraciError::Protocol(format!(
+                    "Cannot determine the length of a value with unknown type 0x{other:02x}"
+                )));
+            }
+        }
+        Ok(in_msg.as_bytes()[start..in_msg.position()].to_vec())
+    }
+
     // -----------------------------------------------------------------------
     // Low-level send helpers
     // -----------------------------------------------------------------------
@@ -848,6 +1673,12 @@ impl TraciClient {
         self.socket_mut()?.send_exact(&msg)
     }
 
+    fn close_handshake(&mut self) -> Result<(), TraciError> {
+        self.send_close()?;
+        let mut in_msg = self.socket_mut()?.receive_exact()?;
+        Self::check_result_state_static(&mut in_msg, CMD_CLOSE, false, None)
+    }
+
     fn close_socket(&mut self) {
         if let Some(mut sock) = self.socket.take() {
             let _ = sock.close();
@@ -867,5 +1698,583 @@ impl TraciClient {
 impl Drop for TraciClient {
     fn drop(&mut self) {
         self.close_socket();
+        if let Some(mut child) = self.sumo_process.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Decode a type tag and the value it announces from `data`. Entry point
+/// for the fuzz targets in `fuzz/`; not part of the public API.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub fn fuzz_read_typed_value(data: &[u8]) -> Result<TraciValue, TraciError> {
+    let mut in_msg = Storage::from_bytes(data.to_vec());
+    let type_id = in_msg.read_u8()?;
+    TraciClient::read_typed_value(&mut in_msg, type_id)
+}
+
+/// Return `true` for I/O errors caused by the server closing its end of the
+/// connection.
+fn is_closed_by_peer(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::UnexpectedEof
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::BrokenPipe
+    )
+}
+
+/// Wait up to `timeout` for `child` to exit, then kill it. A SUMO that
+/// hangs after the close handshake must not block the caller forever.
+fn wait_or_kill(child: &mut Child, timeout: Duration) -> std::io::Result<()> {
+    let deadline = std::time::Instant::now() + timeout;
+    while std::time::Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    // kill fails if the process exited in the meantime; wait reaps it either way
+    let _ = child.kill();
+    child.wait().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn batch_get_sends_one_message_and_decodes_in_order() {
+        let reply = mock::batch_get_reply(&[
+            (CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", mock::double(13.9)),
+            (CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, "e0", mock::string_list(&["veh0", "veh1"])),
+            (CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh1", mock::pos_2d(1.0, 2.0)),
+        ]);
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let values = client
+            .batch_get(&[
+                (CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0"),
+                (CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, "e0"),
+                (CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh1"),
+            ])
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                TraciValue::Double(13.9),
+                TraciValue::StringList(vec!["veh0".into(), "veh1".into()]),
+                TraciValue::Pos2D { x: 1.0, y: 2.0 },
+            ]
+        );
+
+        drop(client);
+        let mut expected = mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", None);
+        expected.extend(mock::command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, "e0", None));
+        expected.extend(mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh1", None));
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn batch_get_fails_on_an_error_status() {
+        let mut reply = mock::batch_get_reply(&[(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", mock::double(1.0))]);
+        mock::write_status(&mut reply, CMD_GET_VEHICLE_VARIABLE, RTYPE_ERR, "Vehicle 'ghost' is not known");
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        let err = client
+            .batch_get(&[(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0"), (CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "ghost")])
+            .unwrap_err();
+        assert_eq!(err.server_message(), Some("Vehicle 'ghost' is not known"));
+    }
+
+    #[test]
+    fn batch_set_reports_every_status_in_order() {
+        let mut reply = mock::status(CMD_SET_VEHICLE_VARIABLE);
+        mock::write_status(&mut reply, CMD_SET_VEHICLE_VARIABLE, RTYPE_ERR, "Vehicle 'ghost' is not known");
+        mock::write_status(&mut reply, CMD_SET_VEHICLE_VARIABLE, RTYPE_OK, "");
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let requests = [
+            (CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", mock::double(10.0)),
+            (CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "ghost", mock::double(11.0)),
+            (CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh1", mock::double(12.0)),
+        ];
+        let results = client.batch_set(&requests).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.server_message(), Some("Vehicle 'ghost' is not known"));
+        assert!(err.to_string().contains("batch_set('ghost')"), "{err}");
+        assert!(results[2].is_ok());
+
+        drop(client);
+        let expected: Vec<u8> = requests
+            .iter()
+            .flat_map(|(cmd, var, id, value)| mock::command(*cmd, *var, id, Some(value)))
+            .collect();
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    /// The reply to the step-event batch for the given id lists, in the
+    /// order `simulation_step_with_events` requests them.
+    fn step_events_reply(lists: &[(u8, &[&str])]) -> Storage {
+        let responses: Vec<_> = lists
+            .iter()
+            .map(|(var, ids)| (CMD_GET_SIM_VARIABLE, *var, "", mock::string_list(ids)))
+            .collect();
+        mock::batch_get_reply(&responses)
+    }
+
+    #[test]
+    fn simulation_step_with_events_reports_departures_and_arrivals() {
+        let events = step_events_reply(&[
+            (VAR_DEPARTED_VEHICLES_IDS, &["veh1"]),
+            (VAR_ARRIVED_VEHICLES_IDS, &["veh0"]),
+            (VAR_TELEPORT_STARTING_VEHICLES_IDS, &[]),
+            (VAR_TELEPORT_ENDING_VEHICLES_IDS, &[]),
+            (VAR_COLLIDING_VEHICLES_IDS, &[]),
+        ]);
+        let (mut client, server) = mock::connect_replies(vec![mock::step_reply(&[]), events]);
+
+        let events = client.simulation_step_with_events(0.0).unwrap();
+        assert_eq!(
+            events,
+            StepEvents { departed: vec!["veh1".into()], arrived: vec!["veh0".into()], ..Default::default() }
+        );
+
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2, "the event lists are fetched in one round trip");
+        assert_eq!(requests[1].len(), 5 * mock::command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_IDS, "", None).len());
+    }
+
+    #[test]
+    fn with_timeout_times_out_and_restores_the_previous_timeout() {
+        let slow = mock::Reply::Delayed(Duration::from_millis(500), mock::status(CMD_SETORDER));
+        let (mut client, _server) = mock::connect(vec![slow]);
+        client.set_timeout(Some(Duration::from_secs(30))).unwrap();
+
+        let err = client.with_timeout(Duration::from_millis(50), |c| c.set_order(1)).unwrap_err();
+        assert!(matches!(err, TraciError::Timeout(_)), "{err}");
+        assert_eq!(client.socket_mut().unwrap().timeout().unwrap(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn empty_batches_do_not_touch_the_connection() {
+        let (mut client, server) = mock::connect_replies(vec![]);
+        assert!(client.batch_get(&[]).unwrap().is_empty());
+        assert!(client.batch_set(&[]).unwrap().is_empty());
+        drop(client);
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn load_drops_the_cached_coordinate_converter() {
+        let utm32 = "+proj=utm +zone=32 +ellps=WGS84 +datum=WGS84 +units=m +no_defs";
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string(utm32)),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("0.00,0.00")),
+            mock::status(CMD_LOAD),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string(utm32)),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("-500000.00,-5000000.00")),
+        ]);
+
+        let before = client.coordinate_converter().unwrap();
+        client.load(&["-c".into(), "other.sumocfg".into()]).unwrap();
+        let after = client.coordinate_converter().unwrap();
+        assert_eq!(before, CoordinateConverter::new(utm32, (0.0, 0.0)));
+        assert_eq!(after, CoordinateConverter::new(utm32, (-500_000.0, -5_000_000.0)));
+
+        drop(client);
+        assert_eq!(server.requests().len(), 5);
+    }
+
+    #[test]
+    fn simulation_step_reports_the_end_of_run_signal() {
+        let mut close = Storage::new();
+        mock::write_response(&mut close, CMD_CLOSE, &Storage::new());
+        let (mut client, server) = mock::connect_replies(vec![mock::step_reply(&[]), mock::step_reply(&[close])]);
+
+        assert!(client.simulation_step(0.0).unwrap());
+        assert!(!client.simulation_step(0.0).unwrap());
+        assert!(client.is_finished().unwrap(), "known without asking the server");
+
+        drop(client);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn simulation_step_after_a_clean_shutdown_is_simulation_end() {
+        let (mut client, _server) = mock::connect(vec![mock::Reply::Close]);
+        let err = client.simulation_step(0.0).unwrap_err();
+        assert!(matches!(err, TraciError::SimulationEnd), "{err}");
+        assert!(client.is_finished().unwrap());
+    }
+
+    #[test]
+    fn simulation_step_keeps_a_connection_reset_as_an_error() {
+        let (mut client, _server) = mock::connect(vec![mock::Reply::Reset]);
+        let err = client.simulation_step(0.0).unwrap_err();
+        assert!(matches!(&err, TraciError::Connection(e) if e.kind() == std::io::ErrorKind::ConnectionReset), "{err}");
+    }
+
+    #[test]
+    fn simulation_step_with_events_reports_a_departed_person() {
+        let events = step_events_reply(&[
+            (VAR_DEPARTED_VEHICLES_IDS, &[]),
+            (VAR_ARRIVED_VEHICLES_IDS, &[]),
+            (VAR_TELEPORT_STARTING_VEHICLES_IDS, &[]),
+            (VAR_TELEPORT_ENDING_VEHICLES_IDS, &[]),
+            (VAR_COLLIDING_VEHICLES_IDS, &[]),
+            (VAR_DEPARTED_PERSONS_IDS, &["ped0"]),
+            (VAR_ARRIVED_PERSONS_IDS, &[]),
+        ]);
+        let (mut client, server) = mock::connect_replies(vec![mock::step_reply(&[]), events]);
+        client.set_person_step_events(true);
+
+        let events = client.simulation_step_with_events(0.0).unwrap();
+        assert_eq!(events, StepEvents { departed_persons: vec!["ped0".into()], ..Default::default() });
+
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2, "person events share the batched request");
+        assert_eq!(requests[1].len(), 7 * mock::command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_IDS, "", None).len());
+    }
+
+    #[test]
+    fn replay_after_reconnect_resends_the_recorded_subscriptions() {
+        let acks = || {
+            vec![
+                mock::status(CMD_SUBSCRIBE_VEHICLE_VARIABLE),
+                mock::status(CMD_SUBSCRIBE_VEHICLE_CONTEXT),
+                mock::status(CMD_ADD_SUBSCRIPTION_FILTER),
+            ]
+        };
+        let (mut client, first) = mock::connect_replies(acks());
+        client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLE_VARIABLE, "veh0", 0.0, 100.0, &[VAR_SPEED]).unwrap();
+        client
+            .subscribe_object_context(CMD_SUBSCRIBE_VEHICLE_CONTEXT, "veh0", 0.0, 100.0, CMD_GET_VEHICLE_VARIABLE, 50.0, &[VAR_SPEED])
+            .unwrap();
+        let mut dist = Storage::new();
+        dist.write_u8(TYPE_DOUBLE);
+        dist.write_f64(80.0);
+        client.add_subscription_filter(FILTER_TYPE_DOWNSTREAM_DIST, Some(&dist)).unwrap();
+
+        // A restarted SUMO on another port
+        let mut script = vec![mock::version_reply(21, "SUMO mock").into()];
+        script.extend(acks().into_iter().map(mock::Reply::from));
+        script.push(mock::status(CMD_CLOSE).into());
+        let second = mock::MockServer::start(script);
+        client.reconnect("127.0.0.1", second.port()).unwrap();
+        client.replay_subscriptions().unwrap();
+
+        // close forgets the subscriptions, so nothing is left to replay
+        client.close().unwrap();
+        client.replay_subscriptions().unwrap();
+
+        drop(client);
+        let recorded = first.requests();
+        let replayed = second.requests();
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(replayed[1..4], recorded[..]);
+        assert_eq!(replayed.len(), 5, "version, three replayed commands, close");
+    }
+
+    #[test]
+    fn subscription_values_after_a_compound_still_decode() {
+        // VAR_NEXT_TLS: the signal count, then (id, link index, distance,
+        // state) for each upcoming signal, all in one flat compound
+        let mut next_tls = Storage::new();
+        next_tls.begin_compound(1 + 4);
+        next_tls.write_u8(TYPE_INTEGER);
+        next_tls.write_i32(1);
+        next_tls.write_u8(TYPE_STRING);
+        next_tls.write_string("tl0");
+        next_tls.write_u8(TYPE_INTEGER);
+        next_tls.write_i32(3);
+        next_tls.write_u8(TYPE_DOUBLE);
+        next_tls.write_f64(42.5);
+        next_tls.write_u8(TYPE_BYTE);
+        next_tls.write_u8(b'r');
+        let sub = mock::subscription(
+            RESPONSE_SUBSCRIBE_VEHICLE_VARIABLE,
+            "veh0",
+            &[(VAR_SPEED, mock::double(13.9)), (VAR_NEXT_TLS, next_tls), (VAR_ROAD_ID, mock::string("e1"))],
+        );
+        let (mut client, _server) = mock::connect_replies(vec![mock::step_reply(&[sub])]);
+
+        assert!(client.simulation_step(0.0).unwrap());
+        let results = &client.vehicle.subscription_results["veh0"];
+        assert_eq!(results[&VAR_SPEED], TraciValue::Double(13.9));
+        assert_eq!(
+            results[&VAR_NEXT_TLS],
+            TraciValue::Compound(vec![
+                TraciValue::Int(1),
+                TraciValue::String("tl0".into()),
+                TraciValue::Int(3),
+                TraciValue::Double(42.5),
+                TraciValue::Int(b'r' as i32),
+            ])
+        );
+        assert_eq!(results[&VAR_ROAD_ID], TraciValue::String("e1".into()));
+    }
+
+    #[test]
+    fn deeply_nested_compounds_are_a_protocol_error() {
+        let mut value = Storage::new();
+        for _ in 0..=MAX_COMPOUND_DEPTH {
+            value.write_u8(TYPE_COMPOUND);
+            value.write_i32(1);
+        }
+        value.write_u8(TYPE_INTEGER);
+        value.write_i32(0);
+        value.read_u8().unwrap();
+
+        let err = TraciClient::read_typed_value(&mut value, TYPE_COMPOUND).unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "{err:?}");
+    }
+
+    #[test]
+    fn compounds_at_the_depth_limit_decode() {
+        let mut value = Storage::new();
+        for _ in 0..MAX_COMPOUND_DEPTH {
+            value.write_u8(TYPE_COMPOUND);
+            value.write_i32(1);
+        }
+        value.write_u8(TYPE_INTEGER);
+        value.write_i32(7);
+        value.read_u8().unwrap();
+
+        let mut decoded = TraciClient::read_typed_value(&mut value, TYPE_COMPOUND).unwrap();
+        for _ in 0..MAX_COMPOUND_DEPTH {
+            decoded = match decoded {
+                TraciValue::Compound(mut items) => items.remove(0),
+                other => panic!("expected a compound, got {other:?}"),
+            };
+        }
+        assert_eq!(decoded, TraciValue::Int(7));
+    }
+
+    #[test]
+    fn values_after_an_undecoded_type_still_parse() {
+        let mut msg = Storage::new();
+        msg.write_u8(POSITION_ROADMAP);
+        msg.write_string("e0");
+        msg.write_f64(12.5);
+        msg.write_u8(1);
+        msg.write_u8(TYPE_DOUBLE);
+        msg.write_f64(13.9);
+        let roadmap = msg.as_bytes()[1..1 + 4 + 2 + 8 + 1].to_vec();
+
+        let type_id = msg.read_u8().unwrap();
+        let value = TraciClient::read_typed_value(&mut msg, type_id).unwrap();
+        assert_eq!(value, TraciValue::Unknown { type_id: POSITION_ROADMAP, raw: roadmap });
+        let type_id = msg.read_u8().unwrap();
+        assert_eq!(TraciClient::read_typed_value(&mut msg, type_id).unwrap(), TraciValue::Double(13.9));
+    }
+
+    #[test]
+    fn a_subscription_variable_after_a_bounding_box_still_parses() {
+        let mut boundary = Storage::new();
+        boundary.write_u8(TYPE_BOUNDINGBOX);
+        for v in [0.0, 0.0, 100.0, 50.0] {
+            boundary.write_f64(v);
+        }
+        let raw = boundary.as_bytes()[1..].to_vec();
+        let sub = mock::subscription(
+            RESPONSE_SUBSCRIBE_POLYGON_VARIABLE,
+            "area0",
+            // A variable SUMO might add in a later version
+            &[(0xfe, boundary), (VAR_TYPE, mock::string("park"))],
+        );
+        let (mut client, _server) = mock::connect_replies(vec![mock::step_reply(&[sub])]);
+
+        assert!(client.simulation_step(0.0).unwrap());
+        let results = &client.polygon.subscription_results["area0"];
+        assert_eq!(results[&0xfe], TraciValue::Unknown { type_id: TYPE_BOUNDINGBOX, raw });
+        assert_eq!(results[&VAR_TYPE], TraciValue::String("park".into()));
+    }
+
+    #[test]
+    fn a_type_of_unknown_length_is_a_protocol_error() {
+        let mut msg = Storage::new();
+        msg.write_f64(1.0);
+        let err = TraciClient::read_typed_value(&mut msg, 0x0a).unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "{err:?}");
+    }
+
+    #[test]
+    fn connect_with_retries_waits_for_a_late_server() {
+        let server = mock::MockServer::start_late(
+            Duration::from_millis(300),
+            vec![mock::version_reply(21, "SUMO mock").into()],
+        );
+        let client = TraciClient::connect_with_retries("127.0.0.1", server.port(), 50, Duration::from_millis(20)).unwrap();
+        assert_eq!(client.version().api, 21);
+        drop(client);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn connect_with_retries_reports_the_last_refusal() {
+        let port = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+        match TraciClient::connect_with_retries("127.0.0.1", port, 2, Duration::from_millis(10)) {
+            Err(TraciError::Connection(e)) => assert_eq!(e.kind(), std::io::ErrorKind::ConnectionRefused),
+            Err(other) => panic!("expected a connection error, got {other:?}"),
+            Ok(_) => panic!("connected to a closed port"),
+        }
+    }
+
+    #[test]
+    fn a_server_that_never_replies_times_out() {
+        let (mut client, server) = mock::connect(vec![mock::Reply::Silence]);
+        client.set_timeout(Some(Duration::from_millis(100))).unwrap();
+
+        let start = std::time::Instant::now();
+        let err = crate::SimulationScope::default().get_time(&mut client).unwrap_err();
+        assert!(matches!(err, TraciError::Timeout(_)), "{err}");
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(client);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_or_kill_kills_a_process_that_does_not_exit() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let start = std::time::Instant::now();
+        wait_or_kill(&mut child, Duration::from_millis(100)).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn close_waits_for_a_process_that_exits() {
+        let (mut client, _server) = mock::connect_replies(vec![mock::status(CMD_CLOSE)]);
+        client.sumo_process = Some(Command::new("true").spawn().unwrap());
+
+        let start = std::time::Instant::now();
+        client.close().unwrap();
+        assert!(start.elapsed() < SUMO_EXIT_TIMEOUT);
+        assert!(client.sumo_process.is_none());
+    }
+
+    #[test]
+    fn execute_batch_interleaves_gets_and_sets() {
+        let mut reply = Storage::new();
+        mock::write_status(&mut reply, CMD_GET_VEHICLE_VARIABLE, RTYPE_OK, "");
+        mock::write_get_response(&mut reply, CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", &mock::double(13.9));
+        mock::write_status(&mut reply, CMD_SET_VEHICLE_VARIABLE, RTYPE_OK, "");
+        mock::write_status(&mut reply, CMD_GET_VEHICLE_VARIABLE, RTYPE_OK, "");
+        mock::write_get_response(&mut reply, CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", &mock::double(10.0));
+        mock::write_status(&mut reply, CMD_SET_VEHICLE_VARIABLE, RTYPE_ERR, "Vehicle 'ghost' is not known");
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let speed = mock::double(10.0);
+        let mut batch = CommandBatch::new();
+        batch
+            .get(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0")
+            .set(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", &speed)
+            .get(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0")
+            .set(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "ghost", &speed);
+        let results = client.execute_batch(&batch).unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), &Some(TraciValue::Double(13.9)));
+        assert_eq!(results[1].as_ref().unwrap(), &None);
+        assert_eq!(results[2].as_ref().unwrap(), &Some(TraciValue::Double(10.0)));
+        let err = results[3].as_ref().unwrap_err();
+        assert_eq!(err.server_message(), Some("Vehicle 'ghost' is not known"));
+
+        drop(client);
+        let mut expected = mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", None);
+        expected.extend(mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", Some(&speed)));
+        expected.extend(mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", None));
+        expected.extend(mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "ghost", Some(&speed)));
+        assert_eq!(server.requests(), vec![expected], "all four commands go out in one message");
+    }
+
+    /// A reply to a GET of `VAR_SPEED` whose response declares `declared`
+    /// bytes, followed by `trailing` extra bytes. The response is 20 bytes.
+    fn speed_reply_declaring(declared: u8, trailing: usize) -> Storage {
+        let mut reply = mock::status(CMD_GET_VEHICLE_VARIABLE);
+        reply.write_u8(declared);
+        reply.write_u8(RESPONSE_GET_VEHICLE_VARIABLE);
+        reply.write_u8(VAR_SPEED);
+        reply.write_string("veh0");
+        reply.write_u8(TYPE_DOUBLE);
+        reply.write_f64(13.9);
+        reply.write_packet(&vec![0; trailing]);
+        reply
+    }
+
+    fn get_speed(client: &mut TraciClient) -> Result<f64, TraciError> {
+        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", None);
+        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+        let speed = client.read_double_from_input()?;
+        client.finish_get()?;
+        Ok(speed)
+    }
+
+    #[test]
+    fn get_response_length_matching_its_value_is_accepted() {
+        let (mut client, server) = mock::connect_replies(vec![speed_reply_declaring(20, 0)]);
+        assert_eq!(get_speed(&mut client).unwrap(), 13.9);
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn get_response_declaring_more_than_the_message_is_a_protocol_error() {
+        let (mut client, server) = mock::connect_replies(vec![speed_reply_declaring(40, 0)]);
+        match get_speed(&mut client).unwrap_err() {
+            TraciError::Protocol(msg) => assert_eq!(
+                msg,
+                format!("Response to command 0x{CMD_GET_VEHICLE_VARIABLE:02x} declares 40 bytes but only 20 were received")
+            ),
+            other => panic!("unexpected error {other:?}"),
+        }
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn get_response_length_not_matching_its_value_is_a_protocol_error() {
+        let (mut client, server) =
+            mock::connect_replies(vec![speed_reply_declaring(24, 4), speed_reply_declaring(16, 0)]);
+        for mismatch in ["declares 24 bytes but 20 were read", "declares 16 bytes but 20 were received"] {
+            match get_speed(&mut client).unwrap_err() {
+                TraciError::Protocol(msg) => {
+                    assert_eq!(msg, format!("Response to command 0x{CMD_GET_VEHICLE_VARIABLE:02x} {mismatch}"))
+                }
+                other => panic!("unexpected error {other:?}"),
+            }
+        }
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn batch_get_checks_each_response_length_after_reading_it() {
+        let mut reply = speed_reply_declaring(24, 4);
+        let next = mock::batch_get_reply(&[(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh1", mock::double(7.0))]);
+        reply.write_packet(next.as_bytes());
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let requests = [(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh0"), (CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, "veh1")];
+        match client.batch_get(&requests).unwrap_err() {
+            TraciError::Protocol(msg) => assert_eq!(
+                msg,
+                format!("Response to command 0x{CMD_GET_VEHICLE_VARIABLE:02x} declares 24 bytes but 20 were read")
+            ),
+            other => panic!("unexpected error {other:?}"),
+        }
+        drop(client);
+        server.requests();
     }
 }
@@ -236,6 +236,7 @@ pub const POSITION_ROADMAP: u8     = 0x04;
 // ============================================================================
 // DATA TYPES
 // ============================================================================
+pub const TYPE_BOUNDINGBOX: u8 = 0x05;
 pub const TYPE_POLYGON: u8    = 0x06;
 pub const TYPE_UBYTE: u8      = 0x07;
 pub const TYPE_BYTE: u8       = 0x08;
@@ -261,6 +262,7 @@ pub const INVALID_DOUBLE_VALUE: f64 = -1_073_741_824.0_f64;
 pub const INVALID_INT_VALUE: i32    = -1_073_741_824_i32;
 pub const MAX_ORDER: i32            = 1_073_741_824_i32;
 pub const DEFAULT_NUM_RETRIES: i32  = 60;
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000;
 
 // ============================================================================
 // DISTANCE REQUESTS
@@ -308,6 +310,16 @@ pub const STOP_CHARGING_STATION: u8     = 0x20;
 pub const STOP_PARKING_AREA: u8         = 0x40;
 pub const STOP_OVERHEAD_WIRE: u8        = 0x80;
 
+// ============================================================================
+// NEIGHBOR QUERY MODE BITS (VAR_NEIGHBORS)
+// ============================================================================
+/// Query the left neighbor lane instead of the right one.
+pub const NEIGHBOR_LEFT: u8                 = 0x01;
+/// Query leaders instead of followers.
+pub const NEIGHBOR_LEADERS: u8              = 0x02;
+/// Return only neighbors that block a lane change.
+pub const NEIGHBOR_BLOCKING: u8             = 0x04;
+
 // ============================================================================
 // DEPARTURE FLAGS
 // ============================================================================
@@ -400,6 +412,7 @@ pub const TL_RED_YELLOW_GREEN_STATE: u8           = 0x20;
 pub const TL_PHASE_INDEX: u8                      = 0x22;
 pub const TL_PROGRAM: u8                          = 0x23;
 pub const TL_PHASE_DURATION: u8                   = 0x24;
+pub const TL_BLOCKING_VEHICLES: u8                = 0x25;
 pub const TL_CONTROLLED_LANES: u8                 = 0x26;
 pub const TL_CONTROLLED_LINKS: u8                 = 0x27;
 pub const TL_CURRENT_PHASE: u8                    = 0x28;
@@ -408,6 +421,12 @@ pub const TL_CONTROLLED_JUNCTIONS: u8             = 0x2a;
 pub const TL_COMPLETE_DEFINITION_RYG: u8          = 0x2b;
 pub const TL_COMPLETE_PROGRAM_RYG: u8             = 0x2c;
 pub const TL_NEXT_SWITCH: u8                      = 0x2d;
+pub const TL_RIVAL_VEHICLES: u8                   = 0x30;
+pub const TL_PRIORITY_VEHICLES: u8                = 0x31;
+pub const TL_CONSTRAINT: u8                       = 0x32;
+pub const TL_CONSTRAINT_SWAP: u8                  = 0x33;
+pub const TL_CONSTRAINT_BYFOE: u8                 = 0x34;
+pub const TL_CONSTRAINT_REMOVE: u8                = 0x35;
 pub const TL_SPENT_DURATION: u8                   = 0x38;
 pub const LANE_LINK_NUMBER: u8                    = 0x30;
 pub const LANE_EDGE_ID: u8                        = 0x31;
@@ -464,6 +483,8 @@ pub const VAR_ROUTE_INDEX: u8                     = 0x69;
 pub const VAR_WAITING_TIME: u8                    = 0x7a;
 pub const VAR_ACCUMULATED_WAITING_TIME: u8        = 0x87;
 pub const VAR_NEXT_TLS: u8                        = 0x70;
+pub const VAR_NEXT_STOPS: u8                      = 0x73;
+pub const VAR_NEXT_STOPS2: u8                     = 0x74;
 pub const VAR_TIME: u8                            = 0x66;
 pub const VAR_TIME_STEP: u8                       = 0x70;
 pub const VAR_ELECTRICITYCONSUMPTION: u8          = 0x71;
@@ -477,7 +498,11 @@ pub const VAR_TELEPORT_ENDING_VEHICLES_NUMBER: u8    = 0x77;
 pub const VAR_TELEPORT_ENDING_VEHICLES_IDS: u8       = 0x78;
 pub const VAR_ARRIVED_VEHICLES_NUMBER: u8         = 0x79;
 pub const VAR_ARRIVED_VEHICLES_IDS: u8            = 0x7a;
+pub const VAR_COLLIDING_VEHICLES_NUMBER: u8       = 0x80;
+pub const VAR_COLLIDING_VEHICLES_IDS: u8          = 0x81;
+pub const VAR_COLLISIONS: u8                      = 0x20;
 pub const VAR_DELTA_T: u8                         = 0x7b;
+pub const VAR_SCALE: u8                           = 0x8e;
 pub const VAR_NET_BOUNDING_BOX: u8                = 0x7c;
 pub const VAR_MIN_EXPECTED_VEHICLES: u8           = 0x7d;
 pub const VAR_EMERGENCY_DECEL: u8                 = 0x7b;
@@ -558,3 +583,94 @@ pub const FIND_INTERMODAL_ROUTE: u8               = 0x87;
 pub const VAR_TIMELOSS: u8                        = 0x8c;
 pub const VAR_STOP_ARRIVALDELAY: u8               = 0x22;
 pub const VAR_PREV_SPEED: u8                      = 0x3c;
+pub const VAR_HIGHLIGHT: u8                       = 0xc7;
+pub const VAR_ACTIONSTEPLENGTH: u8                = 0x7d;
+pub const VAR_LASTACTIONTIME: u8                  = 0x7f;
+pub const VAR_NEIGHBORS: u8                       = 0xbf;
+
+// ============================================================================
+// INTENTIONALLY SHARED VALUES
+// ============================================================================
+//
+// TraCI variable ids are only unique within one command domain, so the same
+// byte legitimately names different variables elsewhere (e.g. 0x66 is
+// VAR_TIME for the simulation but VAR_NOISEEMISSION for vehicles and edges,
+// 0x7a is VAR_ARRIVED_VEHICLES_IDS vs VAR_WAITING_TIME). The checks below pin
+// the known cross-domain overlaps and fail the build if two variables queried
+// through the *same* domain ever collide.
+
+const fn all_distinct(ids: &[u8]) -> bool {
+    let mut i = 0;
+    while i < ids.len() {
+        let mut j = i + 1;
+        while j < ids.len() {
+            if ids[i] == ids[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+const _: () = {
+    // simulation vs. vehicle/edge
+    assert!(VAR_TIME == VAR_NOISEEMISSION);
+    assert!(VAR_ARRIVED_VEHICLES_IDS == VAR_WAITING_TIME);
+    assert!(FIND_INTERMODAL_ROUTE == VAR_ACCUMULATED_WAITING_TIME);
+    assert!(VAR_DELTA_T == VAR_EMERGENCY_DECEL);
+    assert!(VAR_NET_BOUNDING_BOX == VAR_APPARENT_DECEL);
+    assert!(VAR_MIN_EXPECTED_VEHICLES == VAR_ACTIONSTEPLENGTH);
+    assert!(VAR_TIME_STEP == VAR_NEXT_TLS);
+    assert!(VAR_LOADED_VEHICLES_IDS == VAR_ACCELERATION);
+    // edge/junction topology vs. vehicle
+    assert!(FROM_JUNCTION == VAR_EMERGENCY_DECEL && INCOMING_EDGES == FROM_JUNCTION);
+    assert!(TO_JUNCTION == VAR_APPARENT_DECEL && OUTGOING_EDGES == TO_JUNCTION);
+    assert!(VAR_BIDI == VAR_LASTACTIONTIME);
+    // command ids vs. variable ids
+    assert!(CMD_CLEAR_PENDING_VEHICLES == VAR_PENDING_VEHICLES);
+    assert!(CMD_SET_POI_VARIABLE == VAR_HIGHLIGHT);
+};
+
+// Edge variables
+const _: () = assert!(all_distinct(&[
+    ID_COUNT, LAST_STEP_LENGTH, LAST_STEP_MEAN_SPEED, LAST_STEP_OCCUPANCY,
+    LAST_STEP_VEHICLE_HALTING_NUMBER, LAST_STEP_VEHICLE_ID_LIST, LAST_STEP_VEHICLE_NUMBER,
+    TRACI_ID_LIST, VAR_CO2EMISSION, VAR_COEMISSION, VAR_CURRENT_TRAVELTIME, VAR_EDGE_EFFORT,
+    VAR_EDGE_TRAVELTIME, VAR_ELECTRICITYCONSUMPTION, VAR_FUELCONSUMPTION, VAR_HCEMISSION,
+    VAR_LANE_INDEX, VAR_NAME, VAR_NOISEEMISSION, VAR_NOXEMISSION, VAR_PARAMETER,
+    VAR_PMXEMISSION, VAR_WAITING_TIME, LANE_ALLOWED, LANE_DISALLOWED, VAR_MAXSPEED,
+]));
+
+// Simulation variables
+const _: () = assert!(all_distinct(&[
+    DISTANCE_REQUEST, FIND_INTERMODAL_ROUTE, FIND_ROUTE, POSITION_CONVERSION,
+    VAR_ARRIVED_PERSONS_IDS, VAR_ARRIVED_PERSONS_NUMBER, VAR_ARRIVED_VEHICLES_IDS,
+    VAR_ARRIVED_VEHICLES_NUMBER, VAR_BUS_STOP_WAITING, VAR_BUS_STOP_WAITING_IDS, VAR_COLLISIONS,
+    VAR_DELTA_T, VAR_DEPARTED_PERSONS_IDS, VAR_DEPARTED_PERSONS_NUMBER, VAR_DEPARTED_VEHICLES_IDS,
+    VAR_DEPARTED_VEHICLES_NUMBER, VAR_LOADED_VEHICLES_IDS, VAR_LOADED_VEHICLES_NUMBER,
+    VAR_MIN_EXPECTED_VEHICLES, VAR_NET_BOUNDING_BOX, VAR_OPTION, VAR_PARAMETER,
+    VAR_PARAMETER_WITH_KEY, VAR_PENDING_VEHICLES, VAR_SCALE, VAR_TELEPORT_ENDING_VEHICLES_IDS,
+    VAR_TELEPORT_ENDING_VEHICLES_NUMBER, VAR_TELEPORT_STARTING_VEHICLES_IDS,
+    VAR_TELEPORT_STARTING_VEHICLES_NUMBER, VAR_TIME, VAR_TIME_STEP,
+]));
+
+// Vehicle variables
+const _: () = assert!(all_distinct(&[
+    ID_COUNT, LAST_STEP_PERSON_ID_LIST, TRACI_ID_LIST, VAR_ACCEL, VAR_ACCELERATION,
+    VAR_ACCUMULATED_WAITING_TIME, VAR_ACTIONSTEPLENGTH, VAR_ALLOWED_SPEED, VAR_ANGLE,
+    VAR_BEST_LANES, VAR_CO2EMISSION, VAR_COEMISSION, VAR_COLOR, VAR_DECEL, VAR_DISTANCE,
+    VAR_EDGES, VAR_ELECTRICITYCONSUMPTION, VAR_EMISSIONCLASS, VAR_FOLLOWER, VAR_FOLLOW_SPEED,
+    VAR_FUELCONSUMPTION, VAR_HCEMISSION, VAR_HEIGHT, VAR_IMPERFECTION, VAR_LANECHANGE_MODE,
+    VAR_LANEPOSITION, VAR_LANEPOSITION_LAT, VAR_LANE_ID, VAR_LANE_INDEX, VAR_LASTACTIONTIME,
+    VAR_LATALIGNMENT, VAR_LEADER, VAR_LENGTH, VAR_LINE, VAR_MAXSPEED, VAR_MAXSPEED_LAT,
+    VAR_MINGAP, VAR_MINGAP_LAT, VAR_NEIGHBORS, VAR_NEXT_STOPS2, VAR_NEXT_TLS, VAR_NOISEEMISSION,
+    VAR_NOXEMISSION, VAR_PARAMETER, VAR_PERSON_CAPACITY, VAR_PERSON_NUMBER, VAR_PMXEMISSION,
+    VAR_POSITION, VAR_POSITION3D, VAR_ROAD_ID, VAR_ROUTE_ID, VAR_ROUTE_INDEX, VAR_ROUTE_VALID,
+    VAR_ROUTING_MODE, VAR_SECURE_GAP, VAR_SHAPECLASS, VAR_SIGNALS, VAR_SLOPE, VAR_SPEED,
+    VAR_SPEEDSETMODE, VAR_SPEED_DEVIATION, VAR_SPEED_FACTOR, VAR_SPEED_LAT,
+    VAR_SPEED_WITHOUT_TRACI, VAR_STOPSTATE, VAR_STOP_ARRIVALDELAY, VAR_STOP_DELAY,
+    VAR_STOP_SPEED, VAR_TAU, VAR_TAXI_FLEET, VAR_TIMELOSS, VAR_TYPE, VAR_VEHICLECLASS, VAR_VIA,
+    VAR_WAITING_TIME, VAR_WIDTH, VAR_HIGHLIGHT,
+]));
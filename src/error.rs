@@ -14,13 +14,31 @@ pub enum TraciError {
     Protocol(String),
 
     /// The SUMO server returned `RTYPE_ERR` for a command we sent.
-    SimulationError(String),
+    ///
+    /// `message` is the server's text verbatim, so callers can react to
+    /// specific failures without parsing the formatted error.
+    SimulationError {
+        /// Id of the failed command (`CMD_GET_VEHICLE_VARIABLE`, …).
+        command: u8,
+        /// Error message exactly as sent by SUMO.
+        message: String,
+        /// The scope method and object that failed, e.g.
+        /// `vehicle.get_speed('veh0')`; empty when sent outside a scope.
+        context: String,
+    },
 
     /// The SUMO server replied that the requested command is not implemented.
     NotImplemented(String),
 
+    /// A socket read or write did not complete within the timeout set with
+    /// `TraciClient::set_timeout`.
+    Timeout(String),
+
     /// SUMO has reached the configured end time and closed the simulation.
-    /// Returned by [`TraciClient::simulation_step`] when `CMD_CLOSE` is received.
+    /// Returned by `TraciClient::simulation_step_with_events` when `CMD_CLOSE`
+    /// is received (`simulation_step` reports this as `Ok(false)`), and by
+    /// `simulation_step` when the server has already closed the connection
+    /// cleanly.
     SimulationEnd,
 }
 
@@ -29,13 +47,101 @@ impl fmt::Display for TraciError {
         match self {
             TraciError::Connection(e) => write!(f, "TraCI connection error: {e}"),
             TraciError::Protocol(msg) => write!(f, "TraCI protocol error: {msg}"),
-            TraciError::SimulationError(msg) => write!(f, "TraCI simulation error: {msg}"),
+            TraciError::SimulationError { command, message, context } => {
+                write!(f, "TraCI simulation error: ")?;
+                if !context.is_empty() {
+                    write!(f, "{context}: ")?;
+                }
+                write!(f, "Command 0x{command:02x} failed: {message}")
+            }
             TraciError::NotImplemented(msg) => write!(f, "TraCI command not implemented: {msg}"),
+            TraciError::Timeout(msg) => write!(f, "TraCI timeout: {msg}"),
             TraciError::SimulationEnd => write!(f, "SUMO simulation ended"),
         }
     }
 }
 
+impl TraciError {
+    /// Prefix the error message with `context`, typically the scope method
+    /// and object that failed, e.g. `vehicle.get_speed('veh0')`.
+    pub fn with_context(self, context: impl AsRef<str>) -> Self {
+        let context = context.as_ref();
+        match self {
+            TraciError::Connection(e) => TraciError::Connection(io::Error::new(
+                e.kind(),
+                IoContext { context: context.to_string(), source: e },
+            )),
+            TraciError::Protocol(msg) => TraciError::Protocol(format!("{context}: {msg}")),
+            TraciError::SimulationError { command, message, context: inner } => TraciError::SimulationError {
+                command,
+                message,
+                context: if inner.is_empty() { context.to_string() } else { format!("{context}: {inner}") },
+            },
+            TraciError::NotImplemented(msg) => TraciError::NotImplemented(format!("{context}: {msg}")),
+            TraciError::Timeout(msg) => TraciError::Timeout(format!("{context}: {msg}")),
+            TraciError::SimulationEnd => TraciError::SimulationEnd,
+        }
+    }
+
+    /// Return the id of the command SUMO rejected, for
+    /// [`TraciError::SimulationError`].
+    ///
+    /// ```
+    /// use traci_rs::TraciError;
+    /// let e = TraciError::SimulationError {
+    ///     command: 0xc4,
+    ///     message: "Vehicle 'veh0' is not known.".to_string(),
+    ///     context: String::new(),
+    /// }
+    /// .with_context("vehicle.set_speed('veh0')");
+    /// assert_eq!(e.command(), Some(0xc4));
+    /// assert_eq!(e.server_message(), Some("Vehicle 'veh0' is not known."));
+    /// ```
+    pub fn command(&self) -> Option<u8> {
+        match self {
+            TraciError::SimulationError { command, .. } => Some(*command),
+            _ => None,
+        }
+    }
+
+    /// Return SUMO's error text verbatim, for [`TraciError::SimulationError`].
+    pub fn server_message(&self) -> Option<&str> {
+        match self {
+            TraciError::SimulationError { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+}
+
+/// Run `f` and attach the context produced by `context` to its error, if any.
+/// Used by the [`with_context!`](crate::with_context) macro.
+pub(crate) fn in_context<T>(
+    f: impl FnOnce() -> Result<T, TraciError>,
+    context: impl FnOnce() -> String,
+) -> Result<T, TraciError> {
+    f().map_err(|e| e.with_context(context()))
+}
+
+/// An I/O error annotated by [`TraciError::with_context`]. Keeps the
+/// original error as its source, so callers can still inspect it.
+#[derive(Debug)]
+struct IoContext {
+    context: String,
+    source: io::Error,
+}
+
+impl fmt::Display for IoContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl std::error::Error for IoContext {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 impl std::error::Error for TraciError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -50,3 +156,28 @@ impl From<io::Error> for TraciError {
         TraciError::Connection(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_keeps_the_io_error_as_source() {
+        let err = TraciError::Connection(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"))
+            .with_context("vehicle.get_speed('veh0')");
+        let TraciError::Connection(e) = &err else { panic!("unexpected error {err:?}") };
+        assert_eq!(e.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(err.to_string(), "TraCI connection error: vehicle.get_speed('veh0'): pipe closed");
+
+        let inner = e.get_ref().and_then(|inner| inner.source()).expect("original error kept as source");
+        assert_eq!(inner.to_string(), "pipe closed");
+    }
+
+    #[test]
+    fn context_prefixes_simulation_errors() {
+        let err = TraciError::SimulationError { command: 0xa4, message: "unknown".into(), context: String::new() }
+            .with_context("vehicle.get_speed('veh0')");
+        assert_eq!(err.to_string(), "TraCI simulation error: vehicle.get_speed('veh0'): Command 0xa4 failed: unknown");
+        assert_eq!(err.server_message(), Some("unknown"));
+    }
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Interop with the [`geo-types`](https://docs.rs/geo-types) geometries
+//! (feature `geo`).
+//!
+//! Positions convert to and from [`Point`]; shapes such as those returned by
+//! `LaneScope::get_shape` or `PolygonScope::get_shape` convert with
+//! [`line_string`] and [`polygon`], and the two corners returned by
+//! `SimulationScope::get_net_boundary` with [`rect`]. The geometries are in
+//! SUMO network coordinates; the `z` component is dropped.
+//!
+//! ```
+//! use geo_types::Point;
+//! use traci_rs::{geo, TraciPosition};
+//!
+//! let shape = [
+//!     TraciPosition::new_2d(0.0, 0.0),
+//!     TraciPosition::new_2d(10.0, 0.0),
+//!     TraciPosition::new_2d(10.0, 5.0),
+//! ];
+//! let area = geo::polygon(&shape);
+//! assert_eq!(area.exterior().0.len(), 4); // closed ring
+//! assert_eq!(Point::from(shape[1].clone()), Point::new(10.0, 0.0));
+//! ```
+
+use geo_types::{Coord, LineString, Point, Polygon, Rect};
+
+use crate::types::{Boundary, TraciPosition};
+
+impl From<TraciPosition> for Point<f64> {
+    fn from(pos: TraciPosition) -> Self {
+        Point::new(pos.x, pos.y)
+    }
+}
+
+impl From<&TraciPosition> for Coord<f64> {
+    fn from(pos: &TraciPosition) -> Self {
+        Coord { x: pos.x, y: pos.y }
+    }
+}
+
+impl From<Point<f64>> for TraciPosition {
+    fn from(point: Point<f64>) -> Self {
+        TraciPosition::new_2d(point.x(), point.y())
+    }
+}
+
+impl From<&Boundary> for Rect<f64> {
+    fn from(boundary: &Boundary) -> Self {
+        Rect::new(Coord::from(&boundary.min), Coord::from(&boundary.max))
+    }
+}
+
+/// Convert a shape to an open [`LineString`], e.g. a lane centre line.
+pub fn line_string(shape: &[TraciPosition]) -> LineString<f64> {
+    shape.iter().map(Coord::from).collect()
+}
+
+/// Convert a shape to a [`Polygon`] without holes; the exterior ring is
+/// closed if the shape is not already.
+pub fn polygon(shape: &[TraciPosition]) -> Polygon<f64> {
+    Polygon::new(line_string(shape), Vec::new())
+}
+
+/// Convert the `[lower_left, upper_right]` corners of
+/// `SimulationScope::get_net_boundary` to a [`Rect`], or `None` if fewer
+/// than two corners are given.
+pub fn rect(boundary: &[TraciPosition]) -> Option<Rect<f64>> {
+    match boundary {
+        [lower_left, upper_right, ..] => Some(Rect::new(Coord::from(lower_left), Coord::from(upper_right))),
+        _ => None,
+    }
+}
@@ -17,9 +17,9 @@
 //!     client.simulation_step(0.0)?;
 //!
 //!     // Query all vehicles
-//!     let ids = client.vehicle.get_id_list(&mut client)?;
+//!     let ids = client.vehicle_get_id_list()?;
 //!     for id in &ids {
-//!         let pos = client.vehicle.get_position(&mut client, id)?;
+//!         let pos = client.vehicle_get_position(id)?;
 //!         println!("{}: ({}, {})", id, pos.x, pos.y);
 //!     }
 //!
@@ -30,6 +30,7 @@
 
 #[macro_use]
 pub(crate) mod scopes;
+pub(crate) use scopes::helpers::with_context;
 
 pub mod constants;
 pub mod error;
@@ -37,9 +38,19 @@ pub mod storage;
 pub mod socket;
 pub mod types;
 pub mod client;
+pub mod projection;
+#[cfg(feature = "tokio")]
+pub mod async_client;
+#[cfg(feature = "geo")]
+pub mod geo;
+#[cfg(test)]
+pub(crate) mod mock;
 
-pub use client::TraciClient;
+pub use client::{CommandBatch, TraciClient};
 pub use error::TraciError;
+pub use projection::CoordinateConverter;
+#[cfg(feature = "tokio")]
+pub use async_client::AsyncTraciClient;
 pub use types::*;
 pub use types::SubscribedKinematics;
 
@@ -53,3 +64,6 @@ pub use scopes::edge::EdgeScope;
 pub use scopes::lane::LaneScope;
 pub use scopes::junction::JunctionScope;
 pub use scopes::route::RouteScope;
+pub use scopes::induction_loop::InductionLoopScope;
+pub use scopes::lane_area::LaneAreaScope;
+pub use scopes::multi_entry_exit::MultiEntryExitScope;
@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Scripted stand-in for a SUMO server, used by the unit tests.
+//!
+//! [`MockServer`] accepts a single connection and answers every incoming
+//! message with the next step of its script, recording the request payloads
+//! so tests can compare them with the bytes a client method should send.
+//! The reply builders below produce the payload of one framed message; the
+//! 4-byte length prefix is added when it is sent.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{client::TraciClient, constants::*, storage::Storage, types::TraciStage};
+
+/// One step of a [`MockServer`] script, executed for the next request.
+pub(crate) enum Reply {
+    /// Read the request and answer with this payload.
+    Message(Storage),
+    /// Read the request and answer with this payload after `Duration`.
+    Delayed(Duration, Storage),
+    /// Read the request and close the connection cleanly.
+    Close,
+    /// Read the request and never answer, keeping the connection open
+    /// until the client closes it, like a hung SUMO process.
+    Silence,
+    /// Close the connection without reading the request, which makes the
+    /// client observe a connection reset.
+    Reset,
+}
+
+impl From<Storage> for Reply {
+    fn from(payload: Storage) -> Self {
+        Reply::Message(payload)
+    }
+}
+
+/// A scripted server listening on a free local port.
+pub(crate) struct MockServer {
+    port: u16,
+    handle: JoinHandle<Vec<Vec<u8>>>,
+    // Leading requests left out of `requests`, e.g. the version handshake
+    skip: usize,
+}
+
+impl MockServer {
+    /// Start a server that executes `script` for the requests it receives,
+    /// in order, then closes the connection.
+    pub(crate) fn start(script: Vec<Reply>) -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind mock server");
+        let port = listener.local_addr().expect("mock server address").port();
+        let handle = std::thread::spawn(move || serve(listener, script));
+        Self { port, handle, skip: 0 }
+    }
+
+    /// Like [`Self::start`], but only start listening after `delay`, as a
+    /// SUMO launched in parallel with the client would.
+    pub(crate) fn start_late(delay: Duration, script: Vec<Reply>) -> Self {
+        // Reserve a free port, then release it until the server comes up.
+        let port = TcpListener::bind(("127.0.0.1", 0))
+            .and_then(|listener| listener.local_addr())
+            .expect("pick a free port")
+            .port();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            serve(TcpListener::bind(("127.0.0.1", port)).expect("bind mock server"), script)
+        });
+        Self { port, handle, skip: 0 }
+    }
+
+    /// Port the server listens on.
+    pub(crate) fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Wait until the script has run and return the payloads of the
+    /// requests received, in order.
+    pub(crate) fn requests(self) -> Vec<Vec<u8>> {
+        let requests = self.handle.join().expect("mock server panicked");
+        requests.into_iter().skip(self.skip).collect()
+    }
+}
+
+/// Accept one connection on `listener` and run `script` on it, returning
+/// the requests received.
+fn serve(listener: TcpListener, script: Vec<Reply>) -> Vec<Vec<u8>> {
+    let (mut stream, _) = listener.accept().expect("accept mock client");
+    let mut requests = Vec::new();
+    for step in script {
+        if let Reply::Reset = step {
+            // Wait for the request but leave it unread: closing a
+            // socket with pending input sends RST instead of FIN.
+            let _ = stream.peek(&mut [0u8; 1]);
+            break;
+        }
+        let Some(request) = read_frame(&mut stream) else { break };
+        requests.push(request);
+        match step {
+            Reply::Message(payload) => write_frame(&mut stream, &payload),
+            Reply::Delayed(delay, payload) => {
+                std::thread::sleep(delay);
+                write_frame(&mut stream, &payload);
+            }
+            Reply::Silence => {
+                let _ = stream.read_to_end(&mut Vec::new());
+                break;
+            }
+            Reply::Close | Reply::Reset => break,
+        }
+    }
+    requests
+}
+
+/// Connect a [`TraciClient`] to a [`MockServer`] running `script`. The
+/// version handshake done by `connect` is answered first and left out of
+/// [`MockServer::requests`].
+pub(crate) fn connect(script: Vec<Reply>) -> (TraciClient, MockServer) {
+    let mut full = vec![version_reply(21, "SUMO mock").into()];
+    full.extend(script);
+    let mut server = MockServer::start(full);
+    server.skip = 1;
+    let client = TraciClient::connect("127.0.0.1", server.port()).expect("connect to mock server");
+    (client, server)
+}
+
+/// Like [`connect`] for a script that only sends `replies`.
+pub(crate) fn connect_replies(replies: Vec<Storage>) -> (TraciClient, MockServer) {
+    connect(replies.into_iter().map(Reply::from).collect())
+}
+
+fn read_frame(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).ok()?;
+    let mut payload = vec![0u8; u32::from_be_bytes(header) as usize - 4];
+    stream.read_exact(&mut payload).ok()?;
+    Some(payload)
+}
+
+fn write_frame(stream: &mut TcpStream, payload: &Storage) {
+    let total = (payload.len() + 4) as u32;
+    let _ = stream.write_all(&total.to_be_bytes());
+    let _ = stream.write_all(payload.as_bytes());
+}
+
+// ============================================================================
+// Reply builders
+// ============================================================================
+
+/// Append a status response for `cmd`.
+pub(crate) fn write_status(out: &mut Storage, cmd: u8, result: u8, msg: &str) {
+    out.write_u8((1 + 1 + 1 + 4 + msg.len()) as u8);
+    out.write_u8(cmd);
+    out.write_u8(result);
+    out.write_string(msg);
+}
+
+/// Append a command response: a length field (extended if needed), `cmd`
+/// and `body`.
+pub(crate) fn write_response(out: &mut Storage, cmd: u8, body: &Storage) {
+    let length = 1 + 1 + body.len();
+    if length <= 255 {
+        out.write_u8(length as u8);
+    } else {
+        out.write_u8(0);
+        out.write_i32((length + 4) as i32);
+    }
+    out.write_u8(cmd);
+    out.write_packet(body.as_bytes());
+}
+
+/// Append the response to a GET of `var` of `id`; `value` holds the type
+/// tag and the value.
+pub(crate) fn write_get_response(out: &mut Storage, cmd_get: u8, var: u8, id: &str, value: &Storage) {
+    let mut body = Storage::new();
+    body.write_u8(var);
+    body.write_string(id);
+    body.write_packet(value.as_bytes());
+    write_response(out, cmd_get + 0x10, &body);
+}
+
+/// A successful status reply, as sent for a SET or another plain command.
+pub(crate) fn status(cmd: u8) -> Storage {
+    let mut out = Storage::new();
+    write_status(&mut out, cmd, RTYPE_OK, "");
+    out
+}
+
+/// A failed status reply carrying `msg`.
+pub(crate) fn error_status(cmd: u8, msg: &str) -> Storage {
+    let mut out = Storage::new();
+    write_status(&mut out, cmd, RTYPE_ERR, msg);
+    out
+}
+
+/// The full reply to a GET of `var` of `id`.
+pub(crate) fn get_reply(cmd_get: u8, var: u8, id: &str, value: Storage) -> Storage {
+    let mut out = status(cmd_get);
+    write_get_response(&mut out, cmd_get, var, id, &value);
+    out
+}
+
+/// The reply to a batch of GETs, one `(cmd_get, var, id, value)` per
+/// command, in request order.
+pub(crate) fn batch_get_reply(responses: &[(u8, u8, &str, Storage)]) -> Storage {
+    let mut out = Storage::new();
+    for (cmd_get, var, id, value) in responses {
+        write_status(&mut out, *cmd_get, RTYPE_OK, "");
+        write_get_response(&mut out, *cmd_get, *var, id, value);
+    }
+    out
+}
+
+/// The reply to `CMD_GETVERSION`.
+pub(crate) fn version_reply(api: i32, sumo: &str) -> Storage {
+    let mut out = status(CMD_GETVERSION);
+    let mut body = Storage::new();
+    body.write_i32(api);
+    body.write_string(sumo);
+    write_response(&mut out, CMD_GETVERSION, &body);
+    out
+}
+
+/// The reply to `CMD_SIMSTEP` carrying the given subscription responses,
+/// each built with [`subscription`] or [`write_response`].
+pub(crate) fn step_reply(subscriptions: &[Storage]) -> Storage {
+    let mut out = status(CMD_SIMSTEP);
+    out.write_i32(subscriptions.len() as i32);
+    for sub in subscriptions {
+        out.write_packet(sub.as_bytes());
+    }
+    out
+}
+
+/// A variable subscription response for `id`; each variable's value holds
+/// its type tag.
+pub(crate) fn subscription(response_cmd: u8, id: &str, vars: &[(u8, Storage)]) -> Storage {
+    let mut body = Storage::new();
+    body.write_string(id);
+    body.write_u8(vars.len() as u8);
+    for (var, value) in vars {
+        body.write_u8(*var);
+        body.write_u8(RTYPE_OK);
+        body.write_packet(value.as_bytes());
+    }
+    let mut out = Storage::new();
+    write_response(&mut out, response_cmd, &body);
+    out
+}
+
+// ============================================================================
+// Value builders (type tag + value)
+// ============================================================================
+
+pub(crate) fn double(v: f64) -> Storage {
+    let mut s = Storage::new();
+    s.write_u8(TYPE_DOUBLE);
+    s.write_f64(v);
+    s
+}
+
+pub(crate) fn int(v: i32) -> Storage {
+    let mut s = Storage::new();
+    s.write_u8(TYPE_INTEGER);
+    s.write_i32(v);
+    s
+}
+
+pub(crate) fn string(v: &str) -> Storage {
+    let mut s = Storage::new();
+    s.write_u8(TYPE_STRING);
+    s.write_string(v);
+    s
+}
+
+pub(crate) fn string_list(v: &[&str]) -> Storage {
+    let mut s = Storage::new();
+    s.write_u8(TYPE_STRINGLIST);
+    s.write_string_list(&v.iter().map(|x| x.to_string()).collect::<Vec<_>>());
+    s
+}
+
+pub(crate) fn pos_2d(x: f64, y: f64) -> Storage {
+    let mut s = Storage::new();
+    s.write_u8(POSITION_2D);
+    s.write_f64(x);
+    s.write_f64(y);
+    s
+}
+
+/// A stage as SUMO's 13-component compound, e.g. a `VAR_STAGE` value.
+pub(crate) fn stage(stage: &TraciStage) -> Storage {
+    let mut s = Storage::new();
+    s.begin_compound(13);
+    s.write_u8(TYPE_INTEGER);
+    s.write_i32(stage.type_);
+    for text in [&stage.v_type, &stage.line, &stage.dest_stop] {
+        s.write_u8(TYPE_STRING);
+        s.write_string(text);
+    }
+    s.write_u8(TYPE_STRINGLIST);
+    s.write_string_list(&stage.edges);
+    for v in [stage.travel_time, stage.cost, stage.length] {
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(v);
+    }
+    s.write_u8(TYPE_STRING);
+    s.write_string(&stage.intended);
+    for v in [stage.depart, stage.depart_pos, stage.arrival_pos] {
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(v);
+    }
+    s.write_u8(TYPE_STRING);
+    s.write_string(&stage.description);
+    s
+}
+
+// ============================================================================
+// Expected requests
+// ============================================================================
+
+/// The payload a client sends for a GET or SET built with `create_command`.
+pub(crate) fn command(cmd: u8, var: u8, id: &str, add: Option<&Storage>) -> Vec<u8> {
+    let mut out = Storage::new();
+    TraciClient::write_command(&mut out, cmd, var, id, add);
+    out.as_bytes().to_vec()
+}
+
+/// The payload a client sends for a variable subscription.
+pub(crate) fn subscribe(dom_id: u8, id: &str, begin: f64, end: f64, vars: &[u8]) -> Vec<u8> {
+    let mut out = Storage::new();
+    TraciClient::write_subscribe_command(&mut out, dom_id, id, begin, end, None, vars);
+    out.as_bytes().to_vec()
+}
+
+/// The payload a client sends for a context subscription of `domain`
+/// objects within `range` of `id`.
+pub(crate) fn subscribe_context(dom_id: u8, id: &str, begin: f64, end: f64, domain: u8, range: f64, vars: &[u8]) -> Vec<u8> {
+    let mut out = Storage::new();
+    TraciClient::write_subscribe_command(&mut out, dom_id, id, begin, end, Some((domain, range)), vars);
+    out.as_bytes().to_vec()
+}
@@ -0,0 +1,389 @@
+// SPDX-License-Identifier: EPL-2.0
+//! Local geo ↔ Cartesian conversion.
+//!
+//! SUMO converts between network coordinates and lon/lat with the network's
+//! projection (`projParameter`) and offset (`netOffset`):
+//!
+//! ```text
+//! x, y = project(lon, lat) + netOffset
+//! ```
+//!
+//! [`CoordinateConverter`] fetches both once and then converts points without
+//! a TraCI round trip. Only UTM projections are evaluated locally; for any
+//! other projection, or when the parameters cannot be read, every conversion
+//! falls back to `SimulationScope::convert_geo`.
+
+use crate::{
+    client::TraciClient,
+    constants::*,
+    error::TraciError,
+    storage::Storage,
+    types::TraciPosition,
+};
+
+// WGS84 ellipsoid and UTM scale factor.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// Universal Transverse Mercator zone on the WGS84 ellipsoid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Utm {
+    zone: i32,
+    south: bool,
+}
+
+impl Utm {
+    /// Parse a PROJ.4 string such as `+proj=utm +zone=32 +ellps=WGS84 +datum=WGS84 +units=m +no_defs`.
+    /// Returns `None` for anything that is not a WGS84 UTM projection.
+    fn parse(proj: &str) -> Option<Self> {
+        let mut is_utm = false;
+        let mut zone = None;
+        let mut south = false;
+        for token in proj.split_whitespace() {
+            let token = token.trim_start_matches('+');
+            match token.split_once('=') {
+                Some(("proj", "utm")) => is_utm = true,
+                Some(("zone", z)) => zone = z.parse::<i32>().ok(),
+                Some(("ellps", e)) | Some(("datum", e)) if e != "WGS84" => return None,
+                None if token == "south" => south = true,
+                _ => {}
+            }
+        }
+        match zone {
+            Some(zone) if is_utm && (1..=60).contains(&zone) => Some(Self { zone, south }),
+            _ => None,
+        }
+    }
+
+    fn central_meridian(&self) -> f64 {
+        ((self.zone * 6 - 183) as f64).to_radians()
+    }
+
+    /// Forward projection: lon/lat in degrees → easting/northing in metres.
+    fn forward(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let ep2 = e2 / (1.0 - e2);
+        let phi = lat.to_radians();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let n = WGS84_A / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+        let t = phi.tan().powi(2);
+        let c = ep2 * cos_phi * cos_phi;
+        let a = (lon.to_radians() - self.central_meridian()) * cos_phi;
+        let m = meridian_arc(phi, e2);
+
+        let x = UTM_K0
+            * n
+            * (a + (1.0 - t + c) * a.powi(3) / 6.0
+                + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+            + UTM_FALSE_EASTING;
+        let mut y = UTM_K0
+            * (m + n
+                * phi.tan()
+                * (a * a / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+        if self.south {
+            y += UTM_FALSE_NORTHING_SOUTH;
+        }
+        (x, y)
+    }
+
+    /// Inverse projection: easting/northing in metres → lon/lat in degrees.
+    fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let ep2 = e2 / (1.0 - e2);
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+        let northing = if self.south { y - UTM_FALSE_NORTHING_SOUTH } else { y };
+        let m = northing / UTM_K0;
+        let mu = m / (WGS84_A * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2.powi(3) / 256.0));
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+            + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+        let (sin_phi1, cos_phi1) = phi1.sin_cos();
+        let n1 = WGS84_A / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+        let t1 = phi1.tan().powi(2);
+        let c1 = ep2 * cos_phi1 * cos_phi1;
+        let r1 = WGS84_A * (1.0 - e2) / (1.0 - e2 * sin_phi1 * sin_phi1).powf(1.5);
+        let d = (x - UTM_FALSE_EASTING) / (n1 * UTM_K0);
+
+        let phi = phi1
+            - (n1 * phi1.tan() / r1)
+                * (d * d / 2.0
+                    - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1)
+                        * d.powi(6)
+                        / 720.0);
+        let lambda = self.central_meridian()
+            + (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+                + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1) * d.powi(5)
+                    / 120.0)
+                / cos_phi1;
+        (lambda.to_degrees(), phi.to_degrees())
+    }
+}
+
+/// Length of the meridian arc from the equator to latitude `phi` (radians).
+fn meridian_arc(phi: f64, e2: f64) -> f64 {
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
+
+// ============================================================================
+// CoordinateConverter
+// ============================================================================
+
+/// Converts between SUMO network coordinates and lon/lat without a TraCI
+/// round trip per point.
+///
+/// Build one with [`CoordinateConverter::fetch`] after connecting, then reuse
+/// it for every conversion. When the network projection is unknown or cannot
+/// be evaluated locally the conversion methods transparently ask SUMO
+/// instead. Networks SUMO reports as unprojected cannot be converted at all.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateConverter {
+    net_offset: (f64, f64),
+    projection: Option<Utm>,
+    // false only for networks SUMO reports as unprojected (`projParameter` "!")
+    geo_referenced: bool,
+}
+
+impl CoordinateConverter {
+    /// Build a converter from a PROJ.4 `proj_parameter` string and the network
+    /// offset, as found in the `<location>` element of a `.net.xml` file.
+    pub fn new(proj_parameter: &str, net_offset: (f64, f64)) -> Self {
+        Self {
+            net_offset,
+            projection: Utm::parse(proj_parameter),
+            geo_referenced: has_projection(proj_parameter),
+        }
+    }
+
+    /// Fetch the `projParameter` and `netOffset` simulation parameters once
+    /// and build a converter from them.
+    ///
+    /// If either parameter is unknown to SUMO, or the projection cannot be
+    /// evaluated locally, the converter forwards every conversion to SUMO.
+    pub fn fetch(client: &mut TraciClient) -> Result<Self, TraciError> {
+        let proj = match get_sim_parameter(client, "projParameter") {
+            Ok(p) => p,
+            Err(TraciError::SimulationError { .. }) | Err(TraciError::NotImplemented(_)) => String::new(),
+            Err(e) => return Err(e),
+        };
+        let offset = match get_sim_parameter(client, "netOffset") {
+            Ok(o) => parse_offset(&o),
+            Err(TraciError::SimulationError { .. }) | Err(TraciError::NotImplemented(_)) => None,
+            Err(e) => return Err(e),
+        };
+        match offset {
+            Some(net_offset) => Ok(Self::new(&proj, net_offset)),
+            None => Ok(Self { net_offset: (0.0, 0.0), projection: None, geo_referenced: has_projection(&proj) }),
+        }
+    }
+
+    /// Return `true` if conversions are computed locally.
+    pub fn is_local(&self) -> bool {
+        self.projection.is_some()
+    }
+
+    /// Return `false` if SUMO reports the network as unprojected, i.e. lon/lat
+    /// conversions are meaningless.
+    pub fn is_geo_referenced(&self) -> bool {
+        self.geo_referenced
+    }
+
+    /// Convert network coordinates to lon/lat locally, or `None` if the
+    /// projection is not supported.
+    pub fn xy_to_geo(&self, x: f64, y: f64) -> Option<TraciPosition> {
+        let utm = self.projection?;
+        let (lon, lat) = utm.inverse(x - self.net_offset.0, y - self.net_offset.1);
+        Some(TraciPosition::new_2d(lon, lat))
+    }
+
+    /// Convert lon/lat to network coordinates locally, or `None` if the
+    /// projection is not supported.
+    pub fn geo_to_xy(&self, lon: f64, lat: f64) -> Option<TraciPosition> {
+        let utm = self.projection?;
+        let (x, y) = utm.forward(lon, lat);
+        Some(TraciPosition::new_2d(x + self.net_offset.0, y + self.net_offset.1))
+    }
+
+    /// Convert network coordinates to lon/lat, asking SUMO only if the
+    /// projection cannot be evaluated locally.
+    ///
+    /// Fails with [`TraciError::Protocol`] if SUMO reports the network as unprojected.
+    pub fn convert_to_geo(&self, client: &mut TraciClient, x: f64, y: f64) -> Result<TraciPosition, TraciError> {
+        self.require_projection()?;
+        match self.xy_to_geo(x, y) {
+            Some(p) => Ok(p),
+            None => crate::scopes::simulation::convert_geo(client, x, y, false),
+        }
+    }
+
+    /// Convert lon/lat to network coordinates, asking SUMO only if the
+    /// projection cannot be evaluated locally.
+    ///
+    /// Fails with [`TraciError::Protocol`] if SUMO reports the network as unprojected.
+    pub fn convert_from_geo(&self, client: &mut TraciClient, lon: f64, lat: f64) -> Result<TraciPosition, TraciError> {
+        self.require_projection()?;
+        match self.geo_to_xy(lon, lat) {
+            Some(p) => Ok(p),
+            None => crate::scopes::simulation::convert_geo(client, lon, lat, true),
+        }
+    }
+
+    // SUMO answers conversions on an unprojected network with the unchanged
+    // input, so refuse them instead of returning bogus lon/lat.
+    fn require_projection(&self) -> Result<(), TraciError> {
+        if self.geo_referenced {
+            Ok(())
+        } else {
+            Err(TraciError::Protocol("The network has no geo-projection".into()))
+        }
+    }
+}
+
+/// Return `true` unless `proj` is SUMO's "no projection" marker `!`. An
+/// empty (unknown) parameter is left for SUMO to decide.
+fn has_projection(proj: &str) -> bool {
+    proj.trim() != "!"
+}
+
+fn get_sim_parameter(client: &mut TraciClient, key: &str) -> Result<String, TraciError> {
+    let mut add = Storage::new();
+    add.write_u8(TYPE_STRING);
+    add.write_string(key);
+    client.create_command(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", Some(&add));
+    client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRING))?;
+    client.read_string_from_input()
+}
+
+/// Parse a `"x,y"` offset string.
+fn parse_offset(s: &str) -> Option<(f64, f64)> {
+    let (x, y) = s.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    /// The reply to a `POSITION_CONVERSION` to lon/lat.
+    fn lon_lat_reply(lon: f64, lat: f64) -> Storage {
+        let mut value = Storage::new();
+        value.write_u8(POSITION_LON_LAT);
+        value.write_f64(lon);
+        value.write_f64(lat);
+        mock::get_reply(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", value)
+    }
+
+    #[test]
+    fn only_the_bang_marker_means_unprojected() {
+        assert!(!has_projection("!"));
+        assert!(!has_projection(" ! "));
+        assert!(has_projection(""));
+        assert!(has_projection("+proj=merc"));
+        assert!(!CoordinateConverter::new("!", (0.0, 0.0)).is_geo_referenced());
+        assert!(CoordinateConverter::new("", (0.0, 0.0)).is_geo_referenced());
+    }
+
+    #[test]
+    fn an_unprojected_network_refuses_conversions() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("!")),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("0.00,0.00")),
+        ]);
+        let converter = CoordinateConverter::fetch(&mut client).unwrap();
+        let err = converter.convert_to_geo(&mut client, 10.0, 20.0).unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "unexpected error {err:?}");
+        drop(client);
+        assert_eq!(server.requests().len(), 2, "no conversion sent");
+    }
+
+    #[test]
+    fn failed_parameter_lookups_fall_back_to_the_server() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::error_status(CMD_GET_SIM_VARIABLE, "Invalid parameter 'projParameter'"),
+            mock::error_status(CMD_GET_SIM_VARIABLE, "Invalid parameter 'netOffset'"),
+            lon_lat_reply(2.11, 41.39),
+        ]);
+        let converter = CoordinateConverter::fetch(&mut client).unwrap();
+        assert!(converter.is_geo_referenced());
+        assert!(!converter.is_local());
+        let geo = converter.convert_to_geo(&mut client, 10.0, 20.0).unwrap();
+        assert_eq!(geo, TraciPosition::new_2d(2.11, 41.39));
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2][1..3], [CMD_GET_SIM_VARIABLE, POSITION_CONVERSION]);
+    }
+
+    #[test]
+    fn an_empty_projection_falls_back_to_the_server() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("")),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("0.00,0.00")),
+            lon_lat_reply(2.11, 41.39),
+        ]);
+        let converter = CoordinateConverter::fetch(&mut client).unwrap();
+        assert_eq!(converter.convert_to_geo(&mut client, 10.0, 20.0).unwrap(), TraciPosition::new_2d(2.11, 41.39));
+        drop(client);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    /// lon, lat and the network x, y of the same point for zone 31 and a
+    /// `netOffset` of -424000,-4576000. The x, y come from Karney's
+    /// sixth-order Krüger series, which PROJ evaluates for `+proj=utm` when
+    /// SUMO answers `convert_geo`.
+    const BARCELONA: [(f64, f64, f64, f64); 4] = [
+        (2.1734, 41.3851, 6887.5643, 5837.8534),
+        (2.0, 41.3, -7719.6002, -3457.0045),
+        (2.3, 41.5, 17575.8807, 18500.7128),
+        // 2.5° west of the central meridian, near the edge of the zone.
+        (0.5, 41.0, -134263.2417, -34232.4138),
+    ];
+
+    fn assert_close(actual: TraciPosition, expected: (f64, f64), tolerance: f64) {
+        assert!(
+            (actual.x - expected.0).abs() < tolerance && (actual.y - expected.1).abs() < tolerance,
+            "{actual:?} is not within {tolerance} of {expected:?}"
+        );
+    }
+
+    #[test]
+    fn utm_conversions_match_the_projection_of_the_network() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(
+                CMD_GET_SIM_VARIABLE,
+                VAR_PARAMETER,
+                "",
+                mock::string("+proj=utm +zone=31 +ellps=WGS84 +datum=WGS84 +units=m +no_defs"),
+            ),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("-424000.00,-4576000.00")),
+        ]);
+        let converter = CoordinateConverter::fetch(&mut client).unwrap();
+        assert!(converter.is_local());
+        for (lon, lat, x, y) in BARCELONA {
+            assert_close(converter.xy_to_geo(x, y).unwrap(), (lon, lat), 1e-6);
+            assert_close(converter.geo_to_xy(lon, lat).unwrap(), (x, y), 1e-2);
+            assert_close(converter.convert_to_geo(&mut client, x, y).unwrap(), (lon, lat), 1e-6);
+            assert_close(converter.convert_from_geo(&mut client, lon, lat).unwrap(), (x, y), 1e-2);
+
+            let there = converter.geo_to_xy(lon, lat).unwrap();
+            assert_close(converter.xy_to_geo(there.x, there.y).unwrap(), (lon, lat), 1e-6);
+        }
+        drop(client);
+        assert_eq!(server.requests().len(), 2, "no conversion sent");
+    }
+}
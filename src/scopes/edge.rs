@@ -6,7 +6,7 @@ use crate::{
     constants::*,
     error::TraciError,
     storage::Storage,
-    types::{ContextSubscriptionResults, SubscriptionResults},
+    types::{ContextSubscriptionResults, Emissions, SubscriptionResults},
 };
 use std::f64;
 
@@ -36,12 +36,14 @@ impl EdgeScope {
         edge_id: &str,
         key: &str,
     ) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_PARAMETER, edge_id, Some(&add));
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("edge.get_parameter", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_PARAMETER, edge_id, Some(&add));
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     /// Set a generic key/value parameter for an edge.
@@ -52,16 +54,17 @@ impl EdgeScope {
         key: &str,
         value: &str,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_EDGE_VARIABLE, VAR_PARAMETER, edge_id, Some(&add));
-        client.process_set(CMD_SET_EDGE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("edge.set_parameter", edge_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_EDGE_VARIABLE, VAR_PARAMETER, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -70,16 +73,20 @@ impl EdgeScope {
 
     /// Return the list of all edge IDs in the network.
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("edge.get_id_list", {
+            client.create_command(CMD_GET_EDGE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     /// Return the total number of edges in the network.
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("edge.get_id_count", {
+            client.create_command(CMD_GET_EDGE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -93,12 +100,14 @@ impl EdgeScope {
         edge_id: &str,
         time: f64,
     ) -> Result<f64, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(time);
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge_id, Some(&add));
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_adapted_traveltime", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(time);
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge_id, Some(&add));
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Return the effort value of an edge at a given simulation time.
@@ -108,114 +117,185 @@ impl EdgeScope {
         edge_id: &str,
         time: f64,
     ) -> Result<f64, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(time);
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_EDGE_EFFORT, edge_id, Some(&add));
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_effort", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(time);
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_EDGE_EFFORT, edge_id, Some(&add));
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_co2_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CO2EMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_co2_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CO2EMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_co_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_COEMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_co_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_COEMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_hc_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_HCEMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_hc_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_HCEMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_pmx_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_PMXEMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_pmx_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_PMXEMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_nox_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NOXEMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_nox_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NOXEMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_fuel_consumption(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_FUELCONSUMPTION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_fuel_consumption", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_FUELCONSUMPTION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_noise_emission(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NOISEEMISSION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_noise_emission", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NOISEEMISSION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_electricity_consumption(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_electricity_consumption", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return all pollutant and consumption values in one round trip.
+    pub fn get_all_emissions(&self, client: &mut TraciClient, edge_id: &str) -> Result<Emissions, TraciError> {
+        crate::with_context!("edge.get_all_emissions", edge_id, {
+            crate::scopes::helpers::batch_get_emissions(client, CMD_GET_EDGE_VARIABLE, edge_id)
+        })
     }
 
     pub fn get_last_step_mean_speed(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_MEAN_SPEED, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_last_step_mean_speed", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_MEAN_SPEED, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_occupancy(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_OCCUPANCY, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_last_step_occupancy", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_OCCUPANCY, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_length(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_LENGTH, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_last_step_length", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_LENGTH, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
+    /// Return the travel time estimated from the last step's mean speed.
+    ///
+    /// This is SUMO's only "mean travel time" of an edge, so there is no
+    /// separate `get_last_step_mean_travel_time`.
+    #[doc(alias = "get_last_step_mean_travel_time")]
     pub fn get_traveltime(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("edge.get_traveltime", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the sum of the waiting times of all vehicles on the edge.
+    pub fn get_waiting_time(&self, client: &mut TraciClient, edge_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("edge.get_waiting_time", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_WAITING_TIME, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_number(&self, client: &mut TraciClient, edge_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_NUMBER, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("edge.get_last_step_vehicle_number", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_NUMBER, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_last_step_halting_number(&self, client: &mut TraciClient, edge_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_HALTING_NUMBER, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("edge.get_last_step_halting_number", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_HALTING_NUMBER, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_ids(&self, client: &mut TraciClient, edge_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("edge.get_last_step_vehicle_ids", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
+    /// Return the number of lanes of the edge.
+    ///
+    /// The edge domain has no separate lane-count variable: SUMO answers
+    /// `VAR_LANE_INDEX` (0x52) on an edge with its lane count, exactly as
+    /// `traci.edge.getLaneNumber` does.
     pub fn get_lane_number(&self, client: &mut TraciClient, edge_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_LANE_INDEX, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("edge.get_lane_number", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_LANE_INDEX, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the ids of the edge's lanes, rightmost first. SUMO names lanes
+    /// `<edge_id>_<index>`, so only the lane count is queried.
+    pub fn get_lanes(&self, client: &mut TraciClient, edge_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("edge.get_lanes", edge_id, {
+            let n = crate::scope_get_int!(client, CMD_GET_EDGE_VARIABLE, VAR_LANE_INDEX, edge_id)?;
+            Ok((0..n).map(|i| format!("{edge_id}_{i}")).collect())
+        })
     }
 
     pub fn get_street_name(&self, client: &mut TraciClient, edge_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NAME, edge_id, None);
-        client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("edge.get_street_name", edge_id, {
+            client.create_command(CMD_GET_EDGE_VARIABLE, VAR_NAME, edge_id, None);
+            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -233,22 +313,24 @@ impl EdgeScope {
         begin_seconds: f64,
         end_seconds: f64,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        if end_seconds < f64::MAX {
-            add.write_i32(3);
+        crate::with_context!("edge.adapt_traveltime", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COMPOUND);
+            if end_seconds < f64::MAX {
+                add.write_i32(3);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(begin_seconds);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(end_seconds);
+            } else {
+                add.write_i32(1);
+            }
             add.write_u8(TYPE_DOUBLE);
-            add.write_f64(begin_seconds);
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(end_seconds);
-        } else {
-            add.write_i32(1);
-        }
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(time);
-        client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge_id, Some(&add));
-        client.process_set(CMD_SET_EDGE_VARIABLE)?;
-        Ok(())
+            add.write_f64(time);
+            client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Set an effort value for an edge, optionally bounded by [begin_seconds, end_seconds).
@@ -260,22 +342,24 @@ impl EdgeScope {
         begin_seconds: f64,
         end_seconds: f64,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        if end_seconds < f64::MAX {
-            add.write_i32(3);
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(begin_seconds);
+        crate::with_context!("edge.set_effort", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COMPOUND);
+            if end_seconds < f64::MAX {
+                add.write_i32(3);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(begin_seconds);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(end_seconds);
+            } else {
+                add.write_i32(1);
+            }
             add.write_u8(TYPE_DOUBLE);
-            add.write_f64(end_seconds);
-        } else {
-            add.write_i32(1);
-        }
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(effort);
-        client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_EFFORT, edge_id, Some(&add));
-        client.process_set(CMD_SET_EDGE_VARIABLE)?;
-        Ok(())
+            add.write_f64(effort);
+            client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_EFFORT, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Set the maximum speed for all lanes on an edge (m/s).
@@ -285,12 +369,49 @@ impl EdgeScope {
         edge_id: &str,
         speed: f64,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        client.create_command(CMD_SET_EDGE_VARIABLE, VAR_MAXSPEED, edge_id, Some(&add));
-        client.process_set(CMD_SET_EDGE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("edge.set_max_speed", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            client.create_command(CMD_SET_EDGE_VARIABLE, VAR_MAXSPEED, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Allow only `allowed_classes` on all lanes of the edge; an empty list
+    /// allows every vehicle class.
+    pub fn set_allowed(
+        &self,
+        client: &mut TraciClient,
+        edge_id: &str,
+        allowed_classes: &[String],
+    ) -> Result<(), TraciError> {
+        crate::with_context!("edge.set_allowed", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(allowed_classes);
+            client.create_command(CMD_SET_EDGE_VARIABLE, LANE_ALLOWED, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Forbid `disallowed_classes` on all lanes of the edge.
+    pub fn set_disallowed(
+        &self,
+        client: &mut TraciClient,
+        edge_id: &str,
+        disallowed_classes: &[String],
+    ) -> Result<(), TraciError> {
+        crate::with_context!("edge.set_disallowed", edge_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(disallowed_classes);
+            client.create_command(CMD_SET_EDGE_VARIABLE, LANE_DISALLOWED, edge_id, Some(&add));
+            client.process_set(CMD_SET_EDGE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -306,10 +427,13 @@ impl EdgeScope {
         begin: f64,
         end: f64,
     ) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_EDGE_VARIABLE, edge_id, begin, end, vars)
+        crate::with_context!("edge.subscribe", edge_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_EDGE_VARIABLE, edge_id, begin, end, vars)
+        })
     }
 
     /// Subscribe to a context around the given edge.
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(
         &self,
         client: &mut TraciClient,
@@ -320,6 +444,72 @@ impl EdgeScope {
         begin: f64,
         end: f64,
     ) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_EDGE_CONTEXT, edge_id, begin, end, domain, range, vars)
+        crate::with_context!("edge.subscribe_context", edge_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_EDGE_CONTEXT, edge_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use crate::scopes::helpers::EMISSION_VARS;
+
+    #[test]
+    fn get_all_emissions_reads_one_batched_response() {
+        let responses: Vec<_> = EMISSION_VARS
+            .iter()
+            .enumerate()
+            .map(|(i, &var)| (CMD_GET_EDGE_VARIABLE, var, "e0", mock::double(i as f64 + 0.5)))
+            .collect();
+        let (mut client, server) = mock::connect_replies(vec![mock::batch_get_reply(&responses)]);
+
+        let emissions = EdgeScope::default().get_all_emissions(&mut client, "e0").unwrap();
+        assert_eq!(
+            emissions,
+            Emissions { co2: 0.5, co: 1.5, hc: 2.5, pmx: 3.5, nox: 4.5, fuel: 5.5, noise: 6.5 }
+        );
+
+        drop(client);
+        let expected: Vec<u8> = EMISSION_VARS
+            .iter()
+            .flat_map(|&var| mock::command(CMD_GET_EDGE_VARIABLE, var, "e0", None))
+            .collect();
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn get_lane_number_matches_a_captured_exchange() {
+        // The exchange of `traci.edge.getLaneNumber("E0")` for a three-lane
+        // edge, byte for byte as on the wire minus the message length.
+        let request = vec![0x09, 0xaa, 0x52, 0x00, 0x00, 0x00, 0x02, b'E', b'0'];
+        let reply = vec![
+            0x07, 0xaa, 0x00, 0x00, 0x00, 0x00, 0x00, // status OK
+            0x0e, 0xba, 0x52, 0x00, 0x00, 0x00, 0x02, b'E', b'0', 0x09, 0x00, 0x00, 0x00, 0x03,
+        ];
+        let (mut client, server) = mock::connect_replies(vec![Storage::from_bytes(reply)]);
+        assert_eq!(EdgeScope::default().get_lane_number(&mut client, "E0").unwrap(), 3);
+        drop(client);
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn waiting_time_and_travel_time_query_their_own_variables() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_EDGE_VARIABLE, VAR_WAITING_TIME, "E0", mock::double(42.5)),
+            mock::get_reply(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, "E0", mock::double(18.25)),
+        ]);
+        let scope = EdgeScope::default();
+        assert_eq!(scope.get_waiting_time(&mut client, "E0").unwrap(), 42.5);
+        assert_eq!(scope.get_traveltime(&mut client, "E0").unwrap(), 18.25);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_GET_EDGE_VARIABLE, VAR_WAITING_TIME, "E0", None),
+                mock::command(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, "E0", None),
+            ]
+        );
     }
 }
@@ -10,6 +10,9 @@ use crate::{
 };
 
 /// Scope for interacting with the SUMO GUI.
+///
+/// Every method addresses one view by id; the main window of `sumo-gui` is
+/// [`DEFAULT_VIEW`](crate::client::DEFAULT_VIEW) (`"View #0"`).
 #[derive(Debug, Default)]
 pub struct GuiScope {
     pub subscription_results: SubscriptionResults,
@@ -20,41 +23,67 @@ impl GuiScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_GUI_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("gui.get_id_list", {
+            client.create_command(CMD_GET_GUI_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
     // Getters
     // -----------------------------------------------------------------------
 
+    /// Return `true` if the server is `sumo-gui`.
+    ///
+    /// Probes the view list; the command-line `sumo` answers GUI commands
+    /// with "not implemented", which maps to `false`.
+    pub fn has_gui(&self, client: &mut TraciClient) -> Result<bool, TraciError> {
+        crate::with_context!("gui.has_gui", {
+            client.create_command(CMD_GET_GUI_VARIABLE, TRACI_ID_LIST, "", None);
+            match client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_STRINGLIST)) {
+                Ok(_) => Ok(true),
+                Err(TraciError::NotImplemented(_)) => Ok(false),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
     /// Return the current zoom level of a GUI view.
     pub fn get_zoom(&self, client: &mut TraciClient, view_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_ZOOM, view_id, None);
-        client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("gui.get_zoom", view_id, {
+            client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_ZOOM, view_id, None);
+            client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Return the 2-D offset (centre) of the given GUI view.
     pub fn get_offset(&self, client: &mut TraciClient, view_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_OFFSET, view_id, None);
-        client.process_get(CMD_GET_GUI_VARIABLE, Some(POSITION_2D))?;
-        client.read_pos_2d_from_input()
+        crate::with_context!("gui.get_offset", view_id, {
+            client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_OFFSET, view_id, None);
+            client.process_get(CMD_GET_GUI_VARIABLE, Some(POSITION_2D))?;
+            client.read_pos_2d_from_input()
+        })
     }
 
     /// Return the name of the colour scheme currently active in a view.
     pub fn get_schema(&self, client: &mut TraciClient, view_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_SCHEMA, view_id, None);
-        client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("gui.get_schema", view_id, {
+            client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_SCHEMA, view_id, None);
+            client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
-    /// Return the bounding box of a GUI view as a 4-point polygon.
+    /// Return the visible area of a GUI view as its lower-left and
+    /// upper-right corners.
     pub fn get_boundary(&self, client: &mut TraciClient, view_id: &str) -> Result<Vec<TraciPosition>, TraciError> {
-        client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, view_id, None);
-        client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_POLYGON))?;
-        client.read_polygon_from_input()
+        crate::with_context!("gui.get_boundary", view_id, {
+            client.create_command(CMD_GET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, view_id, None);
+            client.process_get(CMD_GET_GUI_VARIABLE, Some(TYPE_POLYGON))?;
+            client.read_polygon_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -62,34 +91,41 @@ impl GuiScope {
     // -----------------------------------------------------------------------
 
     pub fn set_zoom(&self, client: &mut TraciClient, view_id: &str, zoom: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(zoom);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_ZOOM, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.set_zoom", view_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(zoom);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_ZOOM, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_offset(&self, client: &mut TraciClient, view_id: &str, x: f64, y: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(POSITION_2D);
-        add.write_f64(x);
-        add.write_f64(y);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_OFFSET, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.set_offset", view_id, {
+            let mut add = Storage::new();
+            add.write_u8(POSITION_2D);
+            add.write_f64(x);
+            add.write_f64(y);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_OFFSET, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_schema(&self, client: &mut TraciClient, view_id: &str, schema_name: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(schema_name);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_SCHEMA, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.set_schema", view_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(schema_name);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_SCHEMA, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    /// Set the view boundary with (xmin, ymin, xmax, ymax).
+    /// Zoom and pan the view so that the rectangle (xmin, ymin)–(xmax, ymax)
+    /// is visible.
     pub fn set_boundary(
         &self,
         client: &mut TraciClient,
@@ -99,19 +135,25 @@ impl GuiScope {
         xmax: f64,
         ymax: f64,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_POLYGON);
-        add.write_u8(2);
-        add.write_f64(xmin);
-        add.write_f64(ymin);
-        add.write_f64(xmax);
-        add.write_f64(ymax);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.set_boundary", view_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_POLYGON);
+            add.write_u8(2);
+            add.write_f64(xmin);
+            add.write_f64(ymin);
+            add.write_f64(xmax);
+            add.write_f64(ymax);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    /// Save a screenshot from the given view.
+    /// Save a screenshot of the given view to `filename`; the image format
+    /// follows the file extension.
+    ///
+    /// A `width` or `height` of `-1` keeps the current window size. SUMO
+    /// writes the file at the end of the current step.
     pub fn screenshot(
         &self,
         client: &mut TraciClient,
@@ -120,35 +162,132 @@ impl GuiScope {
         width: i32,
         height: i32,
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(TYPE_STRING);
-        add.write_string(filename);
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(width);
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(height);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_SCREENSHOT, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.screenshot", view_id, {
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(TYPE_STRING);
+            add.write_string(filename);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(width);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(height);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_SCREENSHOT, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    /// Set the view to track a vehicle.
+    /// Make the view follow a vehicle; an empty `veh_id` stops tracking.
     pub fn track_vehicle(&self, client: &mut TraciClient, view_id: &str, veh_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(veh_id);
-        client.create_command(CMD_SET_GUI_VARIABLE, VAR_VIEW_SCHEMA, view_id, Some(&add));
-        client.process_set(CMD_SET_GUI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("gui.track_vehicle", view_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(veh_id);
+            client.create_command(CMD_SET_GUI_VARIABLE, VAR_TRACK_VEHICLE, view_id, Some(&add));
+            client.process_set(CMD_SET_GUI_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Stop following the vehicle tracked by the view.
+    pub fn stop_tracking(&self, client: &mut TraciClient, view_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("gui.stop_tracking", view_id, {
+            crate::scope_set_string!(client, CMD_SET_GUI_VARIABLE, VAR_TRACK_VEHICLE, view_id, "")?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, view_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_GUI_VARIABLE, view_id, begin, end, vars)
+        crate::with_context!("gui.subscribe", view_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_GUI_VARIABLE, view_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, view_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_GUI_CONTEXT, view_id, begin, end, domain, range, vars)
+        crate::with_context!("gui.subscribe_context", view_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_GUI_CONTEXT, view_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn errors_name_the_scope_method_and_view() {
+        let (mut client, server) = mock::connect_replies(vec![mock::error_status(CMD_GET_GUI_VARIABLE, "View 'v1' is not known")]);
+        let err = GuiScope::default().get_zoom(&mut client, "v1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("TraCI simulation error: gui.get_zoom('v1'): Command 0x{CMD_GET_GUI_VARIABLE:02x} failed: View 'v1' is not known")
+        );
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn stop_tracking_reports_only_its_own_context() {
+        let (mut client, server) = mock::connect_replies(vec![mock::error_status(CMD_SET_GUI_VARIABLE, "View 'v1' is not known")]);
+        let err = GuiScope::default().stop_tracking(&mut client, "v1").unwrap_err();
+        match err {
+            TraciError::SimulationError { context, .. } => assert_eq!(context, "gui.stop_tracking('v1')"),
+            other => panic!("unexpected error {other:?}"),
+        }
+        drop(client);
+        let mut add = Storage::new();
+        add.write_u8(TYPE_STRING);
+        add.write_string("");
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_GUI_VARIABLE, VAR_TRACK_VEHICLE, "v1", Some(&add))]);
+    }
+
+    #[test]
+    fn set_boundary_sends_both_corners_as_a_polygon() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_GUI_VARIABLE)]);
+        GuiScope::default().set_boundary(&mut client, "View #0", -10.0, -5.0, 250.0, 120.5).unwrap();
+        drop(client);
+        let mut add = Storage::new();
+        add.write_u8(TYPE_POLYGON);
+        add.write_u8(2);
+        for v in [-10.0, -5.0, 250.0, 120.5] {
+            add.write_f64(v);
+        }
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, "View #0", Some(&add))]);
+    }
+
+    #[test]
+    fn get_boundary_reads_the_two_corners() {
+        let mut value = Storage::new();
+        value.write_u8(TYPE_POLYGON);
+        value.write_u8(2);
+        for v in [-10.0, -5.0, 250.0, 120.5] {
+            value.write_f64(v);
+        }
+        let (mut client, server) =
+            mock::connect_replies(vec![mock::get_reply(CMD_GET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, "View #0", value)]);
+        let corners = GuiScope::default().get_boundary(&mut client, "View #0").unwrap();
+        assert_eq!(corners, vec![TraciPosition::new_2d(-10.0, -5.0), TraciPosition::new_2d(250.0, 120.5)]);
+        drop(client);
+        assert_eq!(server.requests(), vec![mock::command(CMD_GET_GUI_VARIABLE, VAR_VIEW_BOUNDARY, "View #0", None)]);
+    }
+
+    #[test]
+    fn screenshot_sends_filename_and_size_as_a_compound() {
+        for (width, height) in [(1920, 1080), (-1, -1)] {
+            let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_GUI_VARIABLE)]);
+            GuiScope::default().screenshot(&mut client, "View #0", "frame_0001.png", width, height).unwrap();
+            drop(client);
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(TYPE_STRING);
+            add.write_string("frame_0001.png");
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(width);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(height);
+            assert_eq!(server.requests(), vec![mock::command(CMD_SET_GUI_VARIABLE, VAR_SCREENSHOT, "View #0", Some(&add))]);
+        }
     }
 }
@@ -18,7 +18,9 @@ macro_rules! scope_get_double {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_DOUBLE))?;
-        $client.read_double_from_input()
+        let value = $client.read_double_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -28,7 +30,9 @@ macro_rules! scope_get_int {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_INTEGER))?;
-        $client.read_int_from_input()
+        let value = $client.read_int_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -38,7 +42,9 @@ macro_rules! scope_get_string {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_STRING))?;
-        $client.read_string_from_input()
+        let value = $client.read_string_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -48,7 +54,9 @@ macro_rules! scope_get_string_list {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_STRINGLIST))?;
-        $client.read_string_list_from_input()
+        let value = $client.read_string_list_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -58,7 +66,9 @@ macro_rules! scope_get_double_list {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_DOUBLELIST))?;
-        $client.read_f64_list_from_input()
+        let value = $client.read_f64_list_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -68,7 +78,9 @@ macro_rules! scope_get_pos {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::POSITION_2D))?;
-        $client.read_pos_2d_from_input()
+        let value = $client.read_pos_2d_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -78,7 +90,9 @@ macro_rules! scope_get_pos3d {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::POSITION_3D))?;
-        $client.read_pos_3d_from_input()
+        let value = $client.read_pos_3d_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -88,7 +102,9 @@ macro_rules! scope_get_color {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_COLOR))?;
-        $client.read_color_from_input()
+        let value = $client.read_color_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -98,7 +114,9 @@ macro_rules! scope_get_polygon {
         let mut add: Option<&$crate::storage::Storage> = None;
         $client.create_command($cmd_get, $var, $id, add.take());
         $client.process_get($cmd_get, Some($crate::constants::TYPE_POLYGON))?;
-        $client.read_polygon_from_input()
+        let value = $client.read_polygon_from_input()?;
+        $client.finish_get()?;
+        Ok::<_, $crate::error::TraciError>(value)
     }};
 }
 
@@ -165,6 +183,30 @@ macro_rules! impl_scope_accessors {
             self.subscription_results.get(obj_id)
         }
 
+        /// Return the subscribed `var` of `obj_id` as a double, or `None` if it
+        /// is missing or of another type.
+        pub fn get_subscribed_double(&self, obj_id: &str, var: u8) -> Option<f64> {
+            self.subscription_results.get(obj_id)?.get(&var)?.as_f64()
+        }
+
+        /// Return the subscribed `var` of `obj_id` as an integer, or `None` if
+        /// it is missing or of another type.
+        pub fn get_subscribed_int(&self, obj_id: &str, var: u8) -> Option<i32> {
+            self.subscription_results.get(obj_id)?.get(&var)?.as_i32()
+        }
+
+        /// Return the subscribed `var` of `obj_id` as a string, or `None` if it
+        /// is missing or of another type.
+        pub fn get_subscribed_string(&self, obj_id: &str, var: u8) -> Option<&str> {
+            self.subscription_results.get(obj_id)?.get(&var)?.as_str()
+        }
+
+        /// Return the subscribed `var` of `obj_id` as a string list, or `None`
+        /// if it is missing or of another type.
+        pub fn get_subscribed_string_list(&self, obj_id: &str, var: u8) -> Option<&[String]> {
+            self.subscription_results.get(obj_id)?.get(&var)?.as_string_list()
+        }
+
         /// Return all context subscription results cached after the last `simulation_step`.
         pub fn get_all_context_subscription_results(
             &self,
@@ -181,3 +223,131 @@ macro_rules! impl_scope_accessors {
         }
     };
 }
+
+/// Wrap a scope method body so that any error names the failing call,
+/// e.g. `vehicle.get_speed('veh0'): Command 0xa4 failed: ...`.
+///
+/// ```ignore
+/// crate::with_context!("vehicle.get_speed", vehicle_id, { ... })
+/// crate::with_context!("vehicle.get_id_list", { ... })
+/// ```
+///
+/// A method that builds on another public scope method calls its un-wrapped
+/// body instead (a private helper such as `VehicleScope::query_stops`, or
+/// one of the `scope_get_*!` macros above) so that the error names only the
+/// method the user called.
+macro_rules! with_context {
+    ($name:literal, $id:expr, $body:block) => {
+        $crate::error::in_context(|| $body, || format!(concat!($name, "('{}')"), $id))
+    };
+    ($name:literal, $body:block) => {
+        $crate::error::in_context(|| $body, || concat!($name, "()").to_string())
+    };
+}
+pub(crate) use with_context;
+
+/// Variable ids queried by the `get_all_emissions` helpers, in [`Emissions`]
+/// field order.
+///
+/// [`Emissions`]: crate::types::Emissions
+pub(crate) const EMISSION_VARS: [u8; 7] = [
+    crate::constants::VAR_CO2EMISSION,
+    crate::constants::VAR_COEMISSION,
+    crate::constants::VAR_HCEMISSION,
+    crate::constants::VAR_PMXEMISSION,
+    crate::constants::VAR_NOXEMISSION,
+    crate::constants::VAR_FUELCONSUMPTION,
+    crate::constants::VAR_NOISEEMISSION,
+];
+
+/// Fetch all [`EMISSION_VARS`] of `obj_id` with a single `batch_get`.
+pub(crate) fn batch_get_emissions(
+    client: &mut crate::client::TraciClient,
+    cmd_get: u8,
+    obj_id: &str,
+) -> Result<crate::types::Emissions, crate::error::TraciError> {
+    let requests: Vec<(u8, u8, &str)> = EMISSION_VARS.iter().map(|&var| (cmd_get, var, obj_id)).collect();
+    let values = client
+        .batch_get(&requests)?
+        .into_iter()
+        .map(expect_double)
+        .collect::<Result<Vec<f64>, _>>()?;
+    Ok(crate::types::Emissions {
+        co2: values[0],
+        co: values[1],
+        hc: values[2],
+        pmx: values[3],
+        nox: values[4],
+        fuel: values[5],
+        noise: values[6],
+    })
+}
+
+/// Unwrap a `batch_get` result that must be a double.
+pub(crate) fn expect_double(value: crate::types::TraciValue) -> Result<f64, crate::error::TraciError> {
+    match value {
+        crate::types::TraciValue::Double(d) => Ok(d),
+        other => Err(crate::error::TraciError::Protocol(format!("Expected a double but got {other:?}"))),
+    }
+}
+
+/// Unwrap a `batch_get` result that must be a string.
+pub(crate) fn expect_string(value: crate::types::TraciValue) -> Result<String, crate::error::TraciError> {
+    match value {
+        crate::types::TraciValue::String(s) => Ok(s),
+        other => Err(crate::error::TraciError::Protocol(format!("Expected a string but got {other:?}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::*;
+    use crate::scopes::edge::EdgeScope;
+    use crate::types::{TraciResults, TraciValue};
+
+    /// An edge scope holding one subscribed value for `e0`.
+    fn scope_with(var: u8, value: TraciValue) -> EdgeScope {
+        let mut scope = EdgeScope::default();
+        scope.subscription_results.insert("e0".into(), TraciResults::from([(var, value)]));
+        scope
+    }
+
+    #[test]
+    fn get_subscribed_double_is_none_for_another_type() {
+        let scope = scope_with(LAST_STEP_MEAN_SPEED, TraciValue::Double(8.5));
+        assert_eq!(scope.get_subscribed_double("e0", LAST_STEP_MEAN_SPEED), Some(8.5));
+        assert_eq!(scope.get_subscribed_double("e1", LAST_STEP_MEAN_SPEED), None);
+        assert_eq!(scope.get_subscribed_double("e0", LAST_STEP_OCCUPANCY), None);
+
+        let scope = scope_with(LAST_STEP_MEAN_SPEED, TraciValue::Int(8));
+        assert_eq!(scope.get_subscribed_double("e0", LAST_STEP_MEAN_SPEED), None);
+    }
+
+    #[test]
+    fn get_subscribed_int_is_none_for_another_type() {
+        let scope = scope_with(LAST_STEP_VEHICLE_NUMBER, TraciValue::Int(3));
+        assert_eq!(scope.get_subscribed_int("e0", LAST_STEP_VEHICLE_NUMBER), Some(3));
+
+        let scope = scope_with(LAST_STEP_VEHICLE_NUMBER, TraciValue::Double(3.0));
+        assert_eq!(scope.get_subscribed_int("e0", LAST_STEP_VEHICLE_NUMBER), None);
+    }
+
+    #[test]
+    fn get_subscribed_string_is_none_for_another_type() {
+        let scope = scope_with(VAR_NAME, TraciValue::String("Main Street".into()));
+        assert_eq!(scope.get_subscribed_string("e0", VAR_NAME), Some("Main Street"));
+
+        let scope = scope_with(VAR_NAME, TraciValue::StringList(vec!["Main Street".into()]));
+        assert_eq!(scope.get_subscribed_string("e0", VAR_NAME), None);
+    }
+
+    #[test]
+    fn get_subscribed_string_list_is_none_for_another_type() {
+        let ids = vec!["veh0".to_string(), "veh1".to_string()];
+        let scope = scope_with(LAST_STEP_VEHICLE_ID_LIST, TraciValue::StringList(ids.clone()));
+        assert_eq!(scope.get_subscribed_string_list("e0", LAST_STEP_VEHICLE_ID_LIST), Some(&ids[..]));
+
+        let scope = scope_with(LAST_STEP_VEHICLE_ID_LIST, TraciValue::String("veh0".into()));
+        assert_eq!(scope.get_subscribed_string_list("e0", LAST_STEP_VEHICLE_ID_LIST), None);
+    }
+}
@@ -20,79 +20,106 @@ impl InductionLoopScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("induction_loop.get_id_list", {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("induction_loop.get_id_count", {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, loop_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_PARAMETER, loop_id, Some(&add));
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("induction_loop.get_parameter", loop_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_PARAMETER, loop_id, Some(&add));
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     /// Number of vehicles that passed the detector in the last measurement interval.
     pub fn get_interval_vehicle_number(&self, client: &mut TraciClient, loop_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_LAST_INTERVAL_NUMBER, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("induction_loop.get_interval_vehicle_number", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_LAST_INTERVAL_NUMBER, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_position(&self, client: &mut TraciClient, loop_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_POSITION, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("induction_loop.get_position", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_POSITION, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lane_id(&self, client: &mut TraciClient, loop_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_LANE_ID, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("induction_loop.get_lane_id", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, VAR_LANE_ID, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_number(&self, client: &mut TraciClient, loop_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_NUMBER, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("induction_loop.get_last_step_vehicle_number", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_NUMBER, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_last_step_mean_speed(&self, client: &mut TraciClient, loop_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_MEAN_SPEED, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("induction_loop.get_last_step_mean_speed", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_MEAN_SPEED, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_ids(&self, client: &mut TraciClient, loop_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("induction_loop.get_last_step_vehicle_ids", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
+    /// Percentage (0–100) of the last step during which the loop was occupied.
     pub fn get_last_step_occupancy(&self, client: &mut TraciClient, loop_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_OCCUPANCY, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("induction_loop.get_last_step_occupancy", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_OCCUPANCY, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
+    /// Mean length (m) of the vehicles that passed the loop in the last step.
     pub fn get_last_step_mean_length(&self, client: &mut TraciClient, loop_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_LENGTH, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("induction_loop.get_last_step_mean_length", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_LENGTH, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
+    /// Seconds since the last vehicle left the loop; 0 while a vehicle is on it.
     pub fn get_time_since_detection(&self, client: &mut TraciClient, loop_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_TIME_SINCE_DETECTION, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("induction_loop.get_time_since_detection", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_TIME_SINCE_DETECTION, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Return per-vehicle data collected at this induction loop in the last step.
@@ -108,53 +135,127 @@ impl InductionLoopScope {
     ///     TYPE_DOUBLE  leave_time
     ///     TYPE_STRING  type_id
     /// ```
+    ///
+    /// A vehicle still on the detector has `leave_time == -1`; see
+    /// [`TraciVehicleData::is_on_detector`].
     pub fn get_vehicle_data(
         &self,
         client: &mut TraciClient,
         loop_id: &str,
     ) -> Result<Vec<TraciVehicleData>, TraciError> {
-        client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_DATA, loop_id, None);
-        client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_COMPOUND))?;
-
-        // number of compounds
-        let _components = client.read_int_from_input()?;
-        // inner list type tag (TYPE_INTEGER)
-        let _tag = client.read_ubyte_from_input()?;
-        let n = client.read_int_from_input()?;
+        crate::with_context!("induction_loop.get_vehicle_data", loop_id, {
+            client.create_command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_DATA, loop_id, None);
+            client.process_get(CMD_GET_INDUCTIONLOOP_VARIABLE, Some(TYPE_COMPOUND))?;
 
-        let mut result = Vec::with_capacity(n as usize);
-        for _ in 0..n {
+            // number of compounds
+            let _components = client.read_int_from_input()?;
+            // inner list type tag (TYPE_INTEGER)
             let _tag = client.read_ubyte_from_input()?;
-            let id = client.read_string_from_input()?;
+            let n = client.read_int_from_input()?;
 
-            let _tag = client.read_ubyte_from_input()?;
-            let length = client.read_double_from_input()?;
+            let mut result = Vec::with_capacity(n.max(0) as usize);
+            for _ in 0..n {
+                let _tag = client.read_ubyte_from_input()?;
+                let id = client.read_string_from_input()?;
 
-            let _tag = client.read_ubyte_from_input()?;
-            let entry_time = client.read_double_from_input()?;
+                let _tag = client.read_ubyte_from_input()?;
+                let length = client.read_double_from_input()?;
 
-            let _tag = client.read_ubyte_from_input()?;
-            let leave_time = client.read_double_from_input()?;
+                let _tag = client.read_ubyte_from_input()?;
+                let entry_time = client.read_double_from_input()?;
 
-            let _tag = client.read_ubyte_from_input()?;
-            let type_id = client.read_string_from_input()?;
-
-            result.push(TraciVehicleData {
-                id,
-                length,
-                entry_time,
-                leave_time,
-                type_id,
-            });
-        }
-        Ok(result)
+                let _tag = client.read_ubyte_from_input()?;
+                let leave_time = client.read_double_from_input()?;
+
+                let _tag = client.read_ubyte_from_input()?;
+                let type_id = client.read_string_from_input()?;
+
+                result.push(TraciVehicleData {
+                    id,
+                    length,
+                    entry_time,
+                    leave_time,
+                    type_id,
+                });
+            }
+            Ok(result)
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, loop_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_INDUCTIONLOOP_VARIABLE, loop_id, begin, end, vars)
+        crate::with_context!("induction_loop.subscribe", loop_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_INDUCTIONLOOP_VARIABLE, loop_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, loop_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_INDUCTIONLOOP_CONTEXT, loop_id, begin, end, domain, range, vars)
+        crate::with_context!("induction_loop.subscribe_context", loop_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_INDUCTIONLOOP_CONTEXT, loop_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn get_vehicle_data_keeps_a_vehicle_still_on_the_detector() {
+        let passed = TraciVehicleData {
+            id: "veh0".into(),
+            length: 5.0,
+            entry_time: 10.2,
+            leave_time: 10.6,
+            type_id: "passenger".into(),
+        };
+        let present = TraciVehicleData {
+            id: "veh1".into(),
+            length: 12.0,
+            entry_time: 10.8,
+            leave_time: -1.0,
+            type_id: "truck".into(),
+        };
+        let mut value = Storage::new();
+        value.begin_compound(1 + 5 * 2);
+        value.write_u8(TYPE_INTEGER);
+        value.write_i32(2);
+        for v in [&passed, &present] {
+            value.write_u8(TYPE_STRING);
+            value.write_string(&v.id);
+            for d in [v.length, v.entry_time, v.leave_time] {
+                value.write_u8(TYPE_DOUBLE);
+                value.write_f64(d);
+            }
+            value.write_u8(TYPE_STRING);
+            value.write_string(&v.type_id);
+        }
+        let reply = mock::get_reply(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_DATA, "e1_0", value);
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let data = InductionLoopScope::default().get_vehicle_data(&mut client, "e1_0").unwrap();
+        assert_eq!(data, vec![passed, present]);
+        assert!(!data[0].is_on_detector());
+        assert!(data[1].is_on_detector());
+        drop(client);
+        let request = mock::command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_VEHICLE_DATA, "e1_0", None);
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn time_since_detection_reads_a_double() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_INDUCTIONLOOP_VARIABLE,
+            LAST_STEP_TIME_SINCE_DETECTION,
+            "e1_0",
+            mock::double(3.5),
+        )]);
+        assert_eq!(InductionLoopScope::default().get_time_since_detection(&mut client, "e1_0").unwrap(), 3.5);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_INDUCTIONLOOP_VARIABLE, LAST_STEP_TIME_SINCE_DETECTION, "e1_0", None)]
+        );
     }
 }
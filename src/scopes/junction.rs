@@ -20,56 +20,115 @@ impl JunctionScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_JUNCTION_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("junction.get_id_list", {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_JUNCTION_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("junction.get_id_count", {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, junction_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_PARAMETER, junction_id, Some(&add));
-        client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("junction.get_parameter", junction_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_PARAMETER, junction_id, Some(&add));
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, junction_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_JUNCTION_VARIABLE, VAR_PARAMETER, junction_id, Some(&add));
-        client.process_set(CMD_SET_JUNCTION_VARIABLE)?;
-        Ok(())
+        crate::with_context!("junction.set_parameter", junction_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_JUNCTION_VARIABLE, VAR_PARAMETER, junction_id, Some(&add));
+            client.process_set(CMD_SET_JUNCTION_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn get_position(&self, client: &mut TraciClient, junction_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_POSITION, junction_id, None);
-        client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(POSITION_2D))?;
-        client.read_pos_2d_from_input()
+        crate::with_context!("junction.get_position", junction_id, {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_POSITION, junction_id, None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(POSITION_2D))?;
+            client.read_pos_2d_from_input()
+        })
     }
 
     pub fn get_shape(&self, client: &mut TraciClient, junction_id: &str) -> Result<Vec<TraciPosition>, TraciError> {
-        client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_SHAPE, junction_id, None);
-        client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_POLYGON))?;
-        client.read_polygon_from_input()
+        crate::with_context!("junction.get_shape", junction_id, {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, VAR_SHAPE, junction_id, None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_POLYGON))?;
+            client.read_polygon_from_input()
+        })
+    }
+
+    /// Return the ids of the edges ending at this junction.
+    pub fn get_incoming_edges(&self, client: &mut TraciClient, junction_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("junction.get_incoming_edges", junction_id, {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, INCOMING_EDGES, junction_id, None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Return the ids of the edges starting at this junction.
+    pub fn get_outgoing_edges(&self, client: &mut TraciClient, junction_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("junction.get_outgoing_edges", junction_id, {
+            client.create_command(CMD_GET_JUNCTION_VARIABLE, OUTGOING_EDGES, junction_id, None);
+            client.process_get(CMD_GET_JUNCTION_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, junction_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_JUNCTION_VARIABLE, junction_id, begin, end, vars)
+        crate::with_context!("junction.subscribe", junction_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_JUNCTION_VARIABLE, junction_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, junction_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_JUNCTION_CONTEXT, junction_id, begin, end, domain, range, vars)
+        crate::with_context!("junction.subscribe_context", junction_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_JUNCTION_CONTEXT, junction_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn incoming_and_outgoing_edges_decode_as_string_lists() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_JUNCTION_VARIABLE, INCOMING_EDGES, "J1", mock::string_list(&["north_in", "west_in"])),
+            mock::get_reply(CMD_GET_JUNCTION_VARIABLE, OUTGOING_EDGES, "J1", mock::string_list(&["east_out"])),
+        ]);
+        let scope = JunctionScope::default();
+        assert_eq!(scope.get_incoming_edges(&mut client, "J1").unwrap(), ["north_in", "west_in"]);
+        assert_eq!(scope.get_outgoing_edges(&mut client, "J1").unwrap(), ["east_out"]);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_GET_JUNCTION_VARIABLE, INCOMING_EDGES, "J1", None),
+                mock::command(CMD_GET_JUNCTION_VARIABLE, OUTGOING_EDGES, "J1", None),
+            ]
+        );
     }
 }
@@ -6,7 +6,7 @@ use crate::{
     constants::*,
     error::TraciError,
     storage::Storage,
-    types::{ContextSubscriptionResults, SubscriptionResults, TraciConnection, TraciPosition},
+    types::{ContextSubscriptionResults, Emissions, SubscriptionResults, TraciConnection, TraciPosition},
 };
 
 /// Scope for interacting with SUMO lane objects.
@@ -20,37 +20,44 @@ impl LaneScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("lane.get_id_list", {
+            client.create_command(CMD_GET_LANE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("lane.get_id_count", {
+            client.create_command(CMD_GET_LANE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, lane_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_PARAMETER, lane_id, Some(&add));
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("lane.get_parameter", lane_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_PARAMETER, lane_id, Some(&add));
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, lane_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_LANE_VARIABLE, VAR_PARAMETER, lane_id, Some(&add));
-        client.process_set(CMD_SET_LANE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("lane.set_parameter", lane_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_LANE_VARIABLE, VAR_PARAMETER, lane_id, Some(&add));
+            client.process_set(CMD_SET_LANE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -58,39 +65,51 @@ impl LaneScope {
     // -----------------------------------------------------------------------
 
     pub fn get_length(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_LENGTH, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_length", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_LENGTH, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_max_speed(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_MAXSPEED, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_max_speed", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_MAXSPEED, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_width(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_WIDTH, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_width", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_WIDTH, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_allowed(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LANE_ALLOWED, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("lane.get_allowed", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LANE_ALLOWED, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_disallowed(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LANE_DISALLOWED, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("lane.get_disallowed", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LANE_DISALLOWED, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_link_number(&self, client: &mut TraciClient, lane_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LANE_LINK_NUMBER, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("lane.get_link_number", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LANE_LINK_NUMBER, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     /// Return the list of connections leaving this lane.
@@ -110,6 +129,12 @@ impl LaneScope {
     ///     TYPE_DOUBLE  length
     /// ```
     pub fn get_links(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<TraciConnection>, TraciError> {
+        crate::with_context!("lane.get_links", lane_id, {
+            self.query_links(client, lane_id)
+        })
+    }
+
+    fn query_links(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<TraciConnection>, TraciError> {
         client.create_command(CMD_GET_LANE_VARIABLE, LANE_LINKS, lane_id, None);
         client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_COMPOUND))?;
 
@@ -158,106 +183,176 @@ impl LaneScope {
         Ok(result)
     }
 
+    /// Return the direction of each outgoing link (`"s"`, `"l"`, `"r"`, …),
+    /// in the order of [`Self::get_links`].
+    pub fn get_link_directions(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("lane.get_link_directions", lane_id, {
+            Ok(self.query_links(client, lane_id)?.into_iter().map(|link| link.direction).collect())
+        })
+    }
+
     pub fn get_shape(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<TraciPosition>, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_SHAPE, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_POLYGON))?;
-        client.read_polygon_from_input()
+        crate::with_context!("lane.get_shape", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_SHAPE, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_POLYGON))?;
+            client.read_polygon_from_input()
+        })
+    }
+
+    /// Return the length of the lane shape, summed over its segments.
+    ///
+    /// Unlike [`Self::get_length`] this is the geometric length, which may
+    /// differ from the lane length used for driving.
+    pub fn get_shape_length(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane.get_shape_length", lane_id, {
+            let shape = crate::scope_get_polygon!(client, CMD_GET_LANE_VARIABLE, VAR_SHAPE, lane_id)?;
+            Ok(TraciPosition::polyline_length(&shape))
+        })
     }
 
     pub fn get_edge_id(&self, client: &mut TraciClient, lane_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LANE_EDGE_ID, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("lane.get_edge_id", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LANE_EDGE_ID, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Return the id of the bidirectional (reverse-direction) lane, or an
+    /// empty string if the lane has none. Mostly relevant for rail networks.
+    pub fn get_bidi(&self, client: &mut TraciClient, lane_id: &str) -> Result<String, TraciError> {
+        crate::with_context!("lane.get_bidi", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_BIDI, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_co2_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_CO2EMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_co2_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_CO2EMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_co_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_COEMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_co_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_COEMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_hc_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_HCEMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_hc_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_HCEMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_pmx_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_PMXEMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_pmx_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_PMXEMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_nox_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_NOXEMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_nox_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_NOXEMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_fuel_consumption(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_FUELCONSUMPTION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_fuel_consumption", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_FUELCONSUMPTION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_noise_emission(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_NOISEEMISSION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_noise_emission", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_NOISEEMISSION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_electricity_consumption(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_electricity_consumption", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return all pollutant and consumption values in one round trip.
+    pub fn get_all_emissions(&self, client: &mut TraciClient, lane_id: &str) -> Result<Emissions, TraciError> {
+        crate::with_context!("lane.get_all_emissions", lane_id, {
+            crate::scopes::helpers::batch_get_emissions(client, CMD_GET_LANE_VARIABLE, lane_id)
+        })
     }
 
     pub fn get_last_step_mean_speed(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_MEAN_SPEED, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_last_step_mean_speed", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_MEAN_SPEED, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_occupancy(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_OCCUPANCY, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_last_step_occupancy", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_OCCUPANCY, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_length(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_LENGTH, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_last_step_length", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_LENGTH, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_traveltime(&self, client: &mut TraciClient, lane_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, VAR_CURRENT_TRAVELTIME, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("lane.get_traveltime", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, VAR_CURRENT_TRAVELTIME, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_number(&self, client: &mut TraciClient, lane_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_NUMBER, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("lane.get_last_step_vehicle_number", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_NUMBER, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_last_step_halting_number(&self, client: &mut TraciClient, lane_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_HALTING_NUMBER, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("lane.get_last_step_halting_number", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_HALTING_NUMBER, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_last_step_vehicle_ids(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, lane_id, None);
-        client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("lane.get_last_step_vehicle_ids", lane_id, {
+            client.create_command(CMD_GET_LANE_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, lane_id, None);
+            client.process_get(CMD_GET_LANE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     /// Return the IDs of lanes on which vehicles must yield to traffic on this lane entering `to_lane_id`.
@@ -267,6 +362,12 @@ impl LaneScope {
         lane_id: &str,
         to_lane_id: &str,
     ) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("lane.get_foes", lane_id, {
+            self.query_foes(client, lane_id, to_lane_id)
+        })
+    }
+
+    fn query_foes(&self, client: &mut TraciClient, lane_id: &str, to_lane_id: &str) -> Result<Vec<String>, TraciError> {
         let mut add = Storage::new();
         add.write_u8(TYPE_STRING);
         add.write_string(to_lane_id);
@@ -277,7 +378,9 @@ impl LaneScope {
 
     /// Return the internal foes (same as `get_foes` with empty to_lane_id).
     pub fn get_internal_foes(&self, client: &mut TraciClient, lane_id: &str) -> Result<Vec<String>, TraciError> {
-        self.get_foes(client, lane_id, "")
+        crate::with_context!("lane.get_internal_foes", lane_id, {
+            self.query_foes(client, lane_id, "")
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -290,12 +393,14 @@ impl LaneScope {
         lane_id: &str,
         allowed_classes: &[String],
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRINGLIST);
-        add.write_string_list(allowed_classes);
-        client.create_command(CMD_SET_LANE_VARIABLE, LANE_ALLOWED, lane_id, Some(&add));
-        client.process_set(CMD_SET_LANE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("lane.set_allowed", lane_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(allowed_classes);
+            client.create_command(CMD_SET_LANE_VARIABLE, LANE_ALLOWED, lane_id, Some(&add));
+            client.process_set(CMD_SET_LANE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_disallowed(
@@ -304,37 +409,104 @@ impl LaneScope {
         lane_id: &str,
         disallowed_classes: &[String],
     ) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRINGLIST);
-        add.write_string_list(disallowed_classes);
-        client.create_command(CMD_SET_LANE_VARIABLE, LANE_DISALLOWED, lane_id, Some(&add));
-        client.process_set(CMD_SET_LANE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("lane.set_disallowed", lane_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(disallowed_classes);
+            client.create_command(CMD_SET_LANE_VARIABLE, LANE_DISALLOWED, lane_id, Some(&add));
+            client.process_set(CMD_SET_LANE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_max_speed(&self, client: &mut TraciClient, lane_id: &str, speed: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        client.create_command(CMD_SET_LANE_VARIABLE, VAR_MAXSPEED, lane_id, Some(&add));
-        client.process_set(CMD_SET_LANE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("lane.set_max_speed", lane_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            client.create_command(CMD_SET_LANE_VARIABLE, VAR_MAXSPEED, lane_id, Some(&add));
+            client.process_set(CMD_SET_LANE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_length(&self, client: &mut TraciClient, lane_id: &str, length: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(length);
-        client.create_command(CMD_SET_LANE_VARIABLE, VAR_LENGTH, lane_id, Some(&add));
-        client.process_set(CMD_SET_LANE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("lane.set_length", lane_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(length);
+            client.create_command(CMD_SET_LANE_VARIABLE, VAR_LENGTH, lane_id, Some(&add));
+            client.process_set(CMD_SET_LANE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, lane_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_LANE_VARIABLE, lane_id, begin, end, vars)
+        crate::with_context!("lane.subscribe", lane_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_LANE_VARIABLE, lane_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, lane_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_LANE_CONTEXT, lane_id, begin, end, domain, range, vars)
+        crate::with_context!("lane.subscribe_context", lane_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_LANE_CONTEXT, lane_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use crate::scopes::helpers::EMISSION_VARS;
+
+    #[test]
+    fn get_all_emissions_reads_one_batched_response() {
+        let responses: Vec<_> = EMISSION_VARS
+            .iter()
+            .map(|&var| (CMD_GET_LANE_VARIABLE, var, "e0_0", mock::double(f64::from(var))))
+            .collect();
+        let (mut client, server) = mock::connect_replies(vec![mock::batch_get_reply(&responses)]);
+
+        let emissions = LaneScope::default().get_all_emissions(&mut client, "e0_0").unwrap();
+        assert_eq!(emissions.co2, f64::from(VAR_CO2EMISSION));
+        assert_eq!(emissions.noise, f64::from(VAR_NOISEEMISSION));
+
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1, "all seven GETs go out in one message");
+        assert_eq!(requests[0].len(), 7 * mock::command(CMD_GET_LANE_VARIABLE, VAR_CO2EMISSION, "e0_0", None).len());
+    }
+
+    #[test]
+    fn get_all_emissions_rejects_non_double_values() {
+        let mut responses: Vec<_> = EMISSION_VARS
+            .iter()
+            .map(|&var| (CMD_GET_LANE_VARIABLE, var, "e0_0", mock::double(1.0)))
+            .collect();
+        responses[3].3 = mock::string("oops");
+        let (mut client, _server) = mock::connect_replies(vec![mock::batch_get_reply(&responses)]);
+
+        let err = LaneScope::default().get_all_emissions(&mut client, "e0_0").unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "{err}");
+    }
+
+    #[test]
+    fn get_bidi_reads_the_reverse_lane() {
+        let reply = mock::get_reply(CMD_GET_LANE_VARIABLE, VAR_BIDI, "rail0_0", mock::string("-rail0_0"));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(LaneScope::default().get_bidi(&mut client, "rail0_0").unwrap(), "-rail0_0");
+        drop(client);
+        assert_eq!(server.requests(), vec![mock::command(CMD_GET_LANE_VARIABLE, VAR_BIDI, "rail0_0", None)]);
+    }
+
+    #[test]
+    fn get_bidi_without_a_reverse_lane_is_empty() {
+        let reply = mock::get_reply(CMD_GET_LANE_VARIABLE, VAR_BIDI, "e0_0", mock::string(""));
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(LaneScope::default().get_bidi(&mut client, "e0_0").unwrap(), "");
     }
 }
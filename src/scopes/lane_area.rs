@@ -10,9 +10,6 @@ use crate::{
 };
 
 /// Scope for interacting with SUMO lane area (E2) detectors.
-///
-/// No domain-specific getter/setter methods were defined in the original C++ API
-/// (the detector's variables are read via subscriptions).
 #[derive(Debug, Default)]
 pub struct LaneAreaScope {
     pub subscription_results: SubscriptionResults,
@@ -23,31 +20,166 @@ impl LaneAreaScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_LANEAREA_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("lane_area.get_id_list", {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_LANEAREA_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("lane_area.get_id_count", {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, det_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_LANEAREA_VARIABLE, VAR_PARAMETER, det_id, Some(&add));
-        client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("lane_area.get_parameter", det_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, VAR_PARAMETER, det_id, Some(&add));
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Start position of the detector on its first lane (m).
+    pub fn get_position(&self, client: &mut TraciClient, det_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane_area.get_position", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, VAR_POSITION, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Length of the detector (m).
+    pub fn get_length(&self, client: &mut TraciClient, det_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane_area.get_length", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, VAR_LENGTH, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    pub fn get_lane_id(&self, client: &mut TraciClient, det_id: &str) -> Result<String, TraciError> {
+        crate::with_context!("lane_area.get_lane_id", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, VAR_LANE_ID, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    pub fn get_last_step_vehicle_number(&self, client: &mut TraciClient, det_id: &str) -> Result<i32, TraciError> {
+        crate::with_context!("lane_area.get_last_step_vehicle_number", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, LAST_STEP_VEHICLE_NUMBER, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    pub fn get_last_step_mean_speed(&self, client: &mut TraciClient, det_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane_area.get_last_step_mean_speed", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, LAST_STEP_MEAN_SPEED, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    pub fn get_last_step_vehicle_ids(&self, client: &mut TraciClient, det_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("lane_area.get_last_step_vehicle_ids", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, LAST_STEP_VEHICLE_ID_LIST, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Percentage (0–100) of the detector length occupied by vehicles.
+    pub fn get_last_step_occupancy(&self, client: &mut TraciClient, det_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane_area.get_last_step_occupancy", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, LAST_STEP_OCCUPANCY, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    pub fn get_last_step_halting_number(&self, client: &mut TraciClient, det_id: &str) -> Result<i32, TraciError> {
+        crate::with_context!("lane_area.get_last_step_halting_number", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, LAST_STEP_VEHICLE_HALTING_NUMBER, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Number of vehicles in the longest jam on the detector.
+    pub fn get_jam_length_vehicle(&self, client: &mut TraciClient, det_id: &str) -> Result<i32, TraciError> {
+        crate::with_context!("lane_area.get_jam_length_vehicle", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_VEHICLE, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Length of the longest jam on the detector (m).
+    pub fn get_jam_length_meters(&self, client: &mut TraciClient, det_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("lane_area.get_jam_length_meters", det_id, {
+            client.create_command(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_METERS, det_id, None);
+            client.process_get(CMD_GET_LANEAREA_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, det_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_LANEAREA_VARIABLE, det_id, begin, end, vars)
+        crate::with_context!("lane_area.subscribe", det_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_LANEAREA_VARIABLE, det_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, det_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_LANEAREA_CONTEXT, det_id, begin, end, domain, range, vars)
+        crate::with_context!("lane_area.subscribe_context", det_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_LANEAREA_CONTEXT, det_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn jam_lengths_decode_as_vehicles_and_meters() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_VEHICLE, "e2_0", mock::int(7)),
+            mock::get_reply(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_METERS, "e2_0", mock::double(52.75)),
+        ]);
+        let scope = LaneAreaScope::default();
+        assert_eq!(scope.get_jam_length_vehicle(&mut client, "e2_0").unwrap(), 7);
+        assert_eq!(scope.get_jam_length_meters(&mut client, "e2_0").unwrap(), 52.75);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_VEHICLE, "e2_0", None),
+                mock::command(CMD_GET_LANEAREA_VARIABLE, JAM_LENGTH_METERS, "e2_0", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn jam_length_meters_rejects_an_integer_reply() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_LANEAREA_VARIABLE,
+            JAM_LENGTH_METERS,
+            "e2_0",
+            mock::int(52),
+        )]);
+        let err = LaneAreaScope::default().get_jam_length_meters(&mut client, "e2_0").unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "unexpected error {err:?}");
+        drop(client);
+        server.requests();
     }
 }
@@ -50,3 +50,112 @@ impl ScopeData {
         &self.context_subscription_results
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::TraciClient, constants::*, error::TraciError, mock};
+    use induction_loop::InductionLoopScope;
+    use lane_area::LaneAreaScope;
+    use multi_entry_exit::MultiEntryExitScope;
+
+    type Subscribe = fn(&mut TraciClient, &str, &[u8]) -> Result<(), TraciError>;
+    type SubscribeContext = fn(&mut TraciClient, &str, u8, f64, &[u8]) -> Result<(), TraciError>;
+
+    /// A detector scope, the commands its subscriptions use and how to reach
+    /// it on the client.
+    struct Detector {
+        id: &'static str,
+        subscribe_cmd: u8,
+        response_cmd: u8,
+        context_cmd: u8,
+        subscribe: Subscribe,
+        subscribe_context: SubscribeContext,
+        results: fn(&TraciClient, &str) -> Option<TraciResults>,
+    }
+
+    const DETECTORS: [Detector; 3] = [
+        Detector {
+            id: "e1_0",
+            subscribe_cmd: CMD_SUBSCRIBE_INDUCTIONLOOP_VARIABLE,
+            response_cmd: RESPONSE_SUBSCRIBE_INDUCTIONLOOP_VARIABLE,
+            context_cmd: CMD_SUBSCRIBE_INDUCTIONLOOP_CONTEXT,
+            subscribe: |c, id, vars| InductionLoopScope::default().subscribe(c, id, vars, 0.0, 100.0),
+            subscribe_context: |c, id, domain, range, vars| {
+                InductionLoopScope::default().subscribe_context(c, id, domain, range, vars, 0.0, 100.0)
+            },
+            results: |c, id| c.induction_loop.get_subscription_results(id).cloned(),
+        },
+        Detector {
+            id: "e2_0",
+            subscribe_cmd: CMD_SUBSCRIBE_LANEAREA_VARIABLE,
+            response_cmd: RESPONSE_SUBSCRIBE_LANEAREA_VARIABLE,
+            context_cmd: CMD_SUBSCRIBE_LANEAREA_CONTEXT,
+            subscribe: |c, id, vars| LaneAreaScope::default().subscribe(c, id, vars, 0.0, 100.0),
+            subscribe_context: |c, id, domain, range, vars| {
+                LaneAreaScope::default().subscribe_context(c, id, domain, range, vars, 0.0, 100.0)
+            },
+            results: |c, id| c.lane_area.get_subscription_results(id).cloned(),
+        },
+        Detector {
+            id: "e3_0",
+            subscribe_cmd: CMD_SUBSCRIBE_MULTIENTRYEXIT_VARIABLE,
+            response_cmd: RESPONSE_SUBSCRIBE_MULTIENTRYEXIT_VARIABLE,
+            context_cmd: CMD_SUBSCRIBE_MULTIENTRYEXIT_CONTEXT,
+            subscribe: |c, id, vars| MultiEntryExitScope::default().subscribe(c, id, vars, 0.0, 100.0),
+            subscribe_context: |c, id, domain, range, vars| {
+                MultiEntryExitScope::default().subscribe_context(c, id, domain, range, vars, 0.0, 100.0)
+            },
+            results: |c, id| c.multi_entry_exit.get_subscription_results(id).cloned(),
+        },
+    ];
+
+    #[test]
+    fn detector_subscriptions_round_trip() {
+        let vars = [LAST_STEP_VEHICLE_NUMBER, LAST_STEP_MEAN_SPEED, LAST_STEP_VEHICLE_ID_LIST];
+        for d in &DETECTORS {
+            let step = mock::step_reply(&[mock::subscription(
+                d.response_cmd,
+                d.id,
+                &[
+                    (LAST_STEP_VEHICLE_NUMBER, mock::int(2)),
+                    (LAST_STEP_MEAN_SPEED, mock::double(8.5)),
+                    (LAST_STEP_VEHICLE_ID_LIST, mock::string_list(&["veh0", "veh1"])),
+                ],
+            )]);
+            let (mut client, server) = mock::connect_replies(vec![mock::status(d.subscribe_cmd), step]);
+
+            (d.subscribe)(&mut client, d.id, &vars).unwrap();
+            assert!(client.simulation_step(0.0).unwrap());
+            let results = (d.results)(&client, d.id).unwrap_or_else(|| panic!("no results for {}", d.id));
+            assert_eq!(results[&LAST_STEP_VEHICLE_NUMBER].as_i32(), Some(2), "{}", d.id);
+            assert_eq!(results[&LAST_STEP_MEAN_SPEED].as_f64(), Some(8.5), "{}", d.id);
+            assert_eq!(
+                results[&LAST_STEP_VEHICLE_ID_LIST].as_string_list(),
+                Some(&["veh0".to_string(), "veh1".to_string()][..]),
+                "{}",
+                d.id
+            );
+
+            drop(client);
+            let requests = server.requests();
+            assert_eq!(requests[0], mock::subscribe(d.subscribe_cmd, d.id, 0.0, 100.0, &vars), "{}", d.id);
+        }
+    }
+
+    #[test]
+    fn detector_context_subscriptions_send_domain_and_range() {
+        for d in &DETECTORS {
+            let (mut client, server) = mock::connect_replies(vec![mock::status(d.context_cmd)]);
+
+            (d.subscribe_context)(&mut client, d.id, CMD_GET_VEHICLE_VARIABLE, 50.0, &[VAR_SPEED]).unwrap();
+            drop(client);
+            assert_eq!(
+                server.requests(),
+                vec![mock::subscribe_context(d.context_cmd, d.id, 0.0, 100.0, CMD_GET_VEHICLE_VARIABLE, 50.0, &[VAR_SPEED])],
+                "{}",
+                d.id
+            );
+        }
+    }
+}
@@ -7,7 +7,8 @@ use crate::{
     error::TraciError,
     storage::Storage,
     types::{
-        ContextSubscriptionResults, SubscriptionResults, TraciColor, TraciPosition, TraciStage,
+        ContextSubscriptionResults, PersonAddOptions, PersonPlanStage, SubscriptionResults, TraciColor,
+        TraciPosition, TraciReservation, TraciStage,
     },
     scopes::simulation::read_traci_stage,
 };
@@ -23,37 +24,44 @@ impl PersonScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("person.get_id_list", {
+            client.create_command(CMD_GET_PERSON_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("person.get_id_count", {
+            client.create_command(CMD_GET_PERSON_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, person_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_PARAMETER, person_id, Some(&add));
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_parameter", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_PARAMETER, person_id, Some(&add));
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, person_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_PARAMETER, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_parameter", person_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_PARAMETER, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -61,103 +69,180 @@ impl PersonScope {
     // -----------------------------------------------------------------------
 
     pub fn get_speed(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SPEED, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_speed", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SPEED, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_position(&self, client: &mut TraciClient, person_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_POSITION, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(POSITION_2D))?;
-        client.read_pos_2d_from_input()
+        crate::with_context!("person.get_position", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_POSITION, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(POSITION_2D))?;
+            client.read_pos_2d_from_input()
+        })
+    }
+
+    /// Return the position as lon/lat (`x` = longitude, `y` = latitude),
+    /// using the client's cached [`CoordinateConverter`](crate::CoordinateConverter).
+    ///
+    /// Fails with [`TraciError::Protocol`] if SUMO reports the network as unprojected.
+    pub fn get_geo_position(&self, client: &mut TraciClient, person_id: &str) -> Result<TraciPosition, TraciError> {
+        crate::with_context!("person.get_geo_position", person_id, {
+            let pos = crate::scope_get_pos!(client, CMD_GET_PERSON_VARIABLE, VAR_POSITION, person_id)?;
+            let converter = client.coordinate_converter()?;
+            converter.convert_to_geo(client, pos.x, pos.y)
+        })
     }
 
     pub fn get_position3d(&self, client: &mut TraciClient, person_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_POSITION3D, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(POSITION_3D))?;
-        client.read_pos_3d_from_input()
+        crate::with_context!("person.get_position3d", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_POSITION3D, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(POSITION_3D))?;
+            client.read_pos_3d_from_input()
+        })
     }
 
     pub fn get_angle(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_ANGLE, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_angle", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_ANGLE, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_slope(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SLOPE, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_slope", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SLOPE, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lane_position(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LANEPOSITION, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_lane_position", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LANEPOSITION, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the network distance from the person's current position to
+    /// `pos` on `edge_id` / `lane`.
+    ///
+    /// SUMO computes the distance along the road network
+    /// (`REQUEST_DRIVINGDIST`); edges only usable on foot may yield
+    /// `INVALID_DOUBLE_VALUE`.
+    pub fn get_walking_distance(&self, client: &mut TraciClient, person_id: &str, edge_id: &str, pos: f64, lane: u8) -> Result<f64, TraciError> {
+        crate::with_context!("person.get_walking_distance", person_id, {
+            let road_id = crate::scope_get_string!(client, CMD_GET_PERSON_VARIABLE, VAR_ROAD_ID, person_id)?;
+            let lane_pos = crate::scope_get_double!(client, CMD_GET_PERSON_VARIABLE, VAR_LANEPOSITION, person_id)?;
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(&road_id);
+            add.write_f64(lane_pos);
+            add.write_u8(0); // lane
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(edge_id);
+            add.write_f64(pos);
+            add.write_u8(lane);
+            add.write_u8(REQUEST_DRIVINGDIST);
+            client.create_command(CMD_GET_SIM_VARIABLE, DISTANCE_REQUEST, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_color(&self, client: &mut TraciClient, person_id: &str) -> Result<TraciColor, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_COLOR, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_COLOR))?;
-        client.read_color_from_input()
+        crate::with_context!("person.get_color", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_COLOR, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_COLOR))?;
+            client.read_color_from_input()
+        })
     }
 
     pub fn get_length(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LENGTH, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_length", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LENGTH, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_road_id(&self, client: &mut TraciClient, person_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_ROAD_ID, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_road_id", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_ROAD_ID, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_lane_id(&self, client: &mut TraciClient, person_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LANE_ID, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_lane_id", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_LANE_ID, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_type_id(&self, client: &mut TraciClient, person_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_TYPE, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_type_id", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_TYPE, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_speed_factor(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SPEED_FACTOR, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_speed_factor", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_SPEED_FACTOR, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_waiting_time(&self, client: &mut TraciClient, person_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_WAITING_TIME, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("person.get_waiting_time", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_WAITING_TIME, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_next_edge(&self, client: &mut TraciClient, person_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_NEXT_EDGE, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_next_edge", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_NEXT_EDGE, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_vehicle(&self, client: &mut TraciClient, person_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_VEHICLE, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("person.get_vehicle", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_VEHICLE, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_remaining_stages(&self, client: &mut TraciClient, person_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, person_id, None);
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("person.get_remaining_stages", person_id, {
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, person_id, None);
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     /// Get a specific stage of the person's journey.
     pub fn get_stage(&self, client: &mut TraciClient, person_id: &str, next_stage_index: i32) -> Result<TraciStage, TraciError> {
+        crate::with_context!("person.get_stage", person_id, {
+            self.query_stage(client, person_id, next_stage_index)
+        })
+    }
+
+    fn query_stage(&self, client: &mut TraciClient, person_id: &str, next_stage_index: i32) -> Result<TraciStage, TraciError> {
         let mut add = Storage::new();
         add.write_u8(TYPE_INTEGER);
         add.write_i32(next_stage_index);
@@ -166,14 +251,47 @@ impl PersonScope {
         read_traci_stage(client)
     }
 
+    /// Return all stages the person has not finished yet, starting with the
+    /// current one.
+    pub fn journey(&self, client: &mut TraciClient, person_id: &str) -> Result<Vec<TraciStage>, TraciError> {
+        crate::with_context!("person.journey", person_id, {
+            let n = crate::scope_get_int!(client, CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, person_id)?;
+            let mut stages = Vec::with_capacity(n.max(0) as usize);
+            for i in 0..n {
+                stages.push(self.query_stage(client, person_id, i)?);
+            }
+            Ok(stages)
+        })
+    }
+
     /// Get the edges for a specific stage.
     pub fn get_edges(&self, client: &mut TraciClient, person_id: &str, next_stage_index: i32) -> Result<Vec<String>, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(next_stage_index);
-        client.create_command(CMD_GET_PERSON_VARIABLE, VAR_EDGES, person_id, Some(&add));
-        client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("person.get_edges", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(next_stage_index);
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_EDGES, person_id, Some(&add));
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Return the taxi reservations of all persons. With `only_new` set to
+    /// `1` only reservations not yet returned by a previous call are listed.
+    pub fn get_taxi_reservations(&self, client: &mut TraciClient, only_new: i32) -> Result<Vec<TraciReservation>, TraciError> {
+        crate::with_context!("person.get_taxi_reservations", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(only_new);
+            client.create_command(CMD_GET_PERSON_VARIABLE, VAR_TAXI_RESERVATIONS, "", Some(&add));
+            client.process_get(CMD_GET_PERSON_VARIABLE, Some(TYPE_COMPOUND))?;
+            let n = client.read_int_from_input()?;
+            let mut result = Vec::with_capacity(n.max(0) as usize);
+            for _ in 0..n {
+                result.push(read_reservation(client)?);
+            }
+            Ok(result)
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -182,9 +300,14 @@ impl PersonScope {
 
     /// Add a new person to the simulation.
     pub fn add(&self, client: &mut TraciClient, person_id: &str, edge_id: &str, pos: f64, depart: f64, type_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("person.add", person_id, {
+            self.send_add(client, person_id, edge_id, pos, depart, type_id)
+        })
+    }
+
+    fn send_add(&self, client: &mut TraciClient, person_id: &str, edge_id: &str, pos: f64, depart: f64, type_id: &str) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(4);
+        add.begin_compound(4);
         add.write_u8(TYPE_STRING);
         add.write_string(type_id);
         add.write_u8(TYPE_STRING);
@@ -198,11 +321,38 @@ impl PersonScope {
         Ok(())
     }
 
+    /// Add the person described by `opts` and append its queued stages.
+    pub fn add_with(&self, client: &mut TraciClient, person_id: &str, opts: &PersonAddOptions) -> Result<(), TraciError> {
+        crate::with_context!("person.add_with", person_id, {
+            self.send_add(client, person_id, &opts.edge_id, opts.pos, opts.depart, &opts.type_id)?;
+            for stage in &opts.stages {
+                match stage {
+                    PersonPlanStage::Walking { edges, arrival_pos } => {
+                        self.send_walking_stage(client, person_id, edges, *arrival_pos, -1.0, -1.0, "")?
+                    }
+                    PersonPlanStage::Driving { to_edge, lines } => {
+                        self.send_driving_stage(client, person_id, to_edge, lines, "")?
+                    }
+                    PersonPlanStage::Waiting { duration } => {
+                        self.send_waiting_stage(client, person_id, *duration, "waiting", "")?
+                    }
+                    PersonPlanStage::Full(stage) => self.send_stage(client, person_id, stage)?,
+                }
+            }
+            Ok(())
+        })
+    }
+
     /// Append a full stage to a person's plan.
     pub fn append_stage(&self, client: &mut TraciClient, person_id: &str, stage: &TraciStage) -> Result<(), TraciError> {
+        crate::with_context!("person.append_stage", person_id, {
+            self.send_stage(client, person_id, stage)
+        })
+    }
+
+    fn send_stage(&self, client: &mut TraciClient, person_id: &str, stage: &TraciStage) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(13);
+        add.begin_compound(13);
         add.write_u8(TYPE_INTEGER);
         add.write_i32(stage.type_);
         add.write_u8(TYPE_STRING);
@@ -239,11 +389,16 @@ impl PersonScope {
 
     /// Append a waiting stage.
     pub fn append_waiting_stage(&self, client: &mut TraciClient, person_id: &str, duration: f64, description: &str, stop_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("person.append_waiting_stage", person_id, {
+            self.send_waiting_stage(client, person_id, duration, description, stop_id)
+        })
+    }
+
+    fn send_waiting_stage(&self, client: &mut TraciClient, person_id: &str, duration: f64, description: &str, stop_id: &str) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(4);
+        add.begin_compound(4);
         add.write_u8(TYPE_INTEGER);
-        add.write_i32(STAGE_WAITING as i32);
+        add.write_i32(STAGE_WAITING);
         add.write_u8(TYPE_DOUBLE);
         add.write_f64(duration);
         add.write_u8(TYPE_STRING);
@@ -256,12 +411,19 @@ impl PersonScope {
     }
 
     /// Append a walking stage.
+    #[allow(clippy::too_many_arguments)]
     pub fn append_walking_stage(&self, client: &mut TraciClient, person_id: &str, edges: &[String], arrival_pos: f64, duration: f64, speed: f64, stop_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("person.append_walking_stage", person_id, {
+            self.send_walking_stage(client, person_id, edges, arrival_pos, duration, speed, stop_id)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn send_walking_stage(&self, client: &mut TraciClient, person_id: &str, edges: &[String], arrival_pos: f64, duration: f64, speed: f64, stop_id: &str) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(6);
+        add.begin_compound(6);
         add.write_u8(TYPE_INTEGER);
-        add.write_i32(STAGE_WALKING as i32);
+        add.write_i32(STAGE_WALKING);
         add.write_u8(TYPE_STRINGLIST);
         add.write_i32(edges.len() as i32);
         for e in edges {
@@ -282,11 +444,16 @@ impl PersonScope {
 
     /// Append a driving stage.
     pub fn append_driving_stage(&self, client: &mut TraciClient, person_id: &str, to_edge: &str, lines: &str, stop_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("person.append_driving_stage", person_id, {
+            self.send_driving_stage(client, person_id, to_edge, lines, stop_id)
+        })
+    }
+
+    fn send_driving_stage(&self, client: &mut TraciClient, person_id: &str, to_edge: &str, lines: &str, stop_id: &str) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(4);
+        add.begin_compound(4);
         add.write_u8(TYPE_INTEGER);
-        add.write_i32(STAGE_DRIVING as i32);
+        add.write_i32(STAGE_DRIVING);
         add.write_u8(TYPE_STRING);
         add.write_string(to_edge);
         add.write_u8(TYPE_STRING);
@@ -300,143 +467,369 @@ impl PersonScope {
 
     /// Remove one stage from a person's plan.
     pub fn remove_stage(&self, client: &mut TraciClient, person_id: &str, next_stage_index: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(next_stage_index);
-        client.create_command(CMD_SET_PERSON_VARIABLE, REMOVE_STAGE, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.remove_stage", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(next_stage_index);
+            client.create_command(CMD_SET_PERSON_VARIABLE, REMOVE_STAGE, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Remove all stages from a person's plan.
+    ///
+    /// The remaining stage count is re-read after every removal; if it does
+    /// not shrink (e.g. the plan changed concurrently) the loop stops with a
+    /// [`TraciError::Protocol`] instead of spinning. The current stage is
+    /// removed last, and only if there is one.
     pub fn remove_stages(&self, client: &mut TraciClient, person_id: &str) -> Result<(), TraciError> {
-        while self.get_remaining_stages(client, person_id)? > 1 {
-            self.remove_stage(client, person_id, 1)?;
-        }
-        self.remove_stage(client, person_id, 0)
+        crate::with_context!("person.remove_stages", person_id, {
+            let mut remaining = crate::scope_get_int!(client, CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, person_id)?;
+            while remaining > 1 {
+                crate::scope_set_int!(client, CMD_SET_PERSON_VARIABLE, REMOVE_STAGE, person_id, 1)?;
+                let now = crate::scope_get_int!(client, CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, person_id)?;
+                if now >= remaining {
+                    return Err(TraciError::Protocol(format!(
+                        "Remaining stages did not decrease ({remaining} -> {now})"
+                    )));
+                }
+                remaining = now;
+            }
+            if remaining == 1 {
+                crate::scope_set_int!(client, CMD_SET_PERSON_VARIABLE, REMOVE_STAGE, person_id, 0)?;
+            }
+            Ok(())
+        })
     }
 
     pub fn reroute_traveltime(&self, client: &mut TraciClient, person_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(0);
-        client.create_command(CMD_SET_PERSON_VARIABLE, CMD_REROUTE_TRAVELTIME, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.reroute_traveltime", person_id, {
+            let mut add = Storage::new();
+            add.begin_compound(0);
+            client.create_command(CMD_SET_PERSON_VARIABLE, CMD_REROUTE_TRAVELTIME, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn move_to(&self, client: &mut TraciClient, person_id: &str, edge_id: &str, position: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(edge_id);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(position);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_MOVE_TO, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
-    }
-
+        crate::with_context!("person.move_to", person_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(edge_id);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(position);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_MOVE_TO, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn move_to_xy(&self, client: &mut TraciClient, person_id: &str, edge_id: &str, x: f64, y: f64, angle: f64, keep_route: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(5);
-        add.write_u8(TYPE_STRING);
-        add.write_string(edge_id);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(x);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(y);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(angle);
-        add.write_u8(TYPE_BYTE);
-        add.write_u8(keep_route as u8);
-        client.create_command(CMD_SET_PERSON_VARIABLE, MOVE_TO_XY, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.move_to_xy", person_id, {
+            let mut add = Storage::new();
+            add.begin_compound(5);
+            add.write_u8(TYPE_STRING);
+            add.write_string(edge_id);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(x);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(y);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(angle);
+            add.write_u8(TYPE_BYTE);
+            add.write_u8(keep_route as u8);
+            client.create_command(CMD_SET_PERSON_VARIABLE, MOVE_TO_XY, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_speed(&self, client: &mut TraciClient, person_id: &str, speed: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_SPEED, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_speed", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_SPEED, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_type(&self, client: &mut TraciClient, person_id: &str, type_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(type_id);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_TYPE, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_type", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(type_id);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_TYPE, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_speed_factor(&self, client: &mut TraciClient, person_id: &str, factor: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(factor);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_SPEED_FACTOR, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_speed_factor", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(factor);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_SPEED_FACTOR, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_length(&self, client: &mut TraciClient, person_id: &str, length: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(length);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_LENGTH, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_length", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(length);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_LENGTH, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_width(&self, client: &mut TraciClient, person_id: &str, width: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(width);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_WIDTH, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_width", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(width);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_WIDTH, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_height(&self, client: &mut TraciClient, person_id: &str, height: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(height);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_HEIGHT, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_height", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(height);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_HEIGHT, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_min_gap(&self, client: &mut TraciClient, person_id: &str, min_gap: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(min_gap);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_MINGAP, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_min_gap", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(min_gap);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_MINGAP, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_color(&self, client: &mut TraciClient, person_id: &str, c: &TraciColor) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COLOR);
-        add.write_u8(c.r);
-        add.write_u8(c.g);
-        add.write_u8(c.b);
-        add.write_u8(c.a);
-        client.create_command(CMD_SET_PERSON_VARIABLE, VAR_COLOR, person_id, Some(&add));
-        client.process_set(CMD_SET_PERSON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("person.set_color", person_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            client.create_command(CMD_SET_PERSON_VARIABLE, VAR_COLOR, person_id, Some(&add));
+            client.process_set(CMD_SET_PERSON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, person_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_PERSON_VARIABLE, person_id, begin, end, vars)
+        crate::with_context!("person.subscribe", person_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_PERSON_VARIABLE, person_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, person_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_PERSON_CONTEXT, person_id, begin, end, domain, range, vars)
+        crate::with_context!("person.subscribe_context", person_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_PERSON_CONTEXT, person_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+// ============================================================================
+// Shared helper: read one TraciReservation compound from client.input
+// ============================================================================
+
+/// Read a reservation compound (10 typed fields) from the input buffer.
+fn read_reservation(client: &mut TraciClient) -> Result<TraciReservation, TraciError> {
+    client.read_ubyte_from_input()?; // TYPE_COMPOUND
+    client.read_int_from_input()?; // components count
+
+    client.read_ubyte_from_input()?;
+    let id = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let persons = client.read_string_list_from_input()?;
+    client.read_ubyte_from_input()?;
+    let group = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let from_edge = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let to_edge = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let depart_pos = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let arrival_pos = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let depart = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let reservation_time = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let state = client.read_int_from_input()?;
+
+    Ok(TraciReservation {
+        id,
+        persons,
+        group,
+        from_edge,
+        to_edge,
+        depart_pos,
+        arrival_pos,
+        depart,
+        reservation_time,
+        state,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    fn reservation(id: &str, persons: &[&str], state: i32) -> TraciReservation {
+        TraciReservation {
+            id: id.into(),
+            persons: persons.iter().map(|p| p.to_string()).collect(),
+            group: String::new(),
+            from_edge: "e0".into(),
+            to_edge: "e4".into(),
+            depart_pos: 5.0,
+            arrival_pos: 40.0,
+            depart: 100.0,
+            reservation_time: 90.0,
+            state,
+        }
+    }
+
+    /// Encode `reservations` as SUMO sends `VAR_TAXI_RESERVATIONS`.
+    fn reservations_value(reservations: &[TraciReservation]) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(reservations.len() as i32);
+        for r in reservations {
+            s.begin_compound(10);
+            s.write_u8(TYPE_STRING);
+            s.write_string(&r.id);
+            s.write_u8(TYPE_STRINGLIST);
+            s.write_string_list(&r.persons);
+            for text in [&r.group, &r.from_edge, &r.to_edge] {
+                s.write_u8(TYPE_STRING);
+                s.write_string(text);
+            }
+            for d in [r.depart_pos, r.arrival_pos, r.depart, r.reservation_time] {
+                s.write_u8(TYPE_DOUBLE);
+                s.write_f64(d);
+            }
+            s.write_u8(TYPE_INTEGER);
+            s.write_i32(r.state);
+        }
+        s
+    }
+
+    #[test]
+    fn get_taxi_reservations_decodes_every_reservation() {
+        let expected = vec![reservation("r0", &["p0"], 1), reservation("r1", &["p1", "p2"], 2)];
+        let reply = mock::get_reply(CMD_GET_PERSON_VARIABLE, VAR_TAXI_RESERVATIONS, "", reservations_value(&expected));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(PersonScope::default().get_taxi_reservations(&mut client, 0).unwrap(), expected);
+        drop(client);
+        let request = mock::command(CMD_GET_PERSON_VARIABLE, VAR_TAXI_RESERVATIONS, "", Some(&mock::int(0)));
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn get_taxi_reservations_without_reservations_is_empty() {
+        let reply = mock::get_reply(CMD_GET_PERSON_VARIABLE, VAR_TAXI_RESERVATIONS, "", reservations_value(&[]));
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        assert!(PersonScope::default().get_taxi_reservations(&mut client, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn add_with_two_stages_is_read_back_by_journey() {
+        let walk = TraciStage {
+            type_: STAGE_WALKING,
+            edges: vec!["e0".into(), "e1".into()],
+            arrival_pos: 20.0,
+            ..TraciStage::default()
+        };
+        let ride = TraciStage {
+            type_: STAGE_DRIVING,
+            line: "bus42".into(),
+            edges: vec!["e1".into(), "e2".into(), "e3".into()],
+            ..TraciStage::default()
+        };
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::status(CMD_SET_PERSON_VARIABLE),
+            mock::status(CMD_SET_PERSON_VARIABLE),
+            mock::status(CMD_SET_PERSON_VARIABLE),
+            mock::get_reply(CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, "ped0", mock::int(2)),
+            mock::get_reply(CMD_GET_PERSON_VARIABLE, VAR_STAGE, "ped0", mock::stage(&walk)),
+            mock::get_reply(CMD_GET_PERSON_VARIABLE, VAR_STAGE, "ped0", mock::stage(&ride)),
+        ]);
+        let scope = PersonScope::default();
+        let opts = PersonAddOptions::new("e0").pos(5.0).depart(10.0).walk(&["e0", "e1"], 20.0).drive("e3", "bus42");
+        scope.add_with(&mut client, "ped0", &opts).unwrap();
+        assert_eq!(scope.journey(&mut client, "ped0").unwrap(), vec![walk, ride]);
+        drop(client);
+
+        let mut person = Storage::new();
+        person.begin_compound(4);
+        person.write_u8(TYPE_STRING);
+        person.write_string("DEFAULT_PEDTYPE");
+        person.write_u8(TYPE_STRING);
+        person.write_string("e0");
+        person.write_u8(TYPE_DOUBLE);
+        person.write_f64(10.0);
+        person.write_u8(TYPE_DOUBLE);
+        person.write_f64(5.0);
+        let mut walking = Storage::new();
+        walking.begin_compound(6);
+        walking.write_u8(TYPE_INTEGER);
+        walking.write_i32(STAGE_WALKING);
+        walking.write_u8(TYPE_STRINGLIST);
+        walking.write_string_list(&["e0".to_string(), "e1".to_string()]);
+        for v in [20.0, -1.0, -1.0] {
+            walking.write_u8(TYPE_DOUBLE);
+            walking.write_f64(v);
+        }
+        walking.write_u8(TYPE_STRING);
+        walking.write_string("");
+        let mut driving = Storage::new();
+        driving.begin_compound(4);
+        driving.write_u8(TYPE_INTEGER);
+        driving.write_i32(STAGE_DRIVING);
+        for text in ["e3", "bus42", ""] {
+            driving.write_u8(TYPE_STRING);
+            driving.write_string(text);
+        }
+        let stage_index = |i| Some(mock::int(i));
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_SET_PERSON_VARIABLE, ADD, "ped0", Some(&person)),
+                mock::command(CMD_SET_PERSON_VARIABLE, APPEND_STAGE, "ped0", Some(&walking)),
+                mock::command(CMD_SET_PERSON_VARIABLE, APPEND_STAGE, "ped0", Some(&driving)),
+                mock::command(CMD_GET_PERSON_VARIABLE, VAR_STAGES_REMAINING, "ped0", None),
+                mock::command(CMD_GET_PERSON_VARIABLE, VAR_STAGE, "ped0", stage_index(0).as_ref()),
+                mock::command(CMD_GET_PERSON_VARIABLE, VAR_STAGE, "ped0", stage_index(1).as_ref()),
+            ]
+        );
     }
 }
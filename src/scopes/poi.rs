@@ -20,37 +20,44 @@ impl PoiScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("poi.get_id_list", {
+            client.create_command(CMD_GET_POI_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("poi.get_id_count", {
+            client.create_command(CMD_GET_POI_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, poi_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_PARAMETER, poi_id, Some(&add));
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("poi.get_parameter", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_PARAMETER, poi_id, Some(&add));
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, poi_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_PARAMETER, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_parameter", poi_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_PARAMETER, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -58,45 +65,59 @@ impl PoiScope {
     // -----------------------------------------------------------------------
 
     pub fn get_type(&self, client: &mut TraciClient, poi_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_TYPE, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("poi.get_type", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_TYPE, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_position(&self, client: &mut TraciClient, poi_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_POSITION, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(POSITION_2D))?;
-        client.read_pos_2d_from_input()
+        crate::with_context!("poi.get_position", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_POSITION, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(POSITION_2D))?;
+            client.read_pos_2d_from_input()
+        })
     }
 
     pub fn get_color(&self, client: &mut TraciClient, poi_id: &str) -> Result<TraciColor, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_COLOR, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_COLOR))?;
-        client.read_color_from_input()
+        crate::with_context!("poi.get_color", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_COLOR, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_COLOR))?;
+            client.read_color_from_input()
+        })
     }
 
     pub fn get_width(&self, client: &mut TraciClient, poi_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_WIDTH, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("poi.get_width", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_WIDTH, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_height(&self, client: &mut TraciClient, poi_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_HEIGHT, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("poi.get_height", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_HEIGHT, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_angle(&self, client: &mut TraciClient, poi_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_ANGLE, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("poi.get_angle", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_ANGLE, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_image_file(&self, client: &mut TraciClient, poi_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_POI_VARIABLE, VAR_IMAGEFILE, poi_id, None);
-        client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("poi.get_image_file", poi_id, {
+            client.create_command(CMD_GET_POI_VARIABLE, VAR_IMAGEFILE, poi_id, None);
+            client.process_get(CMD_GET_POI_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -104,73 +125,91 @@ impl PoiScope {
     // -----------------------------------------------------------------------
 
     pub fn set_type(&self, client: &mut TraciClient, poi_id: &str, poi_type: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(poi_type);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_TYPE, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_type", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(poi_type);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_TYPE, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_position(&self, client: &mut TraciClient, poi_id: &str, x: f64, y: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(POSITION_2D);
-        add.write_f64(x);
-        add.write_f64(y);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_POSITION, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_position", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(POSITION_2D);
+            add.write_f64(x);
+            add.write_f64(y);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_POSITION, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_color(&self, client: &mut TraciClient, poi_id: &str, c: &TraciColor) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COLOR);
-        add.write_u8(c.r);
-        add.write_u8(c.g);
-        add.write_u8(c.b);
-        add.write_u8(c.a);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_COLOR, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_color", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_COLOR, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_width(&self, client: &mut TraciClient, poi_id: &str, width: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(width);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_WIDTH, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_width", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(width);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_WIDTH, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_height(&self, client: &mut TraciClient, poi_id: &str, height: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(height);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_HEIGHT, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_height", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(height);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_HEIGHT, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_angle(&self, client: &mut TraciClient, poi_id: &str, angle: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(angle);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_ANGLE, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_angle", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(angle);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_ANGLE, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_image_file(&self, client: &mut TraciClient, poi_id: &str, image_file: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(image_file);
-        client.create_command(CMD_SET_POI_VARIABLE, VAR_IMAGEFILE, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        crate::with_context!("poi.set_image_file", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(image_file);
+            client.create_command(CMD_SET_POI_VARIABLE, VAR_IMAGEFILE, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    /// Add a new POI to the simulation.
+    /// Add a new POI at (`x`, `y`) on `layer`.
+    ///
+    /// `img_file` is an optional image drawn by `sumo-gui` instead of the
+    /// default marker, scaled to `width` × `height` and rotated by `angle`
+    /// degrees; pass an empty string to keep the marker.
     #[allow(clippy::too_many_arguments)]
     pub fn add(
         &self,
@@ -186,48 +225,102 @@ impl PoiScope {
         height: f64,
         angle: f64,
     ) -> Result<(), TraciError> {
+        crate::with_context!("poi.add", poi_id, {
+            let mut add = Storage::new();
+            add.begin_compound(8);
+            add.write_u8(TYPE_STRING);
+            add.write_string(poi_type);
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(color.r);
+            add.write_u8(color.g);
+            add.write_u8(color.b);
+            add.write_u8(color.a);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(layer);
+            add.write_u8(POSITION_2D);
+            add.write_f64(x);
+            add.write_f64(y);
+            add.write_u8(TYPE_STRING);
+            add.write_string(img_file);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(width);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(height);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(angle);
+            client.create_command(CMD_SET_POI_VARIABLE, ADD, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Remove a POI; `layer` must match the layer it was added on.
+    pub fn remove(&self, client: &mut TraciClient, poi_id: &str, layer: i32) -> Result<(), TraciError> {
+        crate::with_context!("poi.remove", poi_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(layer);
+            client.create_command(CMD_SET_POI_VARIABLE, REMOVE, poi_id, Some(&add));
+            client.process_set(CMD_SET_POI_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    pub fn subscribe(&self, client: &mut TraciClient, poi_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("poi.subscribe", poi_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_POI_VARIABLE, poi_id, begin, end, vars)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn subscribe_context(&self, client: &mut TraciClient, poi_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("poi.subscribe_context", poi_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_POI_CONTEXT, poi_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn add_sends_the_eight_field_compound() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_POI_VARIABLE)]);
+        let color = TraciColor::new(255, 128, 0, 200);
+        PoiScope::default()
+            .add(&mut client, "marker", 12.5, -3.0, &color, "incident", 2, "icons/warn.png", 4.0, 3.0, 90.0)
+            .unwrap();
+        drop(client);
+
         let mut add = Storage::new();
         add.write_u8(TYPE_COMPOUND);
         add.write_i32(8);
         add.write_u8(TYPE_STRING);
-        add.write_string(poi_type);
+        add.write_string("incident");
         add.write_u8(TYPE_COLOR);
-        add.write_u8(color.r);
-        add.write_u8(color.g);
-        add.write_u8(color.b);
-        add.write_u8(color.a);
+        add.write_packet(&[255, 128, 0, 200]);
         add.write_u8(TYPE_INTEGER);
-        add.write_i32(layer);
+        add.write_i32(2);
         add.write_u8(POSITION_2D);
-        add.write_f64(x);
-        add.write_f64(y);
+        add.write_f64(12.5);
+        add.write_f64(-3.0);
         add.write_u8(TYPE_STRING);
-        add.write_string(img_file);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(width);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(height);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(angle);
-        client.create_command(CMD_SET_POI_VARIABLE, ADD, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
-    }
-
-    pub fn remove(&self, client: &mut TraciClient, poi_id: &str, layer: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(layer);
-        client.create_command(CMD_SET_POI_VARIABLE, REMOVE, poi_id, Some(&add));
-        client.process_set(CMD_SET_POI_VARIABLE)?;
-        Ok(())
+        add.write_string("icons/warn.png");
+        for v in [4.0, 3.0, 90.0] {
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(v);
+        }
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_POI_VARIABLE, ADD, "marker", Some(&add))]);
     }
 
-    pub fn subscribe(&self, client: &mut TraciClient, poi_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_POI_VARIABLE, poi_id, begin, end, vars)
-    }
-
-    pub fn subscribe_context(&self, client: &mut TraciClient, poi_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_POI_CONTEXT, poi_id, begin, end, domain, range, vars)
+    #[test]
+    fn set_image_file_sends_a_string() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_POI_VARIABLE)]);
+        PoiScope::default().set_image_file(&mut client, "marker", "icons/ok.png").unwrap();
+        drop(client);
+        let add = mock::string("icons/ok.png");
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_POI_VARIABLE, VAR_IMAGEFILE, "marker", Some(&add))]);
     }
 }
@@ -20,37 +20,44 @@ impl PolygonScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("polygon.get_id_list", {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("polygon.get_id_count", {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, poly_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_PARAMETER, poly_id, Some(&add));
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("polygon.get_parameter", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_PARAMETER, poly_id, Some(&add));
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, poly_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_PARAMETER, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("polygon.set_parameter", poly_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_PARAMETER, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -58,33 +65,43 @@ impl PolygonScope {
     // -----------------------------------------------------------------------
 
     pub fn get_line_width(&self, client: &mut TraciClient, poly_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_WIDTH, poly_id, None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("polygon.get_line_width", poly_id, {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_WIDTH, poly_id, None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_filled(&self, client: &mut TraciClient, poly_id: &str) -> Result<bool, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_FILL, poly_id, None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_INTEGER))?;
-        Ok(client.read_int_from_input()? != 0)
+        crate::with_context!("polygon.get_filled", poly_id, {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_FILL, poly_id, None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_INTEGER))?;
+            Ok(client.read_int_from_input()? != 0)
+        })
     }
 
     pub fn get_type(&self, client: &mut TraciClient, poly_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_TYPE, poly_id, None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("polygon.get_type", poly_id, {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_TYPE, poly_id, None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_shape(&self, client: &mut TraciClient, poly_id: &str) -> Result<Vec<TraciPosition>, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_SHAPE, poly_id, None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_POLYGON))?;
-        client.read_polygon_from_input()
+        crate::with_context!("polygon.get_shape", poly_id, {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_SHAPE, poly_id, None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_POLYGON))?;
+            client.read_polygon_from_input()
+        })
     }
 
     pub fn get_color(&self, client: &mut TraciClient, poly_id: &str) -> Result<TraciColor, TraciError> {
-        client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_COLOR, poly_id, None);
-        client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_COLOR))?;
-        client.read_color_from_input()
+        crate::with_context!("polygon.get_color", poly_id, {
+            client.create_command(CMD_GET_POLYGON_VARIABLE, VAR_COLOR, poly_id, None);
+            client.process_get(CMD_GET_POLYGON_VARIABLE, Some(TYPE_COLOR))?;
+            client.read_color_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -92,54 +109,64 @@ impl PolygonScope {
     // -----------------------------------------------------------------------
 
     pub fn set_type(&self, client: &mut TraciClient, poly_id: &str, poly_type: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(poly_type);
-        client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_TYPE, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("polygon.set_type", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(poly_type);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_TYPE, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_shape(&self, client: &mut TraciClient, poly_id: &str, shape: &[TraciPosition]) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_POLYGON);
-        if shape.len() < 256 {
-            add.write_u8(shape.len() as u8);
-        } else {
-            add.write_u8(0);
-            add.write_i32(shape.len() as i32);
-        }
-        for p in shape {
-            add.write_f64(p.x);
-            add.write_f64(p.y);
-        }
-        client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_SHAPE, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("polygon.set_shape", poly_id, {
+            let mut add = Storage::new();
+            write_shape(&mut add, shape);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_SHAPE, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_color(&self, client: &mut TraciClient, poly_id: &str, c: &TraciColor) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COLOR);
-        add.write_u8(c.r);
-        add.write_u8(c.g);
-        add.write_u8(c.b);
-        add.write_u8(c.a);
-        client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_COLOR, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("polygon.set_color", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_COLOR, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    pub fn set_filled(&self, client: &mut TraciClient, poly_id: &str, filled: bool) -> Result<(), TraciError> {
+        crate::with_context!("polygon.set_filled", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(filled as i32);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_FILL, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_line_width(&self, client: &mut TraciClient, poly_id: &str, line_width: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(line_width);
-        client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_WIDTH, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
+        crate::with_context!("polygon.set_line_width", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(line_width);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, VAR_WIDTH, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Add a polygon to the simulation.
+    #[allow(clippy::too_many_arguments)]
     pub fn add(
         &self,
         client: &mut TraciClient,
@@ -150,45 +177,129 @@ impl PolygonScope {
         poly_type: &str,
         layer: i32,
     ) -> Result<(), TraciError> {
+        crate::with_context!("polygon.add", poly_id, {
+            let mut add = Storage::new();
+            add.begin_compound(5);
+            add.write_u8(TYPE_STRING);
+            add.write_string(poly_type);
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(color.r);
+            add.write_u8(color.g);
+            add.write_u8(color.b);
+            add.write_u8(color.a);
+            add.write_u8(TYPE_UBYTE);
+            add.write_u8(if fill { 1 } else { 0 });
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(layer);
+            write_shape(&mut add, shape);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, ADD, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    pub fn remove(&self, client: &mut TraciClient, poly_id: &str, layer: i32) -> Result<(), TraciError> {
+        crate::with_context!("polygon.remove", poly_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(layer);
+            client.create_command(CMD_SET_POLYGON_VARIABLE, REMOVE, poly_id, Some(&add));
+            client.process_set(CMD_SET_POLYGON_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    pub fn subscribe(&self, client: &mut TraciClient, poly_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("polygon.subscribe", poly_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_POLYGON_VARIABLE, poly_id, begin, end, vars)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn subscribe_context(&self, client: &mut TraciClient, poly_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("polygon.subscribe_context", poly_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_POLYGON_CONTEXT, poly_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+// ============================================================================
+// Shared helper: TYPE_POLYGON encoding
+// ============================================================================
+
+/// Write `shape` as a type-tagged polygon: a ubyte point count (0 followed by
+/// an int count for more than 255 points), then x and y of every point.
+fn write_shape(add: &mut Storage, shape: &[TraciPosition]) {
+    add.write_u8(TYPE_POLYGON);
+    if shape.len() < 256 {
+        add.write_u8(shape.len() as u8);
+    } else {
+        add.write_u8(0);
+        add.write_i32(shape.len() as i32);
+    }
+    for p in shape {
+        add.write_f64(p.x);
+        add.write_f64(p.y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    fn triangle() -> Vec<TraciPosition> {
+        vec![TraciPosition::new_2d(0.0, 0.0), TraciPosition::new_2d(40.0, 0.0), TraciPosition::new_2d(20.0, 35.5)]
+    }
+
+    #[test]
+    fn a_three_point_shape_round_trips_through_storage() {
+        let mut out = Storage::new();
+        write_shape(&mut out, &triangle());
+        assert_eq!(out.len(), 1 + 1 + 3 * 16);
+
+        let mut input = Storage::from_bytes(out.as_bytes().to_vec());
+        assert_eq!(input.read_u8().unwrap(), TYPE_POLYGON);
+        assert_eq!(input.read_u8().unwrap(), 3);
+        let points: Vec<_> = (0..3)
+            .map(|_| TraciPosition::new_2d(input.read_f64().unwrap(), input.read_f64().unwrap()))
+            .collect();
+        assert_eq!(points, triangle());
+        assert!(!input.valid_pos());
+
+        // The same bytes decode through `get_shape`.
+        let (mut client, server) =
+            mock::connect_replies(vec![mock::get_reply(CMD_GET_POLYGON_VARIABLE, VAR_SHAPE, "zone", out)]);
+        assert_eq!(PolygonScope::default().get_shape(&mut client, "zone").unwrap(), triangle());
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn add_sends_the_five_field_compound() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_POLYGON_VARIABLE)]);
+        PolygonScope::default()
+            .add(&mut client, "zone", &triangle(), &TraciColor::new(0, 0, 255, 128), true, "area", 1)
+            .unwrap();
+        drop(client);
+
         let mut add = Storage::new();
         add.write_u8(TYPE_COMPOUND);
         add.write_i32(5);
         add.write_u8(TYPE_STRING);
-        add.write_string(poly_type);
+        add.write_string("area");
         add.write_u8(TYPE_COLOR);
-        add.write_u8(color.r);
-        add.write_u8(color.g);
-        add.write_u8(color.b);
-        add.write_u8(color.a);
+        add.write_packet(&[0, 0, 255, 128]);
         add.write_u8(TYPE_UBYTE);
-        add.write_u8(if fill { 1 } else { 0 });
+        add.write_u8(1);
         add.write_u8(TYPE_INTEGER);
-        add.write_i32(layer);
+        add.write_i32(1);
         add.write_u8(TYPE_POLYGON);
-        add.write_u8(shape.len() as u8);
-        for p in shape {
+        add.write_u8(3);
+        for p in triangle() {
             add.write_f64(p.x);
             add.write_f64(p.y);
         }
-        client.create_command(CMD_SET_POLYGON_VARIABLE, ADD, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
-    }
-
-    pub fn remove(&self, client: &mut TraciClient, poly_id: &str, layer: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(layer);
-        client.create_command(CMD_SET_POLYGON_VARIABLE, REMOVE, poly_id, Some(&add));
-        client.process_set(CMD_SET_POLYGON_VARIABLE)?;
-        Ok(())
-    }
-
-    pub fn subscribe(&self, client: &mut TraciClient, poly_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_POLYGON_VARIABLE, poly_id, begin, end, vars)
-    }
-
-    pub fn subscribe_context(&self, client: &mut TraciClient, poly_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_POLYGON_CONTEXT, poly_id, begin, end, domain, range, vars)
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_POLYGON_VARIABLE, ADD, "zone", Some(&add))]);
     }
 }
@@ -20,61 +20,103 @@ impl RouteScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_ROUTE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("route.get_id_list", {
+            client.create_command(CMD_GET_ROUTE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_ROUTE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("route.get_id_count", {
+            client.create_command(CMD_GET_ROUTE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, route_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_ROUTE_VARIABLE, VAR_PARAMETER, route_id, Some(&add));
-        client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("route.get_parameter", route_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_ROUTE_VARIABLE, VAR_PARAMETER, route_id, Some(&add));
+            client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, route_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_ROUTE_VARIABLE, VAR_PARAMETER, route_id, Some(&add));
-        client.process_set(CMD_SET_ROUTE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("route.set_parameter", route_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_ROUTE_VARIABLE, VAR_PARAMETER, route_id, Some(&add));
+            client.process_set(CMD_SET_ROUTE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Return the list of edge IDs comprising this route.
     pub fn get_edges(&self, client: &mut TraciClient, route_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_ROUTE_VARIABLE, VAR_EDGES, route_id, None);
-        client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("route.get_edges", route_id, {
+            client.create_command(CMD_GET_ROUTE_VARIABLE, VAR_EDGES, route_id, None);
+            client.process_get(CMD_GET_ROUTE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     /// Add a new route to the simulation.
+    ///
+    /// `edges` must form a connected path and `route_id` must not be in use;
+    /// the route can then be referenced by `VehicleScope::add`.
     pub fn add(&self, client: &mut TraciClient, route_id: &str, edges: &[String]) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRINGLIST);
-        add.write_string_list(edges);
-        client.create_command(CMD_SET_ROUTE_VARIABLE, ADD, route_id, Some(&add));
-        client.process_set(CMD_SET_ROUTE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("route.add", route_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(edges);
+            client.create_command(CMD_SET_ROUTE_VARIABLE, ADD, route_id, Some(&add));
+            client.process_set(CMD_SET_ROUTE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, route_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_ROUTE_VARIABLE, route_id, begin, end, vars)
+        crate::with_context!("route.subscribe", route_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_ROUTE_VARIABLE, route_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, route_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_ROUTE_CONTEXT, route_id, begin, end, domain, range, vars)
+        crate::with_context!("route.subscribe_context", route_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_ROUTE_CONTEXT, route_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn add_prefixes_the_edge_list_with_its_count() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_ROUTE_VARIABLE)]);
+        let edges = ["e1".to_string(), "e2".to_string(), "e3".to_string()];
+        RouteScope::default().add(&mut client, "r0", &edges).unwrap();
+        drop(client);
+
+        let mut add = Storage::new();
+        add.write_u8(TYPE_STRINGLIST);
+        add.write_i32(3);
+        for edge in ["e1", "e2", "e3"] {
+            add.write_i32(2);
+            add.write_packet(edge.as_bytes());
+        }
+        assert_eq!(server.requests(), vec![mock::command(CMD_SET_ROUTE_VARIABLE, ADD, "r0", Some(&add))]);
     }
 }
@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: EPL-2.0
-//! TraCI RouteProbe domain scope (no domain-specific methods in the C++ API).
+//! TraCI RouteProbe domain scope.
 
 use crate::{
     client::TraciClient,
@@ -20,44 +20,85 @@ impl RouteProbeScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("route_probe.get_id_list", {
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("route_probe.get_id_count", {
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, obj_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, VAR_PARAMETER, obj_id, Some(&add));
-        client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("route_probe.get_parameter", obj_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, VAR_PARAMETER, obj_id, Some(&add));
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, obj_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_ROUTEPROBE_VARIABLE, VAR_PARAMETER, obj_id, Some(&add));
-        client.process_set(CMD_SET_ROUTEPROBE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("route_probe.set_parameter", obj_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_ROUTEPROBE_VARIABLE, VAR_PARAMETER, obj_id, Some(&add));
+            client.process_set(CMD_SET_ROUTEPROBE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Return the id of the edge the route probe is placed on.
+    pub fn get_edge_id(&self, client: &mut TraciClient, obj_id: &str) -> Result<String, TraciError> {
+        crate::with_context!("route_probe.get_edge_id", obj_id, {
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, VAR_ROAD_ID, obj_id, None);
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Return a route id sampled from the routes seen in the last completed
+    /// interval, weighted by frequency.
+    pub fn sample_last_interval(&self, client: &mut TraciClient, obj_id: &str) -> Result<String, TraciError> {
+        crate::with_context!("route_probe.sample_last_interval", obj_id, {
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, VAR_SAMPLE_LAST, obj_id, None);
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Return a route id sampled from the routes seen so far in the current
+    /// interval, weighted by frequency.
+    pub fn sample_current(&self, client: &mut TraciClient, obj_id: &str) -> Result<String, TraciError> {
+        crate::with_context!("route_probe.sample_current", obj_id, {
+            client.create_command(CMD_GET_ROUTEPROBE_VARIABLE, VAR_SAMPLE_CURRENT, obj_id, None);
+            client.process_get(CMD_GET_ROUTEPROBE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, obj_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_ROUTEPROBE_VARIABLE, obj_id, begin, end, vars)
+        crate::with_context!("route_probe.subscribe", obj_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_ROUTEPROBE_VARIABLE, obj_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, obj_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_ROUTEPROBE_CONTEXT, obj_id, begin, end, domain, range, vars)
+        crate::with_context!("route_probe.subscribe_context", obj_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_ROUTEPROBE_CONTEXT, obj_id, begin, end, domain, range, vars)
+        })
     }
 }
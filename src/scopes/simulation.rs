@@ -7,8 +7,8 @@ use crate::{
     error::TraciError,
     storage::Storage,
     types::{
-        ContextSubscriptionResults, SubscriptionResults, TraciPosition, TraciRoadPosition,
-        TraciStage,
+        Boundary, ContextSubscriptionResults, SimTime, StepEvents, SubscriptionResults,
+        TraciCollision, TraciPosition, TraciRoadPosition, TraciStage,
     },
 };
 
@@ -26,136 +26,342 @@ impl SimulationScope {
     // Simple getters
     // -----------------------------------------------------------------------
 
+    /// Return the current time in milliseconds (`VAR_TIME_STEP`).
+    ///
+    /// The `i32` overflows after about 24.8 days of simulated time; prefer
+    /// [`Self::get_time_ms`] or [`Self::get_sim_time`] for long runs.
     pub fn get_current_time(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME_STEP, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_current_time", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME_STEP, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_time(&self, client: &mut TraciClient) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("simulation.get_time", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the current time in milliseconds without `i32` overflow.
+    ///
+    /// TraCI has no 64-bit time variable, so this rounds the `VAR_TIME`
+    /// seconds to the nearest millisecond.
+    pub fn get_time_ms(&self, client: &mut TraciClient) -> Result<i64, TraciError> {
+        crate::with_context!("simulation.get_time_ms", {
+            Ok(SimTime::from_secs_f64(crate::scope_get_double!(client, CMD_GET_SIM_VARIABLE, VAR_TIME, "")?).as_millis())
+        })
+    }
+
+    /// Return the current time as a [`SimTime`].
+    pub fn get_sim_time(&self, client: &mut TraciClient) -> Result<SimTime, TraciError> {
+        crate::with_context!("simulation.get_sim_time", {
+            Ok(SimTime::from_secs_f64(crate::scope_get_double!(client, CMD_GET_SIM_VARIABLE, VAR_TIME, "")?))
+        })
     }
 
     pub fn get_loaded_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_LOADED_VEHICLES_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_loaded_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_LOADED_VEHICLES_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_loaded_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_LOADED_VEHICLES_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_loaded_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_LOADED_VEHICLES_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_departed_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_departed_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_departed_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_departed_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_VEHICLES_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_arrived_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_VEHICLES_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_arrived_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_VEHICLES_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_arrived_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_VEHICLES_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_arrived_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_VEHICLES_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_starting_teleport_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_STARTING_VEHICLES_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_starting_teleport_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_STARTING_VEHICLES_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_starting_teleport_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_STARTING_VEHICLES_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_starting_teleport_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_STARTING_VEHICLES_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_ending_teleport_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_ENDING_VEHICLES_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_ending_teleport_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_ENDING_VEHICLES_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_ending_teleport_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_ENDING_VEHICLES_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_ending_teleport_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TELEPORT_ENDING_VEHICLES_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_departed_person_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_departed_person_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_departed_person_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_departed_person_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_DEPARTED_PERSONS_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_arrived_person_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_PERSONS_NUMBER, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_arrived_person_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_PERSONS_NUMBER, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_arrived_person_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_PERSONS_IDS, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_arrived_person_id_list", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_ARRIVED_PERSONS_IDS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_delta_t(&self, client: &mut TraciClient) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_DELTA_T, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("simulation.get_delta_t", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_DELTA_T, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the global traffic scaling factor applied to inserted demand.
+    pub fn get_scale(&self, client: &mut TraciClient) -> Result<f64, TraciError> {
+        crate::with_context!("simulation.get_scale", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_SCALE, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Set the global traffic scaling factor, e.g. `2.0` to insert twice the
+    /// loaded demand.
+    pub fn set_scale(&self, client: &mut TraciClient, scale: f64) -> Result<(), TraciError> {
+        crate::with_context!("simulation.set_scale", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(scale);
+            client.create_command(CMD_SET_SIM_VARIABLE, VAR_SCALE, "", Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn get_net_boundary(&self, client: &mut TraciClient) -> Result<Vec<crate::types::TraciPosition>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_NET_BOUNDING_BOX, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_POLYGON))?;
-        client.read_polygon_from_input()
+        crate::with_context!("simulation.get_net_boundary", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_NET_BOUNDING_BOX, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_POLYGON))?;
+            client.read_polygon_from_input()
+        })
+    }
+
+    /// Return the network bounding box as a [`Boundary`].
+    pub fn get_net_boundary_rect(&self, client: &mut TraciClient) -> Result<Boundary, TraciError> {
+        crate::with_context!("simulation.get_net_boundary_rect", {
+            let corners = crate::scope_get_polygon!(client, CMD_GET_SIM_VARIABLE, VAR_NET_BOUNDING_BOX, "")?;
+            Boundary::from_corners(&corners)
+                .ok_or_else(|| TraciError::Protocol(format!("Expected 2 boundary corners but got {}", corners.len())))
+        })
     }
 
     pub fn get_min_expected_number(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_MIN_EXPECTED_VEHICLES, "", None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_min_expected_number", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_MIN_EXPECTED_VEHICLES, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_option(&self, client: &mut TraciClient, option: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_OPTION, option, None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("simulation.get_option", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_OPTION, option, None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_bus_stop_waiting(&self, client: &mut TraciClient, stop_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_BUS_STOP_WAITING, stop_id, None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("simulation.get_bus_stop_waiting", stop_id, {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_BUS_STOP_WAITING, stop_id, None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_bus_stop_waiting_id_list(&self, client: &mut TraciClient, stop_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_SIM_VARIABLE, VAR_BUS_STOP_WAITING_IDS, stop_id, None);
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("simulation.get_bus_stop_waiting_id_list", stop_id, {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_BUS_STOP_WAITING_IDS, stop_id, None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Return the vehicles whose departure time has passed but which could
+    /// not be inserted yet.
+    ///
+    /// SUMO does not filter this list by route; see
+    /// [`Self::clear_pending_vehicles`] to drop the backlog of one route.
+    pub fn get_pending_vehicles(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("simulation.get_pending_vehicles", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_PENDING_VEHICLES, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Return the collisions that occurred during the last step; empty if
+    /// there were none.
+    pub fn get_collisions(&self, client: &mut TraciClient) -> Result<Vec<TraciCollision>, TraciError> {
+        crate::with_context!("simulation.get_collisions", {
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_COLLISIONS, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_COMPOUND))?;
+            client.read_int_from_input()?; // components count
+            client.read_ubyte_from_input()?;
+            let n = client.read_int_from_input()?;
+            let mut result = Vec::with_capacity(n.max(0) as usize);
+            for _ in 0..n {
+                result.push(read_collision(client)?);
+            }
+            Ok(result)
+        })
+    }
+
+    // -----------------------------------------------------------------------
+    // Generic parameters
+    // -----------------------------------------------------------------------
+
+    /// Return the simulation parameter `key` of `object_id`, e.g.
+    /// `get_parameter(client, "cs0", "chargingStation.totalEnergyCharged")`.
+    pub fn get_parameter(&self, client: &mut TraciClient, object_id: &str, key: &str) -> Result<String, TraciError> {
+        crate::with_context!("simulation.get_parameter", object_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, object_id, Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Return the simulation parameter `key` of `object_id` as a
+    /// `(key, value)` pair, the form used by parameter subscriptions.
+    pub fn get_parameter_with_key(&self, client: &mut TraciClient, object_id: &str, key: &str) -> Result<(String, String), TraciError> {
+        crate::with_context!("simulation.get_parameter_with_key", object_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_PARAMETER_WITH_KEY, object_id, Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_COMPOUND))?;
+            client.read_int_from_input()?; // components count
+            client.read_ubyte_from_input()?;
+            let key = client.read_string_from_input()?;
+            client.read_ubyte_from_input()?;
+            let value = client.read_string_from_input()?;
+            Ok((key, value))
+        })
+    }
+
+    /// Set the simulation parameter `key` of `object_id` to `value`.
+    pub fn set_parameter(&self, client: &mut TraciClient, object_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
+        crate::with_context!("simulation.set_parameter", object_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_SIM_VARIABLE, VAR_PARAMETER, object_id, Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    // -----------------------------------------------------------------------
+    // Time advancement
+    // -----------------------------------------------------------------------
+
+    /// Step the simulation until its time reaches `target_time` (seconds) and
+    /// return the number of steps taken.
+    ///
+    /// Steps one `delta_t` at a time so subscriptions are updated on every
+    /// step. A target in the past takes zero steps; the loop also stops early
+    /// if SUMO ends the simulation.
+    pub fn advance_to(&self, client: &mut TraciClient, target_time: f64) -> Result<usize, TraciError> {
+        crate::with_context!("simulation.advance_to", {
+            let start = crate::scope_get_double!(client, CMD_GET_SIM_VARIABLE, VAR_TIME, "")?;
+            let delta_t = crate::scope_get_double!(client, CMD_GET_SIM_VARIABLE, VAR_DELTA_T, "")?;
+            if delta_t <= 0.0 {
+                return Err(TraciError::Protocol(format!("Invalid simulation step length {delta_t}")));
+            }
+            let mut steps = 0;
+            // The epsilon absorbs floating-point drift in `start + steps * delta_t`;
+            // it is far below SUMO's millisecond time resolution.
+            while start + steps as f64 * delta_t < target_time - 1e-6 {
+                if !client.simulation_step(0.0)? {
+                    break;
+                }
+                steps += 1;
+            }
+            Ok(steps)
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -171,21 +377,22 @@ impl SimulationScope {
         lane_index: i32,
         to_geo: bool,
     ) -> Result<TraciPosition, TraciError> {
-        let pos_type = if to_geo { POSITION_LON_LAT } else { POSITION_2D };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(POSITION_ROADMAP);
-        add.write_string(edge_id);
-        add.write_f64(pos);
-        add.write_u8(lane_index as u8);
-        add.write_u8(TYPE_UBYTE);
-        add.write_u8(pos_type);
-        client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
-        let x = client.read_double_from_input()?;
-        let y = client.read_double_from_input()?;
-        Ok(TraciPosition::new_2d(x, y))
+        crate::with_context!("simulation.convert2d", edge_id, {
+            let pos_type = if to_geo { POSITION_LON_LAT } else { POSITION_2D };
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(edge_id);
+            add.write_f64(pos);
+            add.write_u8(lane_index as u8);
+            add.write_u8(TYPE_UBYTE);
+            add.write_u8(pos_type);
+            client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
+            let x = client.read_double_from_input()?;
+            let y = client.read_double_from_input()?;
+            Ok(TraciPosition::new_2d(x, y))
+        })
     }
 
     /// Convert a road position to a 3-D or geographic+alt position.
@@ -197,22 +404,23 @@ impl SimulationScope {
         lane_index: i32,
         to_geo: bool,
     ) -> Result<TraciPosition, TraciError> {
-        let pos_type = if to_geo { POSITION_LON_LAT_ALT } else { POSITION_3D };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(POSITION_ROADMAP);
-        add.write_string(edge_id);
-        add.write_f64(pos);
-        add.write_u8(lane_index as u8);
-        add.write_u8(TYPE_UBYTE);
-        add.write_u8(pos_type);
-        client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
-        let x = client.read_double_from_input()?;
-        let y = client.read_double_from_input()?;
-        let z = client.read_double_from_input()?;
-        Ok(TraciPosition::new_3d(x, y, z))
+        crate::with_context!("simulation.convert3d", edge_id, {
+            let pos_type = if to_geo { POSITION_LON_LAT_ALT } else { POSITION_3D };
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(edge_id);
+            add.write_f64(pos);
+            add.write_u8(lane_index as u8);
+            add.write_u8(TYPE_UBYTE);
+            add.write_u8(pos_type);
+            client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
+            let x = client.read_double_from_input()?;
+            let y = client.read_double_from_input()?;
+            let z = client.read_double_from_input()?;
+            Ok(TraciPosition::new_3d(x, y, z))
+        })
     }
 
     /// Convert a 2-D (or geo) position to a road position.
@@ -224,23 +432,24 @@ impl SimulationScope {
         is_geo: bool,
         v_class: &str,
     ) -> Result<TraciRoadPosition, TraciError> {
-        let src_pos_type = if is_geo { POSITION_LON_LAT } else { POSITION_2D };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(src_pos_type);
-        add.write_f64(x);
-        add.write_f64(y);
-        add.write_u8(TYPE_UBYTE);
-        add.write_u8(POSITION_ROADMAP);
-        add.write_u8(TYPE_STRING);
-        add.write_string(v_class);
-        client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(POSITION_ROADMAP))?;
-        let edge_id = client.read_string_from_input()?;
-        let pos = client.read_double_from_input()?;
-        let lane_index = client.read_ubyte_from_input()? as i32;
-        Ok(TraciRoadPosition { edge_id, pos, lane_index })
+        crate::with_context!("simulation.convert_road", {
+            let src_pos_type = if is_geo { POSITION_LON_LAT } else { POSITION_2D };
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(src_pos_type);
+            add.write_f64(x);
+            add.write_f64(y);
+            add.write_u8(TYPE_UBYTE);
+            add.write_u8(POSITION_ROADMAP);
+            add.write_u8(TYPE_STRING);
+            add.write_string(v_class);
+            client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(POSITION_ROADMAP))?;
+            let edge_id = client.read_string_from_input()?;
+            let pos = client.read_double_from_input()?;
+            let lane_index = client.read_ubyte_from_input()? as i32;
+            Ok(TraciRoadPosition { edge_id, pos, lane_index })
+        })
     }
 
     /// Convert between geographic and Cartesian positions.
@@ -251,21 +460,9 @@ impl SimulationScope {
         y: f64,
         from_geo: bool,
     ) -> Result<TraciPosition, TraciError> {
-        let pos_type = if from_geo { POSITION_2D } else { POSITION_LON_LAT };
-        let src_pos_type = if from_geo { POSITION_LON_LAT } else { POSITION_2D };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(src_pos_type);
-        add.write_f64(x);
-        add.write_f64(y);
-        add.write_u8(TYPE_UBYTE);
-        add.write_u8(pos_type);
-        client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
-        let rx = client.read_double_from_input()?;
-        let ry = client.read_double_from_input()?;
-        Ok(TraciPosition::new_2d(rx, ry))
+        crate::with_context!("simulation.convert_geo", {
+            convert_geo(client, x, y, from_geo)
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -273,6 +470,7 @@ impl SimulationScope {
     // -----------------------------------------------------------------------
 
     /// Get the distance between two 2-D (or geo) positions.
+    #[allow(clippy::too_many_arguments)]
     pub fn get_distance_2d(
         &self,
         client: &mut TraciClient,
@@ -283,21 +481,22 @@ impl SimulationScope {
         is_geo: bool,
         is_driving: bool,
     ) -> Result<f64, TraciError> {
-        let pos_type = if is_geo { POSITION_LON_LAT } else { POSITION_2D };
-        let dist_type: u8 = if is_driving { REQUEST_DRIVINGDIST } else { REQUEST_AIRDIST };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(pos_type);
-        add.write_f64(x1);
-        add.write_f64(y1);
-        add.write_u8(pos_type);
-        add.write_f64(x2);
-        add.write_f64(y2);
-        add.write_u8(dist_type);
-        client.create_command(CMD_GET_SIM_VARIABLE, DISTANCE_REQUEST, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("simulation.get_distance_2d", {
+            let pos_type = if is_geo { POSITION_LON_LAT } else { POSITION_2D };
+            let dist_type: u8 = if is_driving { REQUEST_DRIVINGDIST } else { REQUEST_AIRDIST };
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(pos_type);
+            add.write_f64(x1);
+            add.write_f64(y1);
+            add.write_u8(pos_type);
+            add.write_f64(x2);
+            add.write_f64(y2);
+            add.write_u8(dist_type);
+            client.create_command(CMD_GET_SIM_VARIABLE, DISTANCE_REQUEST, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Get the distance between two road positions.
@@ -310,22 +509,23 @@ impl SimulationScope {
         pos2: f64,
         is_driving: bool,
     ) -> Result<f64, TraciError> {
-        let dist_type: u8 = if is_driving { REQUEST_DRIVINGDIST } else { REQUEST_AIRDIST };
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(POSITION_ROADMAP);
-        add.write_string(edge_id1);
-        add.write_f64(pos1);
-        add.write_u8(0); // lane
-        add.write_u8(POSITION_ROADMAP);
-        add.write_string(edge_id2);
-        add.write_f64(pos2);
-        add.write_u8(0); // lane
-        add.write_u8(dist_type);
-        client.create_command(CMD_GET_SIM_VARIABLE, DISTANCE_REQUEST, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("simulation.get_distance_road", {
+            let dist_type: u8 = if is_driving { REQUEST_DRIVINGDIST } else { REQUEST_AIRDIST };
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(edge_id1);
+            add.write_f64(pos1);
+            add.write_u8(0); // lane
+            add.write_u8(POSITION_ROADMAP);
+            add.write_string(edge_id2);
+            add.write_f64(pos2);
+            add.write_u8(0); // lane
+            add.write_u8(dist_type);
+            client.create_command(CMD_GET_SIM_VARIABLE, DISTANCE_REQUEST, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -342,57 +542,193 @@ impl SimulationScope {
         pos: f64,
         routing_mode: i32,
     ) -> Result<TraciStage, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(5);
-        add.write_u8(TYPE_STRING);
-        add.write_string(from_edge);
-        add.write_u8(TYPE_STRING);
-        add.write_string(to_edge);
-        add.write_u8(TYPE_STRING);
-        add.write_string(v_type);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(pos);
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(routing_mode);
-        client.create_command(CMD_GET_SIM_VARIABLE, FIND_ROUTE, "", Some(&add));
-        client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_COMPOUND))?;
-        read_traci_stage(client)
+        crate::with_context!("simulation.find_route", {
+            let mut add = Storage::new();
+            add.begin_compound(5);
+            add.write_u8(TYPE_STRING);
+            add.write_string(from_edge);
+            add.write_u8(TYPE_STRING);
+            add.write_string(to_edge);
+            add.write_u8(TYPE_STRING);
+            add.write_string(v_type);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(pos);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(routing_mode);
+            client.create_command(CMD_GET_SIM_VARIABLE, FIND_ROUTE, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_traci_stage(client)
+        })
+    }
+
+    /// Compute a multimodal route for a person, e.g. walk → ride → walk.
+    ///
+    /// `modes` is a space-separated list of `"car"`, `"bicycle"` and
+    /// `"public"`; an empty string allows walking only. `destination_stop`
+    /// optionally names a stop to end at instead of `arrival_pos`. Negative
+    /// `depart`, `speed` and `walk_factor` select SUMO's defaults.
+    #[allow(clippy::too_many_arguments)]
+    pub fn find_intermodal_route(
+        &self,
+        client: &mut TraciClient,
+        from_edge: &str,
+        to_edge: &str,
+        modes: &str,
+        depart: f64,
+        routing_mode: i32,
+        speed: f64,
+        walk_factor: f64,
+        depart_pos: f64,
+        arrival_pos: f64,
+        depart_pos_lat: f64,
+        p_type: &str,
+        v_type: &str,
+        destination_stop: &str,
+    ) -> Result<Vec<TraciStage>, TraciError> {
+        crate::with_context!("simulation.find_intermodal_route", {
+            let mut add = Storage::new();
+            add.begin_compound(13);
+            add.write_u8(TYPE_STRING);
+            add.write_string(from_edge);
+            add.write_u8(TYPE_STRING);
+            add.write_string(to_edge);
+            add.write_u8(TYPE_STRING);
+            add.write_string(modes);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(depart);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(routing_mode);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(walk_factor);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(depart_pos);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(arrival_pos);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(depart_pos_lat);
+            add.write_u8(TYPE_STRING);
+            add.write_string(p_type);
+            add.write_u8(TYPE_STRING);
+            add.write_string(v_type);
+            add.write_u8(TYPE_STRING);
+            add.write_string(destination_stop);
+            client.create_command(CMD_GET_SIM_VARIABLE, FIND_INTERMODAL_ROUTE, "", Some(&add));
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_COMPOUND))?;
+            let n = client.read_int_from_input()?;
+            let mut stages = Vec::with_capacity(n.max(0) as usize);
+            for _ in 0..n {
+                client.read_ubyte_from_input()?; // TYPE_COMPOUND
+                stages.push(read_traci_stage(client)?);
+            }
+            Ok(stages)
+        })
     }
 
     // -----------------------------------------------------------------------
     // State management
     // -----------------------------------------------------------------------
 
+    /// Load a state saved with [`Self::save_state`].
+    ///
+    /// Cached subscription results of every scope are discarded since they
+    /// describe the replaced state; new ones arrive with the next step.
     pub fn load_state(&self, client: &mut TraciClient, path: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(path);
-        client.create_command(CMD_SET_SIM_VARIABLE, CMD_LOAD_SIMSTATE, "", Some(&add));
-        client.process_set(CMD_SET_SIM_VARIABLE)?;
-        Ok(())
+        crate::with_context!("simulation.load_state", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(path);
+            client.create_command(CMD_SET_SIM_VARIABLE, CMD_LOAD_SIMSTATE, "", Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            client.clear_subscription_results();
+            Ok(())
+        })
     }
 
     pub fn save_state(&self, client: &mut TraciClient, destination: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(destination);
-        client.create_command(CMD_SET_SIM_VARIABLE, CMD_SAVE_SIMSTATE, "", Some(&add));
-        client.process_set(CMD_SET_SIM_VARIABLE)?;
-        Ok(())
+        crate::with_context!("simulation.save_state", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(destination);
+            client.create_command(CMD_SET_SIM_VARIABLE, CMD_SAVE_SIMSTATE, "", Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Save the current state like [`Self::save_state`] and return the
+    /// simulation time (s) it was saved at.
+    pub fn save_state_with_time(&self, client: &mut TraciClient, destination: &str) -> Result<f64, TraciError> {
+        crate::with_context!("simulation.save_state_with_time", {
+            crate::scope_set_string!(client, CMD_SET_SIM_VARIABLE, CMD_SAVE_SIMSTATE, "", destination)?;
+            crate::scope_get_double!(client, CMD_GET_SIM_VARIABLE, VAR_TIME, "")
+        })
     }
 
     pub fn write_message(&self, client: &mut TraciClient, msg: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(msg);
-        client.create_command(CMD_SET_SIM_VARIABLE, CMD_MESSAGE, "", Some(&add));
-        client.process_set(CMD_SET_SIM_VARIABLE)?;
-        Ok(())
+        crate::with_context!("simulation.write_message", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(msg);
+            client.create_command(CMD_SET_SIM_VARIABLE, CMD_MESSAGE, "", Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Drop the vehicles waiting for insertion on `route_id`, or all waiting
+    /// vehicles if `route_id` is empty.
+    pub fn clear_pending_vehicles(&self, client: &mut TraciClient, route_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("simulation.clear_pending_vehicles", route_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(route_id);
+            client.create_command(CMD_SET_SIM_VARIABLE, CMD_CLEAR_PENDING_VEHICLES, "", Some(&add));
+            client.process_set(CMD_SET_SIM_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_SIM_VARIABLE, "", begin, end, vars)
+        crate::with_context!("simulation.subscribe", {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_SIM_VARIABLE, "", begin, end, vars)
+        })
+    }
+
+    // -----------------------------------------------------------------------
+    // Subscribed step events
+    // -----------------------------------------------------------------------
+
+    /// Return the vehicles departed in the last step from the cached
+    /// `VAR_DEPARTED_VEHICLES_IDS` subscription, or `None` if not subscribed.
+    pub fn get_subscribed_departed_ids(&self) -> Option<&[String]> {
+        self.get_subscribed_string_list("", VAR_DEPARTED_VEHICLES_IDS)
+    }
+
+    /// Return the vehicles arrived in the last step from the cached
+    /// `VAR_ARRIVED_VEHICLES_IDS` subscription, or `None` if not subscribed.
+    pub fn get_subscribed_arrived_ids(&self) -> Option<&[String]> {
+        self.get_subscribed_string_list("", VAR_ARRIVED_VEHICLES_IDS)
+    }
+
+    /// Collect the lifecycle changes of the last step from the cached
+    /// simulation subscription, without another request.
+    ///
+    /// Lists whose variable was not subscribed are left empty; subscribe to
+    /// e.g. `VAR_DEPARTED_VEHICLES_IDS` and `VAR_ARRIVED_VEHICLES_IDS` with
+    /// [`Self::subscribe`] first.
+    pub fn step_events(&self) -> StepEvents {
+        let list = |var| self.get_subscribed_string_list("", var).map(<[String]>::to_vec).unwrap_or_default();
+        StepEvents {
+            departed: list(VAR_DEPARTED_VEHICLES_IDS),
+            arrived: list(VAR_ARRIVED_VEHICLES_IDS),
+            teleport_started: list(VAR_TELEPORT_STARTING_VEHICLES_IDS),
+            teleport_ended: list(VAR_TELEPORT_ENDING_VEHICLES_IDS),
+            collisions: list(VAR_COLLIDING_VEHICLES_IDS),
+            departed_persons: list(VAR_DEPARTED_PERSONS_IDS),
+            arrived_persons: list(VAR_ARRIVED_PERSONS_IDS),
+        }
     }
 }
 
@@ -459,3 +795,317 @@ pub(crate) fn read_traci_stage(client: &mut TraciClient) -> Result<TraciStage, T
         description,
     })
 }
+
+// ============================================================================
+// Shared helper: geo <-> Cartesian conversion on the server
+// ============================================================================
+
+/// Convert between geographic and Cartesian positions via `POSITION_CONVERSION`.
+/// Free function so callers outside the scope (e.g. the coordinate converter)
+/// can use it without borrowing `client.simulation`.
+pub(crate) fn convert_geo(client: &mut TraciClient, x: f64, y: f64, from_geo: bool) -> Result<TraciPosition, TraciError> {
+    let pos_type = if from_geo { POSITION_2D } else { POSITION_LON_LAT };
+    let src_pos_type = if from_geo { POSITION_LON_LAT } else { POSITION_2D };
+    let mut add = Storage::new();
+    add.begin_compound(2);
+    add.write_u8(src_pos_type);
+    add.write_f64(x);
+    add.write_f64(y);
+    add.write_u8(TYPE_UBYTE);
+    add.write_u8(pos_type);
+    client.create_command(CMD_GET_SIM_VARIABLE, POSITION_CONVERSION, "", Some(&add));
+    client.process_get(CMD_GET_SIM_VARIABLE, Some(pos_type))?;
+    let rx = client.read_double_from_input()?;
+    let ry = client.read_double_from_input()?;
+    Ok(TraciPosition::new_2d(rx, ry))
+}
+
+// ============================================================================
+// Shared helper: read one TraciCollision from client.input
+// ============================================================================
+
+/// Read the 9 typed fields of one collision from the input buffer.
+fn read_collision(client: &mut TraciClient) -> Result<TraciCollision, TraciError> {
+    client.read_ubyte_from_input()?;
+    let collider = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let victim = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let collider_type = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let victim_type = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let collider_speed = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let victim_speed = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let type_ = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let lane = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let pos = client.read_double_from_input()?;
+
+    Ok(TraciCollision {
+        collider,
+        victim,
+        collider_type,
+        victim_type,
+        collider_speed,
+        victim_speed,
+        type_,
+        lane,
+        pos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    /// Replies to the `VAR_TIME` and `VAR_DELTA_T` queries of `advance_to`
+    /// followed by `steps` empty simulation steps.
+    fn advance_script(time: f64, delta_t: f64, steps: usize) -> Vec<Storage> {
+        let mut script = vec![
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_TIME, "", mock::double(time)),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_DELTA_T, "", mock::double(delta_t)),
+        ];
+        script.extend((0..steps).map(|_| mock::step_reply(&[])));
+        script
+    }
+
+    #[test]
+    fn advance_to_takes_one_step_per_delta_t() {
+        let (mut client, server) = mock::connect_replies(advance_script(0.0, 1.0, 5));
+        assert_eq!(SimulationScope::default().advance_to(&mut client, 5.0).unwrap(), 5);
+        drop(client);
+        assert_eq!(server.requests().len(), 2 + 5);
+    }
+
+    #[test]
+    fn advance_to_does_not_stop_short_of_a_target_between_steps() {
+        let (mut client, server) = mock::connect_replies(advance_script(0.0, 1.0, 5));
+        assert_eq!(SimulationScope::default().advance_to(&mut client, 4.4).unwrap(), 5);
+        drop(client);
+        assert_eq!(server.requests().len(), 2 + 5);
+    }
+
+    #[test]
+    fn advance_to_a_past_time_takes_no_steps() {
+        let (mut client, server) = mock::connect_replies(advance_script(10.0, 0.5, 0));
+        assert_eq!(SimulationScope::default().advance_to(&mut client, 3.0).unwrap(), 0);
+        drop(client);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    /// Encode `collisions` as SUMO sends `VAR_COLLISIONS`: one flat compound
+    /// holding the count and nine fields per collision.
+    fn collisions_value(collisions: &[TraciCollision]) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(1 + 9 * collisions.len() as i32);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(collisions.len() as i32);
+        for c in collisions {
+            for text in [&c.collider, &c.victim, &c.collider_type, &c.victim_type] {
+                s.write_u8(TYPE_STRING);
+                s.write_string(text);
+            }
+            for speed in [c.collider_speed, c.victim_speed] {
+                s.write_u8(TYPE_DOUBLE);
+                s.write_f64(speed);
+            }
+            for text in [&c.type_, &c.lane] {
+                s.write_u8(TYPE_STRING);
+                s.write_string(text);
+            }
+            s.write_u8(TYPE_DOUBLE);
+            s.write_f64(c.pos);
+        }
+        s
+    }
+
+    #[test]
+    fn get_collisions_decodes_a_rear_end_collision() {
+        let collision = TraciCollision {
+            collider: "veh1".into(),
+            victim: "veh0".into(),
+            collider_type: "passenger".into(),
+            victim_type: "truck".into(),
+            collider_speed: 12.5,
+            victim_speed: 3.0,
+            type_: "collision".into(),
+            lane: "e0_0".into(),
+            pos: 87.25,
+        };
+        let reply = mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_COLLISIONS, "", collisions_value(std::slice::from_ref(&collision)));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(SimulationScope::default().get_collisions(&mut client).unwrap(), vec![collision]);
+        drop(client);
+        assert_eq!(server.requests(), vec![mock::command(CMD_GET_SIM_VARIABLE, VAR_COLLISIONS, "", None)]);
+    }
+
+    #[test]
+    fn get_collisions_without_collisions_is_empty() {
+        let reply = mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_COLLISIONS, "", collisions_value(&[]));
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        assert!(SimulationScope::default().get_collisions(&mut client).unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_parameter_with_key_decodes_the_key_and_value() {
+        let mut pair = Storage::new();
+        pair.begin_compound(2);
+        pair.write_u8(TYPE_STRING);
+        pair.write_string("chargingStation.totalEnergyCharged");
+        pair.write_u8(TYPE_STRING);
+        pair.write_string("1520.5");
+        let reply = mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER_WITH_KEY, "cs0", pair);
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let (key, value) = SimulationScope::default()
+            .get_parameter_with_key(&mut client, "cs0", "chargingStation.totalEnergyCharged")
+            .unwrap();
+        assert_eq!(key, "chargingStation.totalEnergyCharged");
+        assert_eq!(value, "1520.5");
+        drop(client);
+        let request = mock::command(
+            CMD_GET_SIM_VARIABLE,
+            VAR_PARAMETER_WITH_KEY,
+            "cs0",
+            Some(&mock::string("chargingStation.totalEnergyCharged")),
+        );
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn clear_pending_vehicles_writes_the_route_filter() {
+        let (mut client, server) =
+            mock::connect_replies(vec![mock::status(CMD_SET_SIM_VARIABLE), mock::status(CMD_SET_SIM_VARIABLE)]);
+        let scope = SimulationScope::default();
+        scope.clear_pending_vehicles(&mut client, "r_main").unwrap();
+        scope.clear_pending_vehicles(&mut client, "").unwrap();
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_SET_SIM_VARIABLE, CMD_CLEAR_PENDING_VEHICLES, "", Some(&mock::string("r_main"))),
+                mock::command(CMD_SET_SIM_VARIABLE, CMD_CLEAR_PENDING_VEHICLES, "", Some(&mock::string(""))),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_pending_vehicles_reads_a_string_list() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_SIM_VARIABLE,
+            VAR_PENDING_VEHICLES,
+            "",
+            mock::string_list(&["flow0.3", "flow0.4"]),
+        )]);
+        assert_eq!(SimulationScope::default().get_pending_vehicles(&mut client).unwrap(), ["flow0.3", "flow0.4"]);
+        drop(client);
+        assert_eq!(server.requests(), vec![mock::command(CMD_GET_SIM_VARIABLE, VAR_PENDING_VEHICLES, "", None)]);
+    }
+
+    #[test]
+    fn save_state_with_time_returns_the_reported_time() {
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::status(CMD_SET_SIM_VARIABLE),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_TIME, "", mock::double(360.0)),
+        ]);
+        assert_eq!(SimulationScope::default().save_state_with_time(&mut client, "state.xml").unwrap(), 360.0);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_SET_SIM_VARIABLE, CMD_SAVE_SIMSTATE, "", Some(&mock::string("state.xml"))),
+                mock::command(CMD_GET_SIM_VARIABLE, VAR_TIME, "", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_state_empties_every_scope_result_map() {
+        use crate::types::{TraciResults, TraciValue};
+
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_SIM_VARIABLE)]);
+        let domains: Vec<_> = crate::client::domain_map().into_values().collect();
+        for &domain in &domains {
+            let results = TraciResults::from([(VAR_SPEED, TraciValue::Double(1.0))]);
+            client.subscription_cache(domain).insert("obj".into(), results.clone());
+            client
+                .context_subscription_cache(domain)
+                .insert("ego".into(), SubscriptionResults::from([("obj".to_string(), results)]));
+        }
+
+        SimulationScope::default().load_state(&mut client, "state.xml").unwrap();
+        for &domain in &domains {
+            assert!(client.subscription_cache(domain).is_empty(), "{domain:?} variable results kept");
+            assert!(client.context_subscription_cache(domain).is_empty(), "{domain:?} context results kept");
+        }
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn find_intermodal_route_decodes_walk_ride_walk() {
+        let stage = |type_, line: &str, edges: &[&str], travel_time| TraciStage {
+            type_,
+            line: line.to_string(),
+            edges: edges.iter().map(|e| e.to_string()).collect(),
+            travel_time,
+            cost: travel_time,
+            length: 10.0 * travel_time,
+            ..TraciStage::default()
+        };
+        let stages = vec![
+            stage(STAGE_WALKING, "", &["home", "stop_a"], 60.0),
+            TraciStage {
+                v_type: "bus".into(),
+                dest_stop: "stop_b".into(),
+                ..stage(STAGE_DRIVING, "line_7", &["stop_a", "mid", "stop_b"], 300.0)
+            },
+            stage(STAGE_WALKING, "", &["stop_b", "work"], 90.0),
+        ];
+        let mut value = Storage::new();
+        value.begin_compound(stages.len() as i32);
+        for s in &stages {
+            value.write_packet(mock::stage(s).as_bytes());
+        }
+        let (mut client, server) =
+            mock::connect_replies(vec![mock::get_reply(CMD_GET_SIM_VARIABLE, FIND_INTERMODAL_ROUTE, "", value)]);
+
+        let route = SimulationScope::default()
+            .find_intermodal_route(&mut client, "home", "work", "public", -1.0, 0, -1.0, -1.0, 0.0, 0.0, 0.0, "", "", "")
+            .unwrap();
+        assert_eq!(route, stages);
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0][1..3], [CMD_GET_SIM_VARIABLE, FIND_INTERMODAL_ROUTE]);
+    }
+
+    #[test]
+    fn get_time_ms_goes_past_the_i32_millisecond_range() {
+        let reply = mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_TIME, "", mock::double(2_147_483.648));
+        let (mut client, server) = mock::connect_replies(vec![reply.clone(), reply]);
+
+        let sim = SimulationScope::default();
+        assert_eq!(sim.get_time_ms(&mut client).unwrap(), i32::MAX as i64 + 1);
+        assert_eq!(sim.get_sim_time(&mut client).unwrap(), SimTime::from_millis(i32::MAX as i64 + 1));
+        drop(client);
+        let query = mock::command(CMD_GET_SIM_VARIABLE, VAR_TIME, "", None);
+        assert_eq!(server.requests(), vec![query.clone(), query]);
+    }
+
+    #[test]
+    fn get_time_ms_errors_name_only_get_time_ms() {
+        let (mut client, _server) = mock::connect_replies(vec![mock::error_status(CMD_GET_SIM_VARIABLE, "no time")]);
+        match SimulationScope::default().get_time_ms(&mut client).unwrap_err() {
+            TraciError::SimulationError { context, .. } => assert_eq!(context, "simulation.get_time_ms()"),
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+}
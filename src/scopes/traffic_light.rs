@@ -8,6 +8,7 @@ use crate::{
     storage::Storage,
     types::{
         ContextSubscriptionResults, SubscriptionResults, TraciLink, TraciLogic, TraciPhase,
+        TraciSignalConstraint,
     },
 };
 
@@ -22,30 +23,41 @@ impl TrafficLightScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("traffic_lights.get_id_list", {
+            client.create_command(CMD_GET_TL_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("traffic_lights.get_id_count", {
+            client.create_command(CMD_GET_TL_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, tls_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_TL_VARIABLE, VAR_PARAMETER, tls_id, Some(&add));
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("traffic_lights.get_parameter", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_TL_VARIABLE, VAR_PARAMETER, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, tls_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_parameter", tls_id, {
+            self.send_parameter(client, tls_id, key, value)
+        })
+    }
+
+    fn send_parameter(&self, client: &mut TraciClient, tls_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
+        add.begin_compound(2);
         add.write_u8(TYPE_STRING);
         add.write_string(key);
         add.write_u8(TYPE_STRING);
@@ -60,273 +72,695 @@ impl TrafficLightScope {
     // -----------------------------------------------------------------------
 
     pub fn get_red_yellow_green_state(&self, client: &mut TraciClient, tls_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_RED_YELLOW_GREEN_STATE, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("traffic_lights.get_red_yellow_green_state", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_RED_YELLOW_GREEN_STATE, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_program(&self, client: &mut TraciClient, tls_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_CURRENT_PROGRAM, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("traffic_lights.get_program", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CURRENT_PROGRAM, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_phase(&self, client: &mut TraciClient, tls_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_CURRENT_PHASE, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("traffic_lights.get_phase", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CURRENT_PHASE, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_phase_name(&self, client: &mut TraciClient, tls_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, VAR_NAME, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("traffic_lights.get_phase_name", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, VAR_NAME, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_phase_duration(&self, client: &mut TraciClient, tls_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_PHASE_DURATION, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("traffic_lights.get_phase_duration", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_PHASE_DURATION, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_next_switch(&self, client: &mut TraciClient, tls_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_NEXT_SWITCH, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("traffic_lights.get_next_switch", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_NEXT_SWITCH, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the time already spent in the current phase, in seconds.
+    pub fn get_spent_duration(&self, client: &mut TraciClient, tls_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("traffic_lights.get_spent_duration", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_SPENT_DURATION, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the time left until the next phase switch, in seconds:
+    /// [`Self::get_next_switch`] minus the current simulation time.
+    pub fn get_time_to_switch(&self, client: &mut TraciClient, tls_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("traffic_lights.get_time_to_switch", tls_id, {
+            let next_switch = crate::scope_get_double!(client, CMD_GET_TL_VARIABLE, TL_NEXT_SWITCH, tls_id)?;
+            // Get current time inline to avoid borrow issues
+            client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME, "", None);
+            client.process_get(CMD_GET_SIM_VARIABLE, Some(TYPE_DOUBLE))?;
+            let now = client.read_double_from_input()?;
+            Ok(next_switch - now)
+        })
     }
 
     pub fn get_served_person_count(&self, client: &mut TraciClient, tls_id: &str, index: i32) -> Result<i32, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(index);
-        client.create_command(CMD_GET_TL_VARIABLE, VAR_PERSON_NUMBER, tls_id, Some(&add));
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("traffic_lights.get_served_person_count", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(index);
+            client.create_command(CMD_GET_TL_VARIABLE, VAR_PERSON_NUMBER, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_controlled_lanes(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_CONTROLLED_LANES, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("traffic_lights.get_controlled_lanes", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CONTROLLED_LANES, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
-    /// Returns the complete list of program logics (phase definitions) for a traffic light.
-    pub fn get_all_program_logics(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<TraciLogic>, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_COMPLETE_DEFINITION_RYG, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+    /// Return the vehicles that block the intersection at link `link_index`.
+    pub fn get_blocking_vehicles(&self, client: &mut TraciClient, tls_id: &str, link_index: i32) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("traffic_lights.get_blocking_vehicles", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(link_index);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_BLOCKING_VEHICLES, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
 
-        let logic_no = client.read_int_from_input()?;
-        let mut logics = Vec::with_capacity(logic_no as usize);
+    /// Return the vehicles competing with link `link_index` for right of way.
+    pub fn get_rival_vehicles(&self, client: &mut TraciClient, tls_id: &str, link_index: i32) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("traffic_lights.get_rival_vehicles", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(link_index);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_RIVAL_VEHICLES, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
 
-        for _ in 0..logic_no {
-            // header compound
-            client.read_ubyte_from_input()?; // TYPE_COMPOUND
-            client.read_int_from_input()?;   // element count
+    /// Return the vehicles with priority over link `link_index`.
+    pub fn get_priority_vehicles(&self, client: &mut TraciClient, tls_id: &str, link_index: i32) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("traffic_lights.get_priority_vehicles", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(link_index);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_PRIORITY_VEHICLES, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
 
-            client.read_ubyte_from_input()?; // TYPE_STRING
-            let program_id = client.read_string_from_input()?;
+    /// Return the ids of the junctions controlled by the traffic light.
+    pub fn get_controlled_junctions(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("traffic_lights.get_controlled_junctions", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CONTROLLED_JUNCTIONS, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
 
-            client.read_ubyte_from_input()?; // TYPE_INTEGER
-            let type_ = client.read_int_from_input()?;
+    /// Returns the complete list of program logics (phase definitions) for a traffic light.
+    pub fn get_all_program_logics(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<TraciLogic>, TraciError> {
+        crate::with_context!("traffic_lights.get_all_program_logics", tls_id, {
+            client.create_command(CMD_GET_TL_VARIABLE, TL_COMPLETE_DEFINITION_RYG, tls_id, None);
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_logic_list(client.input_mut())
+        })
+    }
 
-            client.read_ubyte_from_input()?; // TYPE_INTEGER
-            let phase_index = client.read_int_from_input()?;
+    /// Returns the controlled links for each signal group.
+    pub fn get_controlled_links(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<Vec<TraciLink>>, TraciError> {
+        crate::with_context!("traffic_lights.get_controlled_links", tls_id, {
+            self.query_controlled_links(client, tls_id)
+        })
+    }
 
-            client.read_ubyte_from_input()?; // TYPE_INTEGER
-            let phase_number = client.read_int_from_input()?;
+    fn query_controlled_links(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<Vec<TraciLink>>, TraciError> {
+        client.create_command(CMD_GET_TL_VARIABLE, TL_CONTROLLED_LINKS, tls_id, None);
+        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+        read_link_list(client.input_mut())
+    }
 
-            let mut phases = Vec::with_capacity(phase_number as usize);
-            for _ in 0..phase_number {
-                client.read_ubyte_from_input()?; // TYPE_COMPOUND
-                client.read_int_from_input()?;   // element count
+    /// Return every controlled link paired with its index into the
+    /// red-yellow-green state string.
+    ///
+    /// Flattens [`Self::get_controlled_links`]; a state index appears once per
+    /// link it controls and not at all if it controls none.
+    pub fn get_link_index_map(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<(usize, TraciLink)>, TraciError> {
+        crate::with_context!("traffic_lights.get_link_index_map", tls_id, {
+            let links = self.query_controlled_links(client, tls_id)?;
+            Ok(links
+                .into_iter()
+                .enumerate()
+                .flat_map(|(index, group)| group.into_iter().map(move |link| (index, link)))
+                .collect())
+        })
+    }
 
-                client.read_ubyte_from_input()?; // TYPE_DOUBLE
-                let duration = client.read_double_from_input()?;
+    // -----------------------------------------------------------------------
+    // Setters
+    // -----------------------------------------------------------------------
 
-                client.read_ubyte_from_input()?; // TYPE_STRING
-                let state = client.read_string_from_input()?;
+    pub fn set_red_yellow_green_state(&self, client: &mut TraciClient, tls_id: &str, state: &str) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_red_yellow_green_state", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(state);
+            client.create_command(CMD_SET_TL_VARIABLE, TL_RED_YELLOW_GREEN_STATE, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
+    }
 
-                client.read_ubyte_from_input()?; // TYPE_DOUBLE
-                let min_dur = client.read_double_from_input()?;
+    pub fn set_phase(&self, client: &mut TraciClient, tls_id: &str, index: i32) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_phase", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(index);
+            client.create_command(CMD_SET_TL_VARIABLE, TL_PHASE_INDEX, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
+    }
 
-                client.read_ubyte_from_input()?; // TYPE_DOUBLE
-                let max_dur = client.read_double_from_input()?;
+    pub fn set_phase_name(&self, client: &mut TraciClient, tls_id: &str, name: &str) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_phase_name", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(name);
+            client.create_command(CMD_SET_TL_VARIABLE, VAR_NAME, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
+    }
 
-                client.read_ubyte_from_input()?; // TYPE_COMPOUND (next indices)
-                let num_next = client.read_int_from_input()?;
-                let mut next = Vec::with_capacity(num_next as usize);
-                for _ in 0..num_next {
-                    client.read_ubyte_from_input()?; // TYPE_INTEGER
-                    next.push(client.read_int_from_input()?);
-                }
+    pub fn set_program(&self, client: &mut TraciClient, tls_id: &str, program_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_program", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(program_id);
+            client.create_command(CMD_SET_TL_VARIABLE, TL_PROGRAM, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
+    }
 
-                client.read_ubyte_from_input()?; // TYPE_STRING
-                let name = client.read_string_from_input()?;
+    pub fn set_phase_duration(&self, client: &mut TraciClient, tls_id: &str, phase_duration: f64) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_phase_duration", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(phase_duration);
+            client.create_command(CMD_SET_TL_VARIABLE, TL_PHASE_DURATION, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
+    }
 
-                phases.push(TraciPhase { duration, state, min_dur, max_dur, next, name });
-            }
+    /// Upload a complete traffic light program logic to the server.
+    ///
+    /// Every phase is sent with its `min_dur`, `max_dur` and `next` indices,
+    /// so actuated programs round-trip through [`Self::get_all_program_logics`].
+    pub fn set_program_logic(&self, client: &mut TraciClient, tls_id: &str, logic: &TraciLogic) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_program_logic", tls_id, {
+            let mut add = Storage::new();
+            add.begin_compound(5);
 
-            client.read_ubyte_from_input()?; // TYPE_COMPOUND (params)
-            let param_number = client.read_int_from_input()?;
-            let mut sub_parameter = std::collections::HashMap::new();
-            for _ in 0..param_number {
-                client.read_ubyte_from_input()?; // TYPE_STRINGLIST
-                let pair = client.read_string_list_from_input()?;
-                if pair.len() >= 2 {
-                    sub_parameter.insert(pair[0].clone(), pair[1].clone());
+            add.write_u8(TYPE_STRING);
+            add.write_string(&logic.program_id);
+
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(logic.type_);
+
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(logic.current_phase_index);
+
+            // phases compound
+            add.begin_compound(logic.phases.len() as i32);
+            for p in &logic.phases {
+                add.begin_compound(6);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(p.duration);
+                add.write_u8(TYPE_STRING);
+                add.write_string(&p.state);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(p.min_dur);
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(p.max_dur);
+                // next indices compound
+                add.begin_compound(p.next.len() as i32);
+                for &n in &p.next {
+                    add.write_u8(TYPE_INTEGER);
+                    add.write_i32(n);
                 }
+                add.write_u8(TYPE_STRING);
+                add.write_string(&p.name);
             }
 
-            logics.push(TraciLogic {
-                program_id,
-                type_,
-                current_phase_index: phase_index,
-                phases,
-                sub_parameter,
-            });
-        }
+            // sub-parameters compound
+            add.begin_compound(logic.sub_parameter.len() as i32);
+            for (k, v) in &logic.sub_parameter {
+                add.write_u8(TYPE_STRINGLIST);
+                add.write_i32(2);
+                add.write_string(k);
+                add.write_string(v);
+            }
 
-        Ok(logics)
+            client.create_command(CMD_SET_TL_VARIABLE, TL_COMPLETE_PROGRAM_RYG, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    /// Returns the controlled links for each signal group.
-    pub fn get_controlled_links(&self, client: &mut TraciClient, tls_id: &str) -> Result<Vec<Vec<TraciLink>>, TraciError> {
-        client.create_command(CMD_GET_TL_VARIABLE, TL_CONTROLLED_LINKS, tls_id, None);
-        client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+    // -----------------------------------------------------------------------
+    // NEMA controller
+    // -----------------------------------------------------------------------
 
-        client.read_ubyte_from_input()?; // outer TYPE_COMPOUND (skip type tag)
-        client.read_int_from_input()?;   // total element count
-
-        let link_no = client.read_int_from_input()?;
-        let mut result = Vec::with_capacity(link_no as usize);
-
-        for _ in 0..link_no {
-            client.read_ubyte_from_input()?; // TYPE_COMPOUND (inner)
-            let no = client.read_int_from_input()?;
-            let mut group = Vec::with_capacity(no as usize);
-            for _ in 0..no {
-                client.read_ubyte_from_input()?; // TYPE_COMPOUND
-                client.read_int_from_input()?;   // 3 strings
-                let from_lane = client.read_string_from_input()?;
-                let to_lane = client.read_string_from_input()?;
-                let via_lane = client.read_string_from_input()?;
-                group.push(TraciLink { from_lane, via_lane, to_lane });
-            }
-            result.push(group);
-        }
+    /// Set the split of each of the eight NEMA phases, in seconds.
+    pub fn set_nema_splits(&self, client: &mut TraciClient, tls_id: &str, splits: &[f64]) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_nema_splits", tls_id, {
+            self.send_parameter(client, tls_id, "NEMA.splits", &join_durations(splits))
+        })
+    }
+
+    /// Set the maximum green time of each of the eight NEMA phases, in seconds.
+    pub fn set_nema_max_greens(&self, client: &mut TraciClient, tls_id: &str, max_greens: &[f64]) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_nema_max_greens", tls_id, {
+            self.send_parameter(client, tls_id, "NEMA.maxGreens", &join_durations(max_greens))
+        })
+    }
+
+    /// Set the cycle length of a NEMA controller, in seconds.
+    pub fn set_nema_cycle_length(&self, client: &mut TraciClient, tls_id: &str, cycle_length: f64) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_nema_cycle_length", tls_id, {
+            self.send_parameter(client, tls_id, "NEMA.cycleLength", &cycle_length.to_string())
+        })
+    }
 
-        Ok(result)
+    /// Set the offset of a NEMA controller, in seconds.
+    pub fn set_nema_offset(&self, client: &mut TraciClient, tls_id: &str, offset: f64) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.set_nema_offset", tls_id, {
+            self.send_parameter(client, tls_id, "NEMA.offset", &offset.to_string())
+        })
     }
 
     // -----------------------------------------------------------------------
-    // Setters
+    // Rail signal constraints
     // -----------------------------------------------------------------------
 
-    pub fn set_red_yellow_green_state(&self, client: &mut TraciClient, tls_id: &str, state: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(state);
-        client.create_command(CMD_SET_TL_VARIABLE, TL_RED_YELLOW_GREEN_STATE, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+    /// Return the constraints of rail signal `tls_id` that apply to
+    /// `trip_id`, or all of its constraints if `trip_id` is empty.
+    pub fn get_constraints(&self, client: &mut TraciClient, tls_id: &str, trip_id: &str) -> Result<Vec<TraciSignalConstraint>, TraciError> {
+        crate::with_context!("traffic_lights.get_constraints", tls_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(trip_id);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CONSTRAINT, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_signal_constraints(client)
+        })
     }
 
-    pub fn set_phase(&self, client: &mut TraciClient, tls_id: &str, index: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(index);
-        client.create_command(CMD_SET_TL_VARIABLE, TL_PHASE_INDEX, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+    /// Return the constraints that have `foe_signal` as their foe signal and
+    /// `foe_id` as their foe trip, or any foe trip if `foe_id` is empty.
+    pub fn get_constraints_by_foe(&self, client: &mut TraciClient, foe_signal: &str, foe_id: &str) -> Result<Vec<TraciSignalConstraint>, TraciError> {
+        crate::with_context!("traffic_lights.get_constraints_by_foe", foe_signal, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(foe_id);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CONSTRAINT_BYFOE, foe_signal, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_signal_constraints(client)
+        })
     }
 
-    pub fn set_phase_name(&self, client: &mut TraciClient, tls_id: &str, name: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(name);
-        client.create_command(CMD_SET_TL_VARIABLE, VAR_NAME, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+    /// Reverse the constraint of `tls_id` in which `trip_id` waits for
+    /// `foe_id` at `foe_signal`. Returns the constraints added by SUMO to
+    /// avoid deadlocks caused by the swap.
+    pub fn swap_constraints(&self, client: &mut TraciClient, tls_id: &str, trip_id: &str, foe_signal: &str, foe_id: &str) -> Result<Vec<TraciSignalConstraint>, TraciError> {
+        crate::with_context!("traffic_lights.swap_constraints", tls_id, {
+            let add = constraint_key(trip_id, foe_signal, foe_id);
+            client.create_command(CMD_GET_TL_VARIABLE, TL_CONSTRAINT_SWAP, tls_id, Some(&add));
+            client.process_get(CMD_GET_TL_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_signal_constraints(client)
+        })
     }
 
-    pub fn set_program(&self, client: &mut TraciClient, tls_id: &str, program_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(program_id);
-        client.create_command(CMD_SET_TL_VARIABLE, TL_PROGRAM, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+    /// Remove the constraints of `tls_id` matching `trip_id`, `foe_signal`
+    /// and `foe_id`; an empty string matches any value.
+    pub fn remove_constraints(&self, client: &mut TraciClient, tls_id: &str, trip_id: &str, foe_signal: &str, foe_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.remove_constraints", tls_id, {
+            let add = constraint_key(trip_id, foe_signal, foe_id);
+            client.create_command(CMD_SET_TL_VARIABLE, TL_CONSTRAINT_REMOVE, tls_id, Some(&add));
+            client.process_set(CMD_SET_TL_VARIABLE)?;
+            Ok(())
+        })
     }
 
-    pub fn set_phase_duration(&self, client: &mut TraciClient, tls_id: &str, phase_duration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(phase_duration);
-        client.create_command(CMD_SET_TL_VARIABLE, TL_PHASE_DURATION, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+    pub fn subscribe(&self, client: &mut TraciClient, tls_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.subscribe", tls_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_TL_VARIABLE, tls_id, begin, end, vars)
+        })
     }
 
-    /// Upload a complete traffic light program logic to the server.
-    pub fn set_program_logic(&self, client: &mut TraciClient, tls_id: &str, logic: &TraciLogic) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(5);
+    #[allow(clippy::too_many_arguments)]
+    pub fn subscribe_context(&self, client: &mut TraciClient, tls_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("traffic_lights.subscribe_context", tls_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_TL_CONTEXT, tls_id, begin, end, domain, range, vars)
+        })
+    }
+}
 
-        add.write_u8(TYPE_STRING);
-        add.write_string(&logic.program_id);
+// ============================================================================
+// Shared helpers: rail signal constraint encoding / decoding
+// ============================================================================
+
+/// Build the `(trip_id, foe_signal, foe_id)` compound that identifies a constraint.
+fn constraint_key(trip_id: &str, foe_signal: &str, foe_id: &str) -> Storage {
+    let mut add = Storage::new();
+    add.begin_compound(3);
+    add.write_u8(TYPE_STRING);
+    add.write_string(trip_id);
+    add.write_u8(TYPE_STRING);
+    add.write_string(foe_signal);
+    add.write_u8(TYPE_STRING);
+    add.write_string(foe_id);
+    add
+}
 
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(logic.type_);
+/// Read a constraint list (component count, typed count, then 9 typed
+/// fields per constraint) from the input buffer.
+fn read_signal_constraints(client: &mut TraciClient) -> Result<Vec<TraciSignalConstraint>, TraciError> {
+    client.read_int_from_input()?; // components count
+    client.read_ubyte_from_input()?;
+    let n = client.read_int_from_input()?;
+    let mut result = Vec::with_capacity(n.max(0) as usize);
+    for _ in 0..n {
+        client.read_ubyte_from_input()?;
+        let signal_id = client.read_string_from_input()?;
+        client.read_ubyte_from_input()?;
+        let trip_id = client.read_string_from_input()?;
+        client.read_ubyte_from_input()?;
+        let foe_id = client.read_string_from_input()?;
+        client.read_ubyte_from_input()?;
+        let foe_signal = client.read_string_from_input()?;
+        client.read_ubyte_from_input()?;
+        let limit = client.read_int_from_input()?;
+        client.read_ubyte_from_input()?;
+        let type_ = client.read_int_from_input()?;
+        client.read_ubyte_from_input()?;
+        let must_wait = client.read_ubyte_from_input()? != 0;
+        client.read_ubyte_from_input()?;
+        let active = client.read_ubyte_from_input()? != 0;
+        client.read_ubyte_from_input()?;
+        let items = client.read_string_list_from_input()?;
+        let param = items
+            .chunks_exact(2)
+            .map(|kv| (kv[0].clone(), kv[1].clone()))
+            .collect();
+        result.push(TraciSignalConstraint {
+            signal_id,
+            trip_id,
+            foe_id,
+            foe_signal,
+            limit,
+            type_,
+            must_wait,
+            active,
+            param,
+        });
+    }
+    Ok(result)
+}
 
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(logic.current_phase_index);
+// ============================================================================
+// Shared helper: NEMA duration lists
+// ============================================================================
 
-        // phases compound
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(logic.phases.len() as i32);
-        for p in &logic.phases {
-            add.write_u8(TYPE_COMPOUND);
-            add.write_i32(6);
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(p.duration);
-            add.write_u8(TYPE_STRING);
-            add.write_string(&p.state);
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(p.min_dur);
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(p.max_dur);
-            // next indices compound
-            add.write_u8(TYPE_COMPOUND);
-            add.write_i32(p.next.len() as i32);
-            for &n in &p.next {
-                add.write_u8(TYPE_INTEGER);
-                add.write_i32(n);
+/// Format per-phase durations as the space-separated list SUMO's NEMA
+/// controller parses.
+fn join_durations(values: &[f64]) -> String {
+    values.iter().map(f64::to_string).collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Shared helper: program logic and controlled link decoding
+// ============================================================================
+
+/// Decode a `TL_COMPLETE_DEFINITION_RYG` value (`TYPE_COMPOUND` tag already
+/// consumed). Used by the getter and by subscription parsing.
+pub(crate) fn read_logic_list(in_msg: &mut Storage) -> Result<Vec<TraciLogic>, TraciError> {
+    let logic_no = in_msg.read_i32()?;
+    let mut logics = Vec::with_capacity(logic_no.max(0) as usize);
+
+    for _ in 0..logic_no {
+        // header compound
+        in_msg.read_u8()?;  // TYPE_COMPOUND
+        in_msg.read_i32()?; // element count
+
+        in_msg.read_u8()?; // TYPE_STRING
+        let program_id = in_msg.read_string()?;
+
+        in_msg.read_u8()?; // TYPE_INTEGER
+        let type_ = in_msg.read_i32()?;
+
+        in_msg.read_u8()?; // TYPE_INTEGER
+        let phase_index = in_msg.read_i32()?;
+
+        in_msg.read_u8()?; // TYPE_COMPOUND (phases)
+        let phase_number = in_msg.read_i32()?;
+
+        let mut phases = Vec::with_capacity(phase_number.max(0) as usize);
+        for _ in 0..phase_number {
+            in_msg.read_u8()?;  // TYPE_COMPOUND
+            in_msg.read_i32()?; // element count
+
+            in_msg.read_u8()?; // TYPE_DOUBLE
+            let duration = in_msg.read_f64()?;
+
+            in_msg.read_u8()?; // TYPE_STRING
+            let state = in_msg.read_string()?;
+
+            in_msg.read_u8()?; // TYPE_DOUBLE
+            let min_dur = in_msg.read_f64()?;
+
+            in_msg.read_u8()?; // TYPE_DOUBLE
+            let max_dur = in_msg.read_f64()?;
+
+            in_msg.read_u8()?; // TYPE_COMPOUND (next indices)
+            let num_next = in_msg.read_i32()?;
+            let mut next = Vec::with_capacity(num_next.max(0) as usize);
+            for _ in 0..num_next {
+                in_msg.read_u8()?; // TYPE_INTEGER
+                next.push(in_msg.read_i32()?);
             }
-            add.write_u8(TYPE_STRING);
-            add.write_string(&p.name);
+
+            in_msg.read_u8()?; // TYPE_STRING
+            let name = in_msg.read_string()?;
+
+            phases.push(TraciPhase { duration, state, min_dur, max_dur, next, name });
         }
 
-        // sub-parameters compound
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(logic.sub_parameter.len() as i32);
-        for (k, v) in &logic.sub_parameter {
-            add.write_u8(TYPE_STRINGLIST);
-            add.write_i32(2);
-            add.write_string(k);
-            add.write_string(v);
+        in_msg.read_u8()?; // TYPE_COMPOUND (params)
+        let param_number = in_msg.read_i32()?;
+        let mut sub_parameter = std::collections::HashMap::new();
+        for _ in 0..param_number {
+            in_msg.read_u8()?; // TYPE_STRINGLIST
+            let pair = in_msg.read_string_list()?;
+            if pair.len() >= 2 {
+                sub_parameter.insert(pair[0].clone(), pair[1].clone());
+            }
         }
 
-        client.create_command(CMD_SET_TL_VARIABLE, TL_COMPLETE_PROGRAM_RYG, tls_id, Some(&add));
-        client.process_set(CMD_SET_TL_VARIABLE)?;
-        Ok(())
+        logics.push(TraciLogic {
+            program_id,
+            type_,
+            current_phase_index: phase_index,
+            phases,
+            sub_parameter,
+        });
     }
 
-    pub fn subscribe(&self, client: &mut TraciClient, tls_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_TL_VARIABLE, tls_id, begin, end, vars)
+    Ok(logics)
+}
+
+/// Decode a `TL_CONTROLLED_LINKS` value (`TYPE_COMPOUND` tag already
+/// consumed) into one group of links per signal index.
+///
+/// The compound's element count covers the nested groups and links too, so
+/// the generic compound decoder cannot parse it.
+pub(crate) fn read_link_list(in_msg: &mut Storage) -> Result<Vec<Vec<TraciLink>>, TraciError> {
+    in_msg.read_i32()?; // total element count
+    in_msg.read_u8()?;  // TYPE_INTEGER
+    let link_no = in_msg.read_i32()?;
+    let mut result = Vec::with_capacity(link_no.max(0) as usize);
+
+    for _ in 0..link_no {
+        in_msg.read_u8()?; // TYPE_COMPOUND
+        let no = in_msg.read_i32()?;
+        let mut group = Vec::with_capacity(no.max(0) as usize);
+        for _ in 0..no {
+            in_msg.read_u8()?;  // TYPE_STRINGLIST
+            in_msg.read_i32()?; // 3 strings
+            let from_lane = in_msg.read_string()?;
+            let to_lane = in_msg.read_string()?;
+            let via_lane = in_msg.read_string()?;
+            group.push(TraciLink { from_lane, via_lane, to_lane });
+        }
+        result.push(group);
     }
 
-    pub fn subscribe_context(&self, client: &mut TraciClient, tls_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_TL_CONTEXT, tls_id, begin, end, domain, range, vars)
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    fn link(from: &str, via: &str, to: &str) -> TraciLink {
+        TraciLink { from_lane: from.into(), via_lane: via.into(), to_lane: to.into() }
+    }
+
+    /// Encode `groups` as SUMO sends `TL_CONTROLLED_LINKS`.
+    fn links_value(groups: &[Vec<TraciLink>]) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(1 + groups.len() as i32);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(groups.len() as i32);
+        for group in groups {
+            s.begin_compound(group.len() as i32);
+            for l in group {
+                s.write_u8(TYPE_STRINGLIST);
+                s.write_string_list(&[l.from_lane.clone(), l.to_lane.clone(), l.via_lane.clone()]);
+            }
+        }
+        s
+    }
+
+    #[test]
+    fn get_link_index_map_pairs_links_with_state_indices() {
+        let groups = vec![
+            vec![link("n_0", ":c_0_0", "e_0"), link("n_0", ":c_0_1", "w_0")],
+            vec![link("s_0", ":c_1_0", "e_0")],
+        ];
+        let reply = mock::get_reply(CMD_GET_TL_VARIABLE, TL_CONTROLLED_LINKS, "c", links_value(&groups));
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        let map = TrafficLightScope::default().get_link_index_map(&mut client, "c").unwrap();
+        assert_eq!(
+            map,
+            vec![
+                (0, link("n_0", ":c_0_0", "e_0")),
+                (0, link("n_0", ":c_0_1", "w_0")),
+                (1, link("s_0", ":c_1_0", "e_0")),
+            ]
+        );
+    }
+
+    fn constraint(trip_id: &str, foe_id: &str, type_: i32) -> TraciSignalConstraint {
+        TraciSignalConstraint {
+            signal_id: "rs0".into(),
+            trip_id: trip_id.into(),
+            foe_id: foe_id.into(),
+            foe_signal: "rs1".into(),
+            limit: 1,
+            type_,
+            must_wait: true,
+            active: false,
+            param: std::collections::HashMap::from([("comment".to_string(), "swapped".to_string())]),
+        }
+    }
+
+    /// Encode `constraints` as SUMO sends `TL_CONSTRAINT` and friends.
+    fn constraints_value(constraints: &[TraciSignalConstraint]) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(1 + 9 * constraints.len() as i32);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(constraints.len() as i32);
+        for c in constraints {
+            for text in [&c.signal_id, &c.trip_id, &c.foe_id, &c.foe_signal] {
+                s.write_u8(TYPE_STRING);
+                s.write_string(text);
+            }
+            for i in [c.limit, c.type_] {
+                s.write_u8(TYPE_INTEGER);
+                s.write_i32(i);
+            }
+            for flag in [c.must_wait, c.active] {
+                s.write_u8(TYPE_BYTE);
+                s.write_u8(flag as u8);
+            }
+            let params: Vec<String> = c.param.iter().flat_map(|(k, v)| [k.clone(), v.clone()]).collect();
+            s.write_u8(TYPE_STRINGLIST);
+            s.write_string_list(&params);
+        }
+        s
+    }
+
+    #[test]
+    fn get_constraints_decodes_every_field() {
+        let expected = vec![constraint("t0", "t1", 0), constraint("t2", "t3", 3)];
+        let reply = mock::get_reply(CMD_GET_TL_VARIABLE, TL_CONSTRAINT, "rs0", constraints_value(&expected));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(TrafficLightScope::default().get_constraints(&mut client, "rs0", "").unwrap(), expected);
+        drop(client);
+        let request = mock::command(CMD_GET_TL_VARIABLE, TL_CONSTRAINT, "rs0", Some(&mock::string("")));
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn swap_constraints_sends_the_key_and_decodes_the_added_constraints() {
+        let added = vec![constraint("t1", "t0", 0)];
+        let reply = mock::get_reply(CMD_GET_TL_VARIABLE, TL_CONSTRAINT_SWAP, "rs0", constraints_value(&added));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let result = TrafficLightScope::default().swap_constraints(&mut client, "rs0", "t0", "rs1", "t1").unwrap();
+        assert_eq!(result, added);
+        drop(client);
+        let request = mock::command(CMD_GET_TL_VARIABLE, TL_CONSTRAINT_SWAP, "rs0", Some(&constraint_key("t0", "rs1", "t1")));
+        assert_eq!(server.requests(), vec![request]);
+    }
+
+    #[test]
+    fn remove_constraints_sends_the_key() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_TL_VARIABLE)]);
+        TrafficLightScope::default().remove_constraints(&mut client, "rs0", "t0", "", "").unwrap();
+        drop(client);
+
+        let mut key = Storage::new();
+        key.begin_compound(3);
+        for text in ["t0", "", ""] {
+            key.write_u8(TYPE_STRING);
+            key.write_string(text);
+        }
+        let request = mock::command(CMD_SET_TL_VARIABLE, TL_CONSTRAINT_REMOVE, "rs0", Some(&key));
+        assert_eq!(server.requests(), vec![request]);
     }
 }
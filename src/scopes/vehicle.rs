@@ -8,7 +8,8 @@ use crate::{
     storage::Storage,
     types::{
         ContextSubscriptionResults, SubscriptionResults, TraciBestLanesData, TraciColor,
-        TraciNextTLSData, TraciPosition,
+        KeepRoute, LaneChangeMode, RoutingMode, SpeedMode, StopState, TraciNextStopData,
+        TraciNextTLSData, TraciPosition, VehicleAddOptions, VehicleSignals, VehicleVar,
     },
 };
 
@@ -66,37 +67,44 @@ impl VehicleScope {
     // -----------------------------------------------------------------------
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("vehicle.get_id_list", {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_id_count", {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, vehicle_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PARAMETER, vehicle_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_parameter", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PARAMETER, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, vehicle_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_PARAMETER, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_parameter", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_PARAMETER, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -104,364 +112,611 @@ impl VehicleScope {
     // -----------------------------------------------------------------------
 
     pub fn get_speed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_speed", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lateral_speed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_LAT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_lateral_speed", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_LAT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_acceleration(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCELERATION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_acceleration", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCELERATION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the vehicle's action step length in seconds: how often its
+    /// driver model decides on a new acceleration and lane.
+    pub fn get_action_step_length(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("vehicle.get_action_step_length", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACTIONSTEPLENGTH, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the simulation time of the vehicle's last action step, in
+    /// seconds. Actions happen every [`Self::get_action_step_length`]
+    /// seconds, so with a step length above the simulation step this lags
+    /// behind the current time between actions.
+    pub fn get_last_action_time(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("vehicle.get_last_action_time", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LASTACTIONTIME, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_position(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(POSITION_2D))?;
-        client.read_pos_2d_from_input()
+        crate::with_context!("vehicle.get_position", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(POSITION_2D))?;
+            client.read_pos_2d_from_input()
+        })
+    }
+
+    /// Return the position as lon/lat (`x` = longitude, `y` = latitude),
+    /// using the client's cached [`CoordinateConverter`](crate::CoordinateConverter).
+    ///
+    /// Fails with [`TraciError::Protocol`] if SUMO reports the network as unprojected.
+    pub fn get_geo_position(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<TraciPosition, TraciError> {
+        crate::with_context!("vehicle.get_geo_position", vehicle_id, {
+            let pos = crate::scope_get_pos!(client, CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, vehicle_id)?;
+            let converter = client.coordinate_converter()?;
+            converter.convert_to_geo(client, pos.x, pos.y)
+        })
+    }
+
+    /// Return the 2-D position of every vehicle in the simulation, fetching
+    /// the id list and then all positions in one batched round trip.
+    pub fn get_all_positions(&self, client: &mut TraciClient) -> Result<Vec<(String, TraciPosition)>, TraciError> {
+        crate::with_context!("vehicle.get_all_positions", {
+            let ids = crate::scope_get_string_list!(client, CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "")?;
+            let requests: Vec<(u8, u8, &str)> =
+                ids.iter().map(|id| (CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, id.as_str())).collect();
+            let values = client.batch_get(&requests)?;
+            ids.into_iter()
+                .zip(values)
+                .map(|(id, value)| match TraciPosition::try_from(value) {
+                    Ok(pos) => Ok((id, pos)),
+                    Err(other) => Err(TraciError::Protocol(format!("Expected a position but got {other:?}"))),
+                })
+                .collect()
+        })
     }
 
     pub fn get_position3d(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<TraciPosition, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION3D, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(POSITION_3D))?;
-        client.read_pos_3d_from_input()
+        crate::with_context!("vehicle.get_position3d", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION3D, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(POSITION_3D))?;
+            client.read_pos_3d_from_input()
+        })
     }
 
     pub fn get_angle(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ANGLE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_angle", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ANGLE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_road_id(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROAD_ID, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_road_id", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROAD_ID, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
+    }
+
+    /// Return `true` while the vehicle is on an internal (junction) edge,
+    /// i.e. its road id starts with `:`.
+    pub fn is_on_junction(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<bool, TraciError> {
+        crate::with_context!("vehicle.is_on_junction", vehicle_id, {
+            Ok(crate::scope_get_string!(client, CMD_GET_VEHICLE_VARIABLE, VAR_ROAD_ID, vehicle_id)?.starts_with(':'))
+        })
+    }
+
+    /// Return the junction the vehicle is crossing, parsed from its internal
+    /// edge id (`:<junction>_<index>`), or `None` on a normal edge.
+    pub fn get_junction_id(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Option<String>, TraciError> {
+        crate::with_context!("vehicle.get_junction_id", vehicle_id, {
+            let road_id = crate::scope_get_string!(client, CMD_GET_VEHICLE_VARIABLE, VAR_ROAD_ID, vehicle_id)?;
+            Ok(internal_edge_junction(&road_id).map(str::to_string))
+        })
     }
 
     pub fn get_lane_id(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_ID, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_lane_id", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_ID, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_lane_index(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_INDEX, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_lane_index", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_INDEX, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_type_id(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TYPE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_type_id", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TYPE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_route_id(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_ID, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_route_id", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_ID, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_route_index(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_route_index", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_route(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("vehicle.get_route", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Return the edges of the route from the current edge (included) to the end.
+    pub fn get_remaining_route(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("vehicle.get_remaining_route", vehicle_id, {
+            let mut route = crate::scope_get_string_list!(client, CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, vehicle_id)?;
+            let route_index = crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, vehicle_id)?;
+            let index = route_split(route_index, route.len());
+            Ok(route.split_off(index))
+        })
+    }
+
+    /// Return the edges of the route already passed, excluding the current edge.
+    pub fn get_completed_route(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("vehicle.get_completed_route", vehicle_id, {
+            let mut route = crate::scope_get_string_list!(client, CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, vehicle_id)?;
+            let route_index = crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, vehicle_id)?;
+            let index = route_split(route_index, route.len());
+            route.truncate(index);
+            Ok(route)
+        })
     }
 
     pub fn get_color(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<TraciColor, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_COLOR, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COLOR))?;
-        client.read_color_from_input()
+        crate::with_context!("vehicle.get_color", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_COLOR, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COLOR))?;
+            client.read_color_from_input()
+        })
     }
 
     pub fn get_lane_position(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANEPOSITION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_lane_position", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANEPOSITION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_distance(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_DISTANCE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_distance", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_DISTANCE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_signals(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_signals", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the active signals as a typed [`VehicleSignals`].
+    pub fn get_signals_typed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<VehicleSignals, TraciError> {
+        crate::with_context!("vehicle.get_signals_typed", vehicle_id, {
+            Ok(VehicleSignals::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id)?))
+        })
     }
 
     pub fn get_lateral_lane_position(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANEPOSITION_LAT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_lateral_lane_position", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANEPOSITION_LAT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_co2_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_CO2EMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_co2_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_CO2EMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_co_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_COEMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_co_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_COEMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_hc_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_HCEMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_hc_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_HCEMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_pmx_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PMXEMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_pmx_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PMXEMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_nox_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NOXEMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_nox_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NOXEMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_fuel_consumption(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_FUELCONSUMPTION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_fuel_consumption", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_FUELCONSUMPTION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_noise_emission(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NOISEEMISSION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_noise_emission", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NOISEEMISSION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_electricity_consumption(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_electricity_consumption", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ELECTRICITYCONSUMPTION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_waiting_time(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_WAITING_TIME, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_waiting_time", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_WAITING_TIME, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_accumulated_waiting_time(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCUMULATED_WAITING_TIME, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_accumulated_waiting_time", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCUMULATED_WAITING_TIME, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Return the time lost so far compared to driving at the desired speed (s).
+    pub fn get_time_loss(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
+        crate::with_context!("vehicle.get_time_loss", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TIMELOSS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lane_change_mode(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_lane_change_mode", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the lane change mode as a typed [`LaneChangeMode`].
+    pub fn get_lane_change_mode_typed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<LaneChangeMode, TraciError> {
+        crate::with_context!("vehicle.get_lane_change_mode_typed", vehicle_id, {
+            Ok(LaneChangeMode::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id)?))
+        })
     }
 
     pub fn get_speed_mode(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_speed_mode", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the speed mode as a typed [`SpeedMode`].
+    pub fn get_speed_mode_typed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<SpeedMode, TraciError> {
+        crate::with_context!("vehicle.get_speed_mode_typed", vehicle_id, {
+            Ok(SpeedMode::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id)?))
+        })
     }
 
     pub fn get_slope(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SLOPE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_slope", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SLOPE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_line(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LINE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_line", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LINE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_via(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_VIA, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("vehicle.get_via", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_VIA, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_stop_state(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_stop_state", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the stop state as a typed [`StopState`].
+    pub fn get_stop_state_typed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<StopState, TraciError> {
+        crate::with_context!("vehicle.get_stop_state_typed", vehicle_id, {
+            Ok(StopState::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, vehicle_id)?))
+        })
     }
 
     pub fn get_routing_mode(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_routing_mode", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_stop_delay(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_DELAY, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_stop_delay", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_DELAY, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_stop_arrival_delay(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_ARRIVALDELAY, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_stop_arrival_delay", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_ARRIVALDELAY, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_speed_without_traci(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_WITHOUT_TRACI, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_speed_without_traci", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_WITHOUT_TRACI, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn is_route_valid(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<bool, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_VALID, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        Ok(client.read_int_from_input()? != 0)
+        crate::with_context!("vehicle.is_route_valid", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_VALID, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            Ok(client.read_int_from_input()? != 0)
+        })
     }
 
     pub fn get_allowed_speed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ALLOWED_SPEED, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_allowed_speed", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ALLOWED_SPEED, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_person_number(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PERSON_NUMBER, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_person_number", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PERSON_NUMBER, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_person_capacity(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PERSON_CAPACITY, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle.get_person_capacity", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_PERSON_CAPACITY, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_person_id_list(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, LAST_STEP_PERSON_ID_LIST, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("vehicle.get_person_id_list", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, LAST_STEP_PERSON_ID_LIST, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     // VehicleType attribute shortcuts
     pub fn get_max_speed(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MAXSPEED, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_max_speed", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MAXSPEED, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_accel(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCEL, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_accel", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_ACCEL, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_decel(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_DECEL, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_decel", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_DECEL, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_tau(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TAU, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_tau", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TAU, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_imperfection(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_IMPERFECTION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_imperfection", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_IMPERFECTION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_speed_factor(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_FACTOR, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_speed_factor", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_FACTOR, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_speed_deviation(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_DEVIATION, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_speed_deviation", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SPEED_DEVIATION, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_vehicle_class(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_VEHICLECLASS, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_vehicle_class", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_VEHICLECLASS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_min_gap(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MINGAP, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_min_gap", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MINGAP, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_width(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_WIDTH, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_width", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_WIDTH, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_length(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LENGTH, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_length", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LENGTH, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_height(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_HEIGHT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_height", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_HEIGHT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_max_speed_lat(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MAXSPEED_LAT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_max_speed_lat", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MAXSPEED_LAT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_min_gap_lat(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MINGAP_LAT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_min_gap_lat", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_MINGAP_LAT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lateral_alignment(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LATALIGNMENT, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_lateral_alignment", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_LATALIGNMENT, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_emission_class(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_EMISSIONCLASS, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_emission_class", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_EMISSIONCLASS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_shape_class(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SHAPECLASS, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle.get_shape_class", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SHAPECLASS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -470,67 +725,53 @@ impl VehicleScope {
 
     /// Get upcoming traffic-light data for a vehicle.
     pub fn get_next_tls(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<TraciNextTLSData>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_TLS, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
-        client.read_int_from_input()?; // skip components count
-        client.read_ubyte_from_input()?; // skip inner type tag
-        let n = client.read_int_from_input()?;
-        let mut result = Vec::with_capacity(n as usize);
-        for _ in 0..n {
-            client.read_ubyte_from_input()?;
-            let id = client.read_string_from_input()?;
-            client.read_ubyte_from_input()?;
-            let tl_index = client.read_int_from_input()?;
-            client.read_ubyte_from_input()?;
-            let dist = client.read_double_from_input()?;
-            client.read_ubyte_from_input()?;
-            let state_byte = client.read_ubyte_from_input()? as i8 as u8;
-            let state = state_byte as char;
-            result.push(TraciNextTLSData { id, tl_index, dist, state });
-        }
-        Ok(result)
+        crate::with_context!("vehicle.get_next_tls", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_TLS, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+            client.read_int_from_input()?; // skip components count
+            client.read_ubyte_from_input()?; // skip inner type tag
+            let n = client.read_int_from_input()?;
+            let mut result = Vec::with_capacity(n as usize);
+            for _ in 0..n {
+                client.read_ubyte_from_input()?;
+                let id = client.read_string_from_input()?;
+                client.read_ubyte_from_input()?;
+                let tl_index = client.read_int_from_input()?;
+                client.read_ubyte_from_input()?;
+                let dist = client.read_double_from_input()?;
+                client.read_ubyte_from_input()?;
+                let state_byte = client.read_ubyte_from_input()? as i8 as u8;
+                let state = state_byte as char;
+                result.push(TraciNextTLSData { id, tl_index, dist, state });
+            }
+            Ok(result)
+        })
     }
 
     /// Get best-lane information for a vehicle.
     pub fn get_best_lanes(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<TraciBestLanesData>, TraciError> {
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_BEST_LANES, vehicle_id, None);
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
-        client.read_int_from_input()?; // skip total byte count
-        client.read_ubyte_from_input()?; // skip inner type tag
-        let n = client.read_int_from_input()?;
-        let mut result = Vec::with_capacity(n as usize);
-        for _ in 0..n {
-            client.read_ubyte_from_input()?;
-            let lane_id = client.read_string_from_input()?;
-            client.read_ubyte_from_input()?;
-            let length = client.read_double_from_input()?;
-            client.read_ubyte_from_input()?;
-            let occupation = client.read_double_from_input()?;
-            client.read_ubyte_from_input()?;
-            // bestLaneOffset is a signed byte
-            let best_lane_offset = client.read_ubyte_from_input()? as i8 as i32;
-            client.read_ubyte_from_input()?;
-            let allows_continuation = client.read_ubyte_from_input()? == 1;
-            client.read_ubyte_from_input()?;
-            let m = client.read_int_from_input()?;
-            let mut continuation_lanes = Vec::with_capacity(m as usize);
-            for _ in 0..m {
-                continuation_lanes.push(client.read_string_from_input()?);
-            }
-            result.push(TraciBestLanesData {
-                lane_id,
-                length,
-                occupation,
-                best_lane_offset,
-                allows_continuation,
-                continuation_lanes,
-            });
-        }
-        Ok(result)
+        crate::with_context!("vehicle.get_best_lanes", vehicle_id, {
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_BEST_LANES, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+            read_best_lanes(client.input_mut())
+        })
     }
 
     /// Get the leading vehicle and gap ahead of a vehicle.
+    /// The id is empty if there is no leader within `dist`.
+    ///
+    /// `dist` is a minimum lookahead along the route: SUMO searches at least
+    /// as far as the vehicle's brake gap, so `0.0` (sent as a literal double)
+    /// does not disable the query but looks ahead by the brake gap only. The
+    /// gap is measured from the vehicle's front plus its `minGap` to the
+    /// leader's back.
     pub fn get_leader(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<(String, f64), TraciError> {
+        crate::with_context!("vehicle.get_leader", vehicle_id, {
+            self.query_leader(client, vehicle_id, dist)
+        })
+    }
+
+    fn query_leader(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<(String, f64), TraciError> {
         let mut add = Storage::new();
         add.write_u8(TYPE_DOUBLE);
         add.write_f64(dist);
@@ -545,7 +786,14 @@ impl VehicleScope {
     }
 
     /// Get the following vehicle and gap behind a vehicle.
+    /// The id is empty if there is no follower within `dist`.
     pub fn get_follower(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<(String, f64), TraciError> {
+        crate::with_context!("vehicle.get_follower", vehicle_id, {
+            self.query_follower(client, vehicle_id, dist)
+        })
+    }
+
+    fn query_follower(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<(String, f64), TraciError> {
         let mut add = Storage::new();
         add.write_u8(TYPE_DOUBLE);
         add.write_f64(dist);
@@ -559,72 +807,190 @@ impl VehicleScope {
         Ok((follower_id, gap))
     }
 
+    /// Return the vehicles next to `vehicle_id` on an adjacent lane, with
+    /// their distance. `mode` combines `NEIGHBOR_LEFT`, `NEIGHBOR_LEADERS`
+    /// and `NEIGHBOR_BLOCKING`; `0` lists all right followers.
+    pub fn get_neighbors(&self, client: &mut TraciClient, vehicle_id: &str, mode: u8) -> Result<Vec<(String, f64)>, TraciError> {
+        crate::with_context!("vehicle.get_neighbors", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_UBYTE);
+            add.write_u8(mode);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NEIGHBORS, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+            // Pairs are written without type tags.
+            let n = client.read_int_from_input()?;
+            let mut result = Vec::with_capacity(n.max(0) as usize);
+            for _ in 0..n {
+                let id = client.read_string_from_input()?;
+                let dist = client.read_double_from_input()?;
+                result.push((id, dist));
+            }
+            Ok(result)
+        })
+    }
+
+    /// Like [`Self::get_leader`], but `None` when there is no leader within `dist`.
+    pub fn get_leader_opt(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<Option<(String, f64)>, TraciError> {
+        crate::with_context!("vehicle.get_leader_opt", vehicle_id, {
+            let (leader_id, gap) = self.query_leader(client, vehicle_id, dist)?;
+            Ok((!leader_id.is_empty()).then_some((leader_id, gap)))
+        })
+    }
+
+    /// Like [`Self::get_follower`], but `None` when there is no follower within `dist`.
+    pub fn get_follower_opt(&self, client: &mut TraciClient, vehicle_id: &str, dist: f64) -> Result<Option<(String, f64)>, TraciError> {
+        crate::with_context!("vehicle.get_follower_opt", vehicle_id, {
+            let (follower_id, gap) = self.query_follower(client, vehicle_id, dist)?;
+            Ok((!follower_id.is_empty()).then_some((follower_id, gap)))
+        })
+    }
+
     /// Get lane change state for a given direction.
     /// Returns `(state_without_traci, state)`.
     pub fn get_lane_change_state(&self, client: &mut TraciClient, vehicle_id: &str, direction: i32) -> Result<(i32, i32), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(direction);
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
-        client.read_int_from_input()?; // components
-        client.read_ubyte_from_input()?;
-        let state_without_traci = client.read_int_from_input()?;
-        client.read_ubyte_from_input()?;
-        let state = client.read_int_from_input()?;
-        Ok((state_without_traci, state))
+        crate::with_context!("vehicle.get_lane_change_state", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(direction);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+            client.read_int_from_input()?; // components
+            client.read_ubyte_from_input()?;
+            let state_without_traci = client.read_int_from_input()?;
+            client.read_ubyte_from_input()?;
+            let state = client.read_int_from_input()?;
+            Ok((state_without_traci, state))
+        })
     }
 
     /// Get the safe follow speed.
+    #[allow(clippy::too_many_arguments)]
     pub fn get_follow_speed(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64, gap: f64, leader_speed: f64, leader_max_decel: f64, leader_id: &str) -> Result<f64, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(5);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(gap);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(leader_speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(leader_max_decel);
-        add.write_u8(TYPE_STRING);
-        add.write_string(leader_id);
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_FOLLOW_SPEED, vehicle_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_follow_speed", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(5);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(gap);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(leader_speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(leader_max_decel);
+            add.write_u8(TYPE_STRING);
+            add.write_string(leader_id);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_FOLLOW_SPEED, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Get the safe gap to keep to a leader.
     pub fn get_secure_gap(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64, leader_speed: f64, leader_max_decel: f64, leader_id: &str) -> Result<f64, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(4);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(leader_speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(leader_max_decel);
-        add.write_u8(TYPE_STRING);
-        add.write_string(leader_id);
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SECURE_GAP, vehicle_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle.get_secure_gap", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(4);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(leader_speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(leader_max_decel);
+            add.write_u8(TYPE_STRING);
+            add.write_string(leader_id);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_SECURE_GAP, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     /// Get the maximum safe speed to reach a stop with given gap.
     pub fn get_stop_speed(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64, gap: f64) -> Result<f64, TraciError> {
+        crate::with_context!("vehicle.get_stop_speed", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(gap);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_SPEED, vehicle_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
+    }
+
+    /// Query up to `limit` stops of a vehicle via `VAR_NEXT_STOPS2`.
+    /// A positive limit returns upcoming stops (the current one first while
+    /// stopped), a negative limit returns already passed stops.
+    fn query_stops(&self, client: &mut TraciClient, vehicle_id: &str, limit: i32) -> Result<Vec<TraciNextStopData>, TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(gap);
-        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_STOP_SPEED, vehicle_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        add.write_u8(TYPE_INTEGER);
+        add.write_i32(limit);
+        client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_STOPS2, vehicle_id, Some(&add));
+        client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+        let n = client.read_int_from_input()?;
+        let mut result = Vec::with_capacity(n.max(0) as usize);
+        for _ in 0..n {
+            result.push(read_next_stop_data(client)?);
+        }
+        Ok(result)
+    }
+
+    /// Return all upcoming stops of a vehicle, including the one it is
+    /// currently halted at. A vehicle without stops yields an empty list.
+    pub fn get_next_stops(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Vec<TraciNextStopData>, TraciError> {
+        crate::with_context!("vehicle.get_next_stops", vehicle_id, {
+            self.query_stops(client, vehicle_id, 0)
+        })
+    }
+
+    /// Return up to `limit` stops of a vehicle: upcoming stops for a positive
+    /// limit, already passed stops for a negative one, and all upcoming stops
+    /// for `0`.
+    pub fn get_stops(&self, client: &mut TraciClient, vehicle_id: &str, limit: i32) -> Result<Vec<TraciNextStopData>, TraciError> {
+        crate::with_context!("vehicle.get_stops", vehicle_id, {
+            self.query_stops(client, vehicle_id, limit)
+        })
+    }
+
+    /// Return `true` if the vehicle is currently halted at a stop.
+    pub fn is_stopped(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<bool, TraciError> {
+        crate::with_context!("vehicle.is_stopped", vehicle_id, {
+            Ok(StopState::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, vehicle_id)?).is_stopped())
+        })
+    }
+
+    /// Return the stop the vehicle is currently halted at, or `None` if it
+    /// is moving.
+    pub fn current_stop(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<Option<TraciNextStopData>, TraciError> {
+        crate::with_context!("vehicle.current_stop", vehicle_id, {
+            if !StopState::from_bits(crate::scope_get_int!(client, CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, vehicle_id)?).is_stopped() {
+                return Ok(None);
+            }
+            Ok(self.query_stops(client, vehicle_id, 1)?.into_iter().next())
+        })
+    }
+
+    /// Return how many lanes the vehicle should move to stay on its best
+    /// route: negative values mean right, positive values left, `0` means the
+    /// current lane is already suitable.
+    ///
+    /// The offset is taken from the best-lanes entry of the vehicle's current
+    /// lane. If the vehicle is not on any of the listed lanes (e.g. while on
+    /// an internal junction lane) the entry whose offset is nearest zero is
+    /// used instead; with no entries at all the result is `0`.
+    pub fn recommended_lane_offset(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<i32, TraciError> {
+        crate::with_context!("vehicle.recommended_lane_offset", vehicle_id, {
+            let lane_id = crate::scope_get_string!(client, CMD_GET_VEHICLE_VARIABLE, VAR_LANE_ID, vehicle_id)?;
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_BEST_LANES, vehicle_id, None);
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_COMPOUND))?;
+            let best_lanes = read_best_lanes(client.input_mut())?;
+            let offset = match best_lanes.iter().find(|b| b.lane_id == lane_id) {
+                Some(current) => current.best_lane_offset,
+                None => best_lanes.iter().map(|b| b.best_lane_offset).min_by_key(|o| o.abs()).unwrap_or(0),
+            };
+            Ok(offset)
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -633,14 +999,17 @@ impl VehicleScope {
 
     /// Add a new vehicle to the simulation.
     ///
-    /// If `depart` is `"-1"` the current simulation time is used.
+    /// If `depart` is `"-1"` the current simulation time is used. See
+    /// [`Self::add_with`] for a variant that only takes the attributes that
+    /// differ from SUMO's defaults.
+    #[allow(clippy::too_many_arguments)]
     pub fn add(
         &self,
         client: &mut TraciClient,
         vehicle_id: &str,
         route_id: &str,
         type_id: &str,
-        mut depart: String,
+        depart: String,
         depart_lane: &str,
         depart_pos: &str,
         depart_speed: &str,
@@ -653,6 +1022,37 @@ impl VehicleScope {
         person_capacity: i32,
         person_number: i32,
     ) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.add", vehicle_id, {
+            let opts = VehicleAddOptions::default()
+                .route(route_id)
+                .type_id(type_id)
+                .depart(depart)
+                .depart_lane(depart_lane)
+                .depart_pos(depart_pos)
+                .depart_speed(depart_speed)
+                .arrival_lane(arrival_lane)
+                .arrival_pos(arrival_pos)
+                .arrival_speed(arrival_speed)
+                .from_taz(from_taz)
+                .to_taz(to_taz)
+                .line(line)
+                .person_capacity(person_capacity)
+                .person_number(person_number);
+            self.send_add(client, vehicle_id, &opts)
+        })
+    }
+
+    /// Add a new vehicle described by `opts`.
+    ///
+    /// If the depart time is `"-1"` the current simulation time is used.
+    pub fn add_with(&self, client: &mut TraciClient, vehicle_id: &str, opts: &VehicleAddOptions) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.add_with", vehicle_id, {
+            self.send_add(client, vehicle_id, opts)
+        })
+    }
+
+    fn send_add(&self, client: &mut TraciClient, vehicle_id: &str, opts: &VehicleAddOptions) -> Result<(), TraciError> {
+        let mut depart = opts.depart.clone();
         if depart == "-1" {
             // Get current time inline to avoid borrow issues
             client.create_command(CMD_GET_SIM_VARIABLE, VAR_TIME_STEP, "", None);
@@ -661,101 +1061,112 @@ impl VehicleScope {
             depart = format!("{:.2}", time_ms as f64 / 1000.0);
         }
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(14);
-        add.write_u8(TYPE_STRING); add.write_string(route_id);
-        add.write_u8(TYPE_STRING); add.write_string(type_id);
+        add.begin_compound(14);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.route_id);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.type_id);
         add.write_u8(TYPE_STRING); add.write_string(&depart);
-        add.write_u8(TYPE_STRING); add.write_string(depart_lane);
-        add.write_u8(TYPE_STRING); add.write_string(depart_pos);
-        add.write_u8(TYPE_STRING); add.write_string(depart_speed);
-        add.write_u8(TYPE_STRING); add.write_string(arrival_lane);
-        add.write_u8(TYPE_STRING); add.write_string(arrival_pos);
-        add.write_u8(TYPE_STRING); add.write_string(arrival_speed);
-        add.write_u8(TYPE_STRING); add.write_string(from_taz);
-        add.write_u8(TYPE_STRING); add.write_string(to_taz);
-        add.write_u8(TYPE_STRING); add.write_string(line);
-        add.write_u8(TYPE_INTEGER); add.write_i32(person_capacity);
-        add.write_u8(TYPE_INTEGER); add.write_i32(person_number);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.depart_lane);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.depart_pos);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.depart_speed);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.arrival_lane);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.arrival_pos);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.arrival_speed);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.from_taz);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.to_taz);
+        add.write_u8(TYPE_STRING); add.write_string(&opts.line);
+        add.write_u8(TYPE_INTEGER); add.write_i32(opts.person_capacity);
+        add.write_u8(TYPE_INTEGER); add.write_i32(opts.person_number);
         client.create_command(CMD_SET_VEHICLE_VARIABLE, ADD_FULL, vehicle_id, Some(&add));
         client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
         Ok(())
     }
 
     pub fn remove(&self, client: &mut TraciClient, vehicle_id: &str, reason: u8) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_BYTE);
-        add.write_u8(reason);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, REMOVE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.remove", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_BYTE);
+            add.write_u8(reason);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, REMOVE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn change_target(&self, client: &mut TraciClient, vehicle_id: &str, edge_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(edge_id);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGETARGET, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.change_target", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(edge_id);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGETARGET, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn change_lane(&self, client: &mut TraciClient, vehicle_id: &str, lane_index: i32, duration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_BYTE);
-        add.write_u8(lane_index as u8);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(duration);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.change_lane", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_BYTE);
+            add.write_u8(lane_index as u8);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(duration);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn change_lane_relative(&self, client: &mut TraciClient, vehicle_id: &str, lane_change: i32, duration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(TYPE_BYTE);
-        add.write_u8(lane_change as u8);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(duration);
-        add.write_u8(TYPE_BYTE);
-        add.write_u8(1);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.change_lane_relative", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(TYPE_BYTE);
+            add.write_u8(lane_change as u8);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(duration);
+            add.write_u8(TYPE_BYTE);
+            add.write_u8(1);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGELANE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn change_sublane(&self, client: &mut TraciClient, vehicle_id: &str, lat_dist: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(lat_dist);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGESUBLANE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.change_sublane", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(lat_dist);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_CHANGESUBLANE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_route_id(&self, client: &mut TraciClient, vehicle_id: &str, route_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(route_id);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTE_ID, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_route_id", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(route_id);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTE_ID, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_route(&self, client: &mut TraciClient, vehicle_id: &str, edges: &[String]) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRINGLIST);
-        add.write_i32(edges.len() as i32);
-        for e in edges {
-            add.write_string(e);
-        }
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_route", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_i32(edges.len() as i32);
+            for e in edges {
+                add.write_string(e);
+            }
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Reroute vehicle using travel-times.
@@ -763,57 +1174,80 @@ impl VehicleScope {
     /// If `current_travel_times` is true, the current edge travel times are
     /// first fetched and adapted before issuing the reroute command.
     pub fn reroute_traveltime(&self, client: &mut TraciClient, vehicle_id: &str, current_travel_times: bool) -> Result<(), TraciError> {
-        if current_travel_times {
-            // Inline edge operations to avoid borrow conflicts
-            client.create_command(CMD_GET_EDGE_VARIABLE, TRACI_ID_LIST, "", None);
-            client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
-            let edges = client.read_string_list_from_input()?;
-            for edge in &edges {
-                // get current traveltime
-                client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, edge, None);
-                client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
-                let tt = client.read_double_from_input()?;
-                // adapt traveltime
-                let mut content = Storage::new();
-                content.write_u8(TYPE_COMPOUND);
-                content.write_i32(3);
-                content.write_u8(TYPE_DOUBLE);
-                content.write_f64(0.0);
-                content.write_u8(TYPE_DOUBLE);
-                content.write_f64(f64::MAX);
-                content.write_u8(TYPE_DOUBLE);
-                content.write_f64(tt);
-                client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge, Some(&content));
-                client.process_set(CMD_SET_EDGE_VARIABLE)?;
+        crate::with_context!("vehicle.reroute_traveltime", vehicle_id, {
+            if current_travel_times {
+                // Inline edge operations to avoid borrow conflicts
+                client.create_command(CMD_GET_EDGE_VARIABLE, TRACI_ID_LIST, "", None);
+                client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_STRINGLIST))?;
+                let edges = client.read_string_list_from_input()?;
+                for edge in &edges {
+                    // get current traveltime
+                    client.create_command(CMD_GET_EDGE_VARIABLE, VAR_CURRENT_TRAVELTIME, edge, None);
+                    client.process_get(CMD_GET_EDGE_VARIABLE, Some(TYPE_DOUBLE))?;
+                    let tt = client.read_double_from_input()?;
+                    // adapt traveltime
+                    let mut content = Storage::new();
+                    content.begin_compound(3);
+                    content.write_u8(TYPE_DOUBLE);
+                    content.write_f64(0.0);
+                    content.write_u8(TYPE_DOUBLE);
+                    content.write_f64(f64::MAX);
+                    content.write_u8(TYPE_DOUBLE);
+                    content.write_f64(tt);
+                    client.create_command(CMD_SET_EDGE_VARIABLE, VAR_EDGE_TRAVELTIME, edge, Some(&content));
+                    client.process_set(CMD_SET_EDGE_VARIABLE)?;
+                }
             }
-        }
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(0);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_REROUTE_TRAVELTIME, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+            let mut add = Storage::new();
+            add.begin_compound(0);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_REROUTE_TRAVELTIME, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Divert a vehicle to the given parking area. SUMO reports an unknown
+    /// parking area as [`TraciError::SimulationError`].
+    pub fn reroute_parking_area(&self, client: &mut TraciClient, vehicle_id: &str, parking_area_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.reroute_parking_area", vehicle_id, {
+            // SUMO expects the parking area id wrapped in a one-element compound
+            let mut add = Storage::new();
+            add.begin_compound(1);
+            add.write_u8(TYPE_STRING);
+            add.write_string(parking_area_id);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_REROUTE_TO_PARKING, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn move_to(&self, client: &mut TraciClient, vehicle_id: &str, lane_id: &str, position: f64, reason: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(3);
-        add.write_u8(TYPE_STRING);
-        add.write_string(lane_id);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(position);
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(reason);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MOVE_TO, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.move_to", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(3);
+            add.write_u8(TYPE_STRING);
+            add.write_string(lane_id);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(position);
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(reason);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MOVE_TO, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn move_to_xy(&self, client: &mut TraciClient, vehicle_id: &str, edge_id: &str, lane: i32, x: f64, y: f64, angle: f64, keep_route: i32) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.move_to_xy", vehicle_id, {
+            self.send_move_to_xy(client, vehicle_id, edge_id, lane, x, y, angle, keep_route)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn send_move_to_xy(&self, client: &mut TraciClient, vehicle_id: &str, edge_id: &str, lane: i32, x: f64, y: f64, angle: f64, keep_route: i32) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(6);
+        add.begin_compound(6);
         add.write_u8(TYPE_STRING);
         add.write_string(edge_id);
         add.write_u8(TYPE_INTEGER);
@@ -831,94 +1265,182 @@ impl VehicleScope {
         Ok(())
     }
 
+    /// Move the vehicle to a network position like [`Self::move_to_xy`],
+    /// with typed `keep_route` flags.
+    ///
+    /// An `angle` of `None` keeps the angle SUMO derives from the matched
+    /// lane (sent as `INVALID_DOUBLE_VALUE`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn move_to_xy_opts(
+        &self,
+        client: &mut TraciClient,
+        vehicle_id: &str,
+        edge_id: &str,
+        lane: i32,
+        x: f64,
+        y: f64,
+        angle: Option<f64>,
+        keep_route: KeepRoute,
+    ) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.move_to_xy_opts", vehicle_id, {
+            let angle = angle.unwrap_or(INVALID_DOUBLE_VALUE);
+            self.send_move_to_xy(client, vehicle_id, edge_id, lane, x, y, angle, keep_route.bits())
+        })
+    }
+
     pub fn slow_down(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64, duration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(duration);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_SLOWDOWN, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.slow_down", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(duration);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_SLOWDOWN, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Open a gap in front of the vehicle.
     ///
     /// Pass `max_decel <= 0.0` to omit the optional 4th parameter.
     pub fn open_gap(&self, client: &mut TraciClient, vehicle_id: &str, new_tau: f64, duration: f64, change_rate: f64, max_decel: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        if max_decel > 0.0 {
-            add.write_i32(4);
-        } else {
-            add.write_i32(3);
-        }
-        add.write_u8(TYPE_DOUBLE); add.write_f64(new_tau);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(duration);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(change_rate);
-        if max_decel > 0.0 {
-            add.write_u8(TYPE_DOUBLE);
-            add.write_f64(max_decel);
-        }
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_OPENGAP, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.open_gap", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COMPOUND);
+            if max_decel > 0.0 {
+                add.write_i32(4);
+            } else {
+                add.write_i32(3);
+            }
+            add.write_u8(TYPE_DOUBLE); add.write_f64(new_tau);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(duration);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(change_rate);
+            if max_decel > 0.0 {
+                add.write_u8(TYPE_DOUBLE);
+                add.write_f64(max_decel);
+            }
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_OPENGAP, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
+    /// Fix the vehicle speed (m/s) until further notice.
+    ///
+    /// The speed is held indefinitely; a negative value (SUMO expects `-1`)
+    /// hands control back to the car-following model. Prefer
+    /// [`Self::release_speed_control`] for that.
     pub fn set_speed(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_speed", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Release a speed previously fixed with [`Self::set_speed`] so the
+    /// car-following model controls the vehicle again.
+    pub fn release_speed_control(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.release_speed_control", vehicle_id, {
+            crate::scope_set_double!(client, CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, vehicle_id, -1.0)?;
+            Ok(())
+        })
     }
 
+    /// Change the acceleration to `accel` (m/s², negative to brake) for the
+    /// next `duration` seconds, after which the car-following model resumes.
     pub fn set_acceleration(&self, client: &mut TraciClient, vehicle_id: &str, accel: f64, duration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(accel);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(duration);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ACCELERATION, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_acceleration", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(accel);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(duration);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ACCELERATION, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set the action step length in seconds. With `reset_action_offset` the
+    /// next action happens one new step length from now; otherwise the
+    /// current action point is kept.
+    ///
+    /// SUMO has no separate flag on the wire: a negative length means "keep
+    /// the offset", so `length` must not be negative.
+    pub fn set_action_step_length(&self, client: &mut TraciClient, vehicle_id: &str, length: f64, reset_action_offset: bool) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_action_step_length", vehicle_id, {
+            if length < 0.0 {
+                return Err(TraciError::Protocol(format!("Invalid action step length {length}, must be non-negative")));
+            }
+            let value = if reset_action_offset { length } else { -length };
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(value);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ACTIONSTEPLENGTH, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_previous_speed(&self, client: &mut TraciClient, vehicle_id: &str, prev_speed: f64, prev_acceleration: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(prev_speed);
-        add.write_u8(TYPE_DOUBLE); add.write_f64(prev_acceleration);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_PREV_SPEED, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_previous_speed", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(prev_speed);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(prev_acceleration);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_PREV_SPEED, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     /// Note: uses raw byte write matching C++ `writeByte(TYPE_INTEGER)`.
     pub fn set_lane_change_mode(&self, client: &mut TraciClient, vehicle_id: &str, mode: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(mode);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_lane_change_mode", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(mode);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set the lane change mode from a typed [`LaneChangeMode`].
+    pub fn set_lane_change_mode_typed(&self, client: &mut TraciClient, vehicle_id: &str, mode: LaneChangeMode) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_lane_change_mode_typed", vehicle_id, {
+            crate::scope_set_int!(client, CMD_SET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, vehicle_id, mode.to_bits())?;
+            Ok(())
+        })
     }
 
     /// Note: uses raw byte write matching C++ `writeByte(TYPE_INTEGER)`.
     pub fn set_speed_mode(&self, client: &mut TraciClient, vehicle_id: &str, mode: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(mode);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_speed_mode", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(mode);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set the speed mode from a typed [`SpeedMode`].
+    pub fn set_speed_mode_typed(&self, client: &mut TraciClient, vehicle_id: &str, mode: SpeedMode) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_speed_mode_typed", vehicle_id, {
+            crate::scope_set_int!(client, CMD_SET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, vehicle_id, mode.bits())?;
+            Ok(())
+        })
     }
 
     /// Set a stop for a vehicle.
+    #[allow(clippy::too_many_arguments)]
     pub fn set_stop(
         &self,
         client: &mut TraciClient,
@@ -930,10 +1452,27 @@ impl VehicleScope {
         flags: i32,
         start_pos: f64,
         until: f64,
+    ) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_stop", vehicle_id, {
+            self.send_stop(client, vehicle_id, edge_id, end_pos, lane_index, duration, flags, start_pos, until)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn send_stop(
+        &self,
+        client: &mut TraciClient,
+        vehicle_id: &str,
+        edge_id: &str,
+        end_pos: f64,
+        lane_index: i32,
+        duration: f64,
+        flags: i32,
+        start_pos: f64,
+        until: f64,
     ) -> Result<(), TraciError> {
         let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(7);
+        add.begin_compound(7);
         add.write_u8(TYPE_STRING); add.write_string(edge_id);
         add.write_u8(TYPE_DOUBLE); add.write_f64(end_pos);
         add.write_u8(TYPE_BYTE);   add.write_u8(lane_index as u8);
@@ -946,110 +1485,329 @@ impl VehicleScope {
         Ok(())
     }
 
+    /// Stop at the bus stop `stop_id` for `duration` seconds or until
+    /// simulation time `until`; pass `INVALID_DOUBLE_VALUE` to leave either unset.
+    pub fn set_bus_stop(&self, client: &mut TraciClient, vehicle_id: &str, stop_id: &str, duration: f64, until: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_bus_stop", vehicle_id, {
+            // The stopping place id takes the place of the edge id.
+            self.send_stop(client, vehicle_id, stop_id, 1.0, 0, duration, STOP_BUS_STOP as i32, INVALID_DOUBLE_VALUE, until)
+        })
+    }
+
+    /// Park off the road at the parking area `stop_id` for `duration` seconds
+    /// or until simulation time `until`; pass `INVALID_DOUBLE_VALUE` to leave
+    /// either unset.
+    pub fn set_parking_area(&self, client: &mut TraciClient, vehicle_id: &str, stop_id: &str, duration: f64, until: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_parking_area", vehicle_id, {
+            // The stopping place id takes the place of the edge id.
+            self.send_stop(client, vehicle_id, stop_id, 1.0, 0, duration, (STOP_PARKING | STOP_PARKING_AREA) as i32, INVALID_DOUBLE_VALUE, until)
+        })
+    }
+
+    /// Stop at the charging station `stop_id` for `duration` seconds or until
+    /// simulation time `until`; pass `INVALID_DOUBLE_VALUE` to leave either unset.
+    pub fn set_charging_station(&self, client: &mut TraciClient, vehicle_id: &str, stop_id: &str, duration: f64, until: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_charging_station", vehicle_id, {
+            // The stopping place id takes the place of the edge id.
+            self.send_stop(client, vehicle_id, stop_id, 1.0, 0, duration, STOP_CHARGING_STATION as i32, INVALID_DOUBLE_VALUE, until)
+        })
+    }
+
+    /// Stop at the container stop `stop_id` for `duration` seconds or until
+    /// simulation time `until`; pass `INVALID_DOUBLE_VALUE` to leave either unset.
+    pub fn set_container_stop(&self, client: &mut TraciClient, vehicle_id: &str, stop_id: &str, duration: f64, until: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_container_stop", vehicle_id, {
+            // The stopping place id takes the place of the edge id.
+            self.send_stop(client, vehicle_id, stop_id, 1.0, 0, duration, STOP_CONTAINER_STOP as i32, INVALID_DOUBLE_VALUE, until)
+        })
+    }
+
+    /// Replace the stop at `next_stop_index` (0 = next upcoming stop) with a
+    /// new one. An empty `edge_id` deletes the stop instead of replacing it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_stop(
+        &self,
+        client: &mut TraciClient,
+        vehicle_id: &str,
+        next_stop_index: i32,
+        edge_id: &str,
+        end_pos: f64,
+        lane_index: i32,
+        duration: f64,
+        flags: i32,
+        start_pos: f64,
+        until: f64,
+    ) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.replace_stop", vehicle_id, {
+            let add = stop_modification(next_stop_index, edge_id, end_pos, lane_index, duration, flags, start_pos, until);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_REPLACE_STOP, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Insert a new stop before the stop at `next_stop_index`
+    /// (0 = before the next upcoming stop).
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_stop(
+        &self,
+        client: &mut TraciClient,
+        vehicle_id: &str,
+        next_stop_index: i32,
+        edge_id: &str,
+        end_pos: f64,
+        lane_index: i32,
+        duration: f64,
+        flags: i32,
+        start_pos: f64,
+        until: f64,
+    ) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.insert_stop", vehicle_id, {
+            let add = stop_modification(next_stop_index, edge_id, end_pos, lane_index, duration, flags, start_pos, until);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_INSERT_STOP, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Let a vehicle continue from its current stop.
+    pub fn resume(&self, client: &mut TraciClient, vehicle_id: &str) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.resume", vehicle_id, {
+            let mut add = Storage::new();
+            add.begin_compound(0);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_RESUME, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
     pub fn set_type(&self, client: &mut TraciClient, vehicle_id: &str, type_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(type_id);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_TYPE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_type", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(type_id);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_TYPE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_color(&self, client: &mut TraciClient, vehicle_id: &str, c: &TraciColor) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COLOR);
-        add.write_u8(c.r);
-        add.write_u8(c.g);
-        add.write_u8(c.b);
-        add.write_u8(c.a);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_COLOR, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_color", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_COLOR, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Draw a circle of colour `color` around the vehicle in sumo-gui.
+    ///
+    /// `size <= 0` picks a radius from the vehicle size. With `alpha_max > 0`
+    /// the circle fades in and out over `duration` seconds instead of staying
+    /// until removed. `type_` selects a highlight layer so several highlights
+    /// can coexist; `0` is the default layer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn highlight(&self, client: &mut TraciClient, vehicle_id: &str, color: &TraciColor, size: f64, alpha_max: u8, duration: f64, type_: i32) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.highlight", vehicle_id, {
+            let count = if type_ > 0 { 5 } else if alpha_max > 0 { 4 } else { 2 };
+            let mut add = Storage::new();
+            add.begin_compound(count);
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(color.r);
+            add.write_u8(color.g);
+            add.write_u8(color.b);
+            add.write_u8(color.a);
+            add.write_u8(TYPE_DOUBLE); add.write_f64(size);
+            if count > 2 {
+                add.write_u8(TYPE_UBYTE); add.write_u8(alpha_max);
+                add.write_u8(TYPE_DOUBLE); add.write_f64(duration);
+            }
+            if count > 4 {
+                add.write_u8(TYPE_UBYTE); add.write_u8(type_ as u8);
+            }
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_HIGHLIGHT, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set every vehicle currently in the network to `c`, using one batched
+    /// message for all of them.
+    pub fn reset_all_colors(&self, client: &mut TraciClient, c: &TraciColor) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.reset_all_colors", {
+            let ids = crate::scope_get_string_list!(client, CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "")?;
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            let requests: Vec<_> = ids
+                .iter()
+                .map(|id| (CMD_SET_VEHICLE_VARIABLE, VAR_COLOR, id.as_str(), add.clone()))
+                .collect();
+            client.batch_set(&requests)?.into_iter().collect()
+        })
     }
 
     pub fn set_line(&self, client: &mut TraciClient, vehicle_id: &str, line: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(line);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_LINE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_line", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(line);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_LINE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_via(&self, client: &mut TraciClient, vehicle_id: &str, via: &[String]) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRINGLIST);
-        add.write_i32(via.len() as i32);
-        for v in via {
-            add.write_string(v);
-        }
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_VIA, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_via", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_i32(via.len() as i32);
+            for v in via {
+                add.write_string(v);
+            }
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_VIA, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_signals(&self, client: &mut TraciClient, vehicle_id: &str, signals: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(signals);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_signals", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(signals);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set the signals from a typed [`VehicleSignals`].
+    pub fn set_signals_typed(&self, client: &mut TraciClient, vehicle_id: &str, signals: VehicleSignals) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_signals_typed", vehicle_id, {
+            crate::scope_set_int!(client, CMD_SET_VEHICLE_VARIABLE, VAR_SIGNALS, vehicle_id, signals.bits())?;
+            Ok(())
+        })
     }
 
     pub fn set_routing_mode(&self, client: &mut TraciClient, vehicle_id: &str, routing_mode: i32) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_INTEGER);
-        add.write_i32(routing_mode);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_routing_mode", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(routing_mode);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    /// Set the routing mode from a typed [`RoutingMode`].
+    pub fn set_routing_mode_typed(&self, client: &mut TraciClient, vehicle_id: &str, mode: RoutingMode) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.set_routing_mode_typed", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(mode.bits());
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // VehicleType attribute shortcuts
     pub fn set_shape_class(&self, client: &mut TraciClient, vehicle_id: &str, clazz: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(clazz);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SHAPECLASS, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_shape_class", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(clazz);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SHAPECLASS, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_emission_class(&self, client: &mut TraciClient, vehicle_id: &str, clazz: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(clazz);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_EMISSIONCLASS, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_emission_class", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(clazz);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_EMISSIONCLASS, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_speed_factor(&self, client: &mut TraciClient, vehicle_id: &str, factor: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(factor);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED_FACTOR, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_speed_factor", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(factor);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED_FACTOR, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_min_gap(&self, client: &mut TraciClient, vehicle_id: &str, min_gap: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(min_gap);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MINGAP, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_min_gap", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(min_gap);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MINGAP, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_max_speed(&self, client: &mut TraciClient, vehicle_id: &str, speed: f64) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_DOUBLE);
-        add.write_f64(speed);
-        client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MAXSPEED, vehicle_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle.set_max_speed", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_DOUBLE);
+            add.write_f64(speed);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, VAR_MAXSPEED, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
+    }
+
+    // -----------------------------------------------------------------------
+    // Taxi device
+    // -----------------------------------------------------------------------
+
+    /// Return the taxis in the given state: `-1` any, `0` empty, `1` on the way
+    /// to a pickup, `2` occupied, `3` picking up while occupied.
+    pub fn get_taxi_fleet(&self, client: &mut TraciClient, flag: i32) -> Result<Vec<String>, TraciError> {
+        crate::with_context!("vehicle.get_taxi_fleet", {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_INTEGER);
+            add.write_i32(flag);
+            client.create_command(CMD_GET_VEHICLE_VARIABLE, VAR_TAXI_FLEET, "", Some(&add));
+            client.process_get(CMD_GET_VEHICLE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
+    }
+
+    /// Dispatch a taxi to serve the given reservations, in order. A
+    /// reservation id appears twice if pickup and drop-off are interleaved
+    /// with other reservations.
+    pub fn dispatch_taxi(&self, client: &mut TraciClient, vehicle_id: &str, reservations: &[String]) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.dispatch_taxi", vehicle_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRINGLIST);
+            add.write_string_list(reservations);
+            client.create_command(CMD_SET_VEHICLE_VARIABLE, CMD_TAXI_DISPATCH, vehicle_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -1057,11 +1815,24 @@ impl VehicleScope {
     // -----------------------------------------------------------------------
 
     pub fn subscribe(&self, client: &mut TraciClient, vehicle_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLE_VARIABLE, vehicle_id, begin, end, vars)
+        crate::with_context!("vehicle.subscribe", vehicle_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLE_VARIABLE, vehicle_id, begin, end, vars)
+        })
     }
 
+    /// Subscribe to typed [`VehicleVar`]s; see [`Self::subscribe`] for raw ids.
+    pub fn subscribe_vars(&self, client: &mut TraciClient, vehicle_id: &str, vars: &[VehicleVar], begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.subscribe_vars", vehicle_id, {
+            let vars: Vec<u8> = vars.iter().map(|v| v.as_u8()).collect();
+            client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLE_VARIABLE, vehicle_id, begin, end, &vars)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, vehicle_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_VEHICLE_CONTEXT, vehicle_id, begin, end, domain, range, vars)
+        crate::with_context!("vehicle.subscribe_context", vehicle_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_VEHICLE_CONTEXT, vehicle_id, begin, end, domain, range, vars)
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -1069,18 +1840,14 @@ impl VehicleScope {
     // -----------------------------------------------------------------------
 
     fn add_subscription_filter_empty(&self, client: &mut TraciClient, filter_type: u8) -> Result<(), TraciError> {
-        client.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, filter_type, None);
-        client.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
-        Ok(())
+        client.add_subscription_filter(filter_type, None)
     }
 
     fn add_subscription_filter_float(&self, client: &mut TraciClient, filter_type: u8, val: f64) -> Result<(), TraciError> {
         let mut add = Storage::new();
         add.write_u8(TYPE_DOUBLE);
         add.write_f64(val);
-        client.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, filter_type, Some(&add));
-        client.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
-        Ok(())
+        client.add_subscription_filter(filter_type, Some(&add))
     }
 
     fn add_subscription_filter_string_list(&self, client: &mut TraciClient, filter_type: u8, vals: &[String]) -> Result<(), TraciError> {
@@ -1090,20 +1857,14 @@ impl VehicleScope {
         for v in vals {
             add.write_string(v);
         }
-        client.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, filter_type, Some(&add));
-        client.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
-        Ok(())
+        client.add_subscription_filter(filter_type, Some(&add))
     }
 
     fn add_subscription_filter_byte_list(&self, client: &mut TraciClient, filter_type: u8, vals: &[i32]) -> Result<(), TraciError> {
+        let bytes: Vec<u8> = vals.iter().map(|&v| v as u8).collect();
         let mut add = Storage::new();
-        add.write_u8(vals.len() as u8);
-        for &v in vals {
-            add.write_u8(v as u8);
-        }
-        client.create_filter_command(CMD_ADD_SUBSCRIPTION_FILTER, filter_type, Some(&add));
-        client.process_set(CMD_ADD_SUBSCRIPTION_FILTER)?;
-        Ok(())
+        add.write_u8_list(&bytes)?;
+        client.add_subscription_filter(filter_type, Some(&add))
     }
 
     // -----------------------------------------------------------------------
@@ -1118,32 +1879,40 @@ impl VehicleScope {
         downstream_dist: f64,
         upstream_dist: f64,
     ) -> Result<(), TraciError> {
-        self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, lanes)?;
-        if no_opposite {
-            self.add_subscription_filter_empty(client, FILTER_TYPE_NOOPPOSITE)?;
-        }
-        if downstream_dist >= 0.0 {
-            self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
-        }
-        if upstream_dist >= 0.0 {
-            self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, upstream_dist)?;
-        }
-        Ok(())
+        crate::with_context!("vehicle.add_subscription_filter_lanes", {
+            self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, lanes)?;
+            if no_opposite {
+                self.add_subscription_filter_empty(client, FILTER_TYPE_NOOPPOSITE)?;
+            }
+            if downstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
+            }
+            if upstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, upstream_dist)?;
+            }
+            Ok(())
+        })
     }
 
     /// Omit vehicles on opposite-direction lanes.
     pub fn add_subscription_filter_no_opposite(&self, client: &mut TraciClient) -> Result<(), TraciError> {
-        self.add_subscription_filter_empty(client, FILTER_TYPE_NOOPPOSITE)
+        crate::with_context!("vehicle.add_subscription_filter_no_opposite", {
+            self.add_subscription_filter_empty(client, FILTER_TYPE_NOOPPOSITE)
+        })
     }
 
     /// Limit the downstream distance for resulting vehicles.
     pub fn add_subscription_filter_downstream_distance(&self, client: &mut TraciClient, dist: f64) -> Result<(), TraciError> {
-        self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, dist)
+        crate::with_context!("vehicle.add_subscription_filter_downstream_distance", {
+            self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, dist)
+        })
     }
 
     /// Limit the upstream distance for resulting vehicles.
     pub fn add_subscription_filter_upstream_distance(&self, client: &mut TraciClient, dist: f64) -> Result<(), TraciError> {
-        self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, dist)
+        crate::with_context!("vehicle.add_subscription_filter_upstream_distance", {
+            self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, dist)
+        })
     }
 
     /// Restrict to leader and follower of the ego (car-following maneuver).
@@ -1152,14 +1921,17 @@ impl VehicleScope {
         downstream_dist: f64,
         upstream_dist: f64,
     ) -> Result<(), TraciError> {
-        self.add_subscription_filter_lead_follow(client, &[0])?;
-        if downstream_dist >= 0.0 {
-            self.add_subscription_filter_downstream_distance(client, downstream_dist)?;
-        }
-        if upstream_dist >= 0.0 {
-            self.add_subscription_filter_upstream_distance(client, upstream_dist)?;
-        }
-        Ok(())
+        crate::with_context!("vehicle.add_subscription_filter_cf_maneuver", {
+            self.add_subscription_filter_empty(client, FILTER_TYPE_LEAD_FOLLOW)?;
+            self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, &[0])?;
+            if downstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
+            }
+            if upstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, upstream_dist)?;
+            }
+            Ok(())
+        })
     }
 
     /// Restrict to neighbors/leader/follower for a lane-change maneuver.
@@ -1170,27 +1942,32 @@ impl VehicleScope {
         downstream_dist: f64,
         upstream_dist: f64,
     ) -> Result<(), TraciError> {
-        if direction.abs() != 1 {
-            eprintln!("Ignoring lane change subscription filter with non-neighboring lane offset direction {}", direction);
-            return Ok(());
-        }
-        self.add_subscription_filter_lead_follow(client, &[0, direction])?;
-        if no_opposite {
-            self.add_subscription_filter_no_opposite(client)?;
-        }
-        if downstream_dist >= 0.0 {
-            self.add_subscription_filter_downstream_distance(client, downstream_dist)?;
-        }
-        if upstream_dist >= 0.0 {
-            self.add_subscription_filter_upstream_distance(client, upstream_dist)?;
-        }
-        Ok(())
+        crate::with_context!("vehicle.add_subscription_filter_lc_maneuver", {
+            if direction.abs() != 1 {
+                eprintln!("Ignoring lane change subscription filter with non-neighboring lane offset direction {}", direction);
+                return Ok(());
+            }
+            self.add_subscription_filter_empty(client, FILTER_TYPE_LEAD_FOLLOW)?;
+            self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, &[0, direction])?;
+            if no_opposite {
+                self.add_subscription_filter_empty(client, FILTER_TYPE_NOOPPOSITE)?;
+            }
+            if downstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
+            }
+            if upstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, upstream_dist)?;
+            }
+            Ok(())
+        })
     }
 
     /// Restrict to lead and follow vehicles in given lanes.
     pub fn add_subscription_filter_lead_follow(&self, client: &mut TraciClient, lanes: &[i32]) -> Result<(), TraciError> {
-        self.add_subscription_filter_empty(client, FILTER_TYPE_LEAD_FOLLOW)?;
-        self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, lanes)
+        crate::with_context!("vehicle.add_subscription_filter_lead_follow", {
+            self.add_subscription_filter_empty(client, FILTER_TYPE_LEAD_FOLLOW)?;
+            self.add_subscription_filter_byte_list(client, FILTER_TYPE_LANES, lanes)
+        })
     }
 
     /// Restrict to foes on an upcoming junction.
@@ -1199,26 +1976,34 @@ impl VehicleScope {
         downstream_dist: f64,
         foe_dist_to_junction: f64,
     ) -> Result<(), TraciError> {
-        self.add_subscription_filter_float(client, FILTER_TYPE_TURN, foe_dist_to_junction)?;
-        if downstream_dist >= 0.0 {
-            self.add_subscription_filter_downstream_distance(client, downstream_dist)?;
-        }
-        Ok(())
+        crate::with_context!("vehicle.add_subscription_filter_turn", {
+            self.add_subscription_filter_float(client, FILTER_TYPE_TURN, foe_dist_to_junction)?;
+            if downstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
+            }
+            Ok(())
+        })
     }
 
     /// Restrict returned vehicles to given vehicle classes.
     pub fn add_subscription_filter_vclass(&self, client: &mut TraciClient, v_classes: &[String]) -> Result<(), TraciError> {
-        self.add_subscription_filter_string_list(client, FILTER_TYPE_VCLASS, v_classes)
+        crate::with_context!("vehicle.add_subscription_filter_vclass", {
+            self.add_subscription_filter_string_list(client, FILTER_TYPE_VCLASS, v_classes)
+        })
     }
 
     /// Restrict returned vehicles to given vehicle types.
     pub fn add_subscription_filter_vtype(&self, client: &mut TraciClient, v_types: &[String]) -> Result<(), TraciError> {
-        self.add_subscription_filter_string_list(client, FILTER_TYPE_VTYPE, v_types)
+        crate::with_context!("vehicle.add_subscription_filter_vtype", {
+            self.add_subscription_filter_string_list(client, FILTER_TYPE_VTYPE, v_types)
+        })
     }
 
     /// Restrict returned vehicles to given field-of-vision angle.
     pub fn add_subscription_filter_field_of_vision(&self, client: &mut TraciClient, angle: f64) -> Result<(), TraciError> {
-        self.add_subscription_filter_float(client, FILTER_TYPE_FIELD_OF_VISION, angle)
+        crate::with_context!("vehicle.add_subscription_filter_field_of_vision", {
+            self.add_subscription_filter_float(client, FILTER_TYPE_FIELD_OF_VISION, angle)
+        })
     }
 
     /// Restrict returned vehicles to given lateral distance.
@@ -1228,14 +2013,16 @@ impl VehicleScope {
         downstream_dist: f64,
         upstream_dist: f64,
     ) -> Result<(), TraciError> {
-        self.add_subscription_filter_float(client, FILTER_TYPE_LATERAL_DIST, lateral_dist)?;
-        if downstream_dist >= 0.0 {
-            self.add_subscription_filter_downstream_distance(client, downstream_dist)?;
-        }
-        if upstream_dist >= 0.0 {
-            self.add_subscription_filter_upstream_distance(client, upstream_dist)?;
-        }
-        Ok(())
+        crate::with_context!("vehicle.add_subscription_filter_lateral_distance", {
+            self.add_subscription_filter_float(client, FILTER_TYPE_LATERAL_DIST, lateral_dist)?;
+            if downstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_DOWNSTREAM_DIST, downstream_dist)?;
+            }
+            if upstream_dist >= 0.0 {
+                self.add_subscription_filter_float(client, FILTER_TYPE_UPSTREAM_DIST, upstream_dist)?;
+            }
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -1252,10 +2039,10 @@ impl VehicleScope {
     /// ```no_run
     /// # use traci_rs::TraciClient;
     /// # let mut client = TraciClient::connect("localhost", 8813).unwrap();
-    /// client.vehicle.subscribe_kinematics(&mut client, "veh_0", 0.0, 3600.0).unwrap();
+    /// client.vehicle_subscribe_kinematics("veh_0", 0.0, 3600.0).unwrap();
     /// loop {
     ///     client.simulation_step(0.0).unwrap();
-    ///     if let Some(k) = client.vehicle.get_subscribed_kinematics("veh_0") {
+    ///     if let Some(k) = client.vehicle_get_subscribed_kinematics("veh_0") {
     ///         println!("pos={:?} speed={}", k.position, k.speed);
     ///     }
     /// }
@@ -1267,16 +2054,18 @@ impl VehicleScope {
         begin: f64,
         end: f64,
     ) -> Result<(), TraciError> {
-        // VAR_ACCELERATION (0x72) is the signed acceleration consistent with
-        // get_acceleration(); use it here for API consistency.
-        let vars = [VAR_POSITION, VAR_SPEED, VAR_ACCELERATION, VAR_ANGLE];
-        client.subscribe_object_variable(
-            CMD_SUBSCRIBE_VEHICLE_VARIABLE,
-            vehicle_id,
-            begin,
-            end,
-            &vars,
-        )
+        crate::with_context!("vehicle.subscribe_kinematics", vehicle_id, {
+            // VAR_ACCELERATION (0x72) is the signed acceleration consistent with
+            // get_acceleration(); use it here for API consistency.
+            let vars = [VAR_POSITION, VAR_SPEED, VAR_ACCELERATION, VAR_ANGLE];
+            client.subscribe_object_variable(
+                CMD_SUBSCRIBE_VEHICLE_VARIABLE,
+                vehicle_id,
+                begin,
+                end,
+                &vars,
+            )
+        })
     }
 
     /// Read the kinematic state for `vehicle_id` from the subscription cache.
@@ -1307,4 +2096,808 @@ impl VehicleScope {
         };
         Some(crate::types::SubscribedKinematics { position: pos, speed, acceleration, angle })
     }
+
+    /// Subscribe `vehicle_id` to `VAR_BEST_LANES` for the duration
+    /// [`begin`, `end`]; read the results with
+    /// [`Self::get_subscribed_best_lanes`] instead of calling
+    /// [`Self::get_best_lanes`] every step.
+    pub fn subscribe_best_lanes(&self, client: &mut TraciClient, vehicle_id: &str, begin: f64, end: f64) -> Result<(), TraciError> {
+        crate::with_context!("vehicle.subscribe_best_lanes", vehicle_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLE_VARIABLE, vehicle_id, begin, end, &[VAR_BEST_LANES])
+        })
+    }
+
+    /// Read the best lanes of `vehicle_id` from the subscription cache, or
+    /// `None` if `VAR_BEST_LANES` is not subscribed for this vehicle.
+    pub fn get_subscribed_best_lanes(&self, vehicle_id: &str) -> Option<&[TraciBestLanesData]> {
+        match self.subscription_results.get(vehicle_id)?.get(&VAR_BEST_LANES)? {
+            crate::types::TraciValue::BestLanesList(lanes) => Some(lanes),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Shared helper: decode VAR_BEST_LANES
+// ============================================================================
+
+/// Decode a `VAR_BEST_LANES` value (`TYPE_COMPOUND` tag already consumed).
+///
+/// The continuation lanes are an untagged string list nested in the
+/// compound, so the generic compound decoder cannot parse it.
+pub(crate) fn read_best_lanes(in_msg: &mut Storage) -> Result<Vec<TraciBestLanesData>, TraciError> {
+    in_msg.read_i32()?; // total element count
+    in_msg.read_u8()?;  // TYPE_INTEGER
+    let n = in_msg.read_i32()?;
+    let mut result = Vec::with_capacity(n.max(0) as usize);
+    for _ in 0..n {
+        in_msg.read_u8()?;
+        let lane_id = in_msg.read_string()?;
+        in_msg.read_u8()?;
+        let length = in_msg.read_f64()?;
+        in_msg.read_u8()?;
+        let occupation = in_msg.read_f64()?;
+        in_msg.read_u8()?;
+        // bestLaneOffset is a signed byte
+        let best_lane_offset = in_msg.read_u8()? as i8 as i32;
+        in_msg.read_u8()?;
+        let allows_continuation = in_msg.read_u8()? == 1;
+        in_msg.read_u8()?;
+        let continuation_lanes = in_msg.read_string_list()?;
+        result.push(TraciBestLanesData {
+            lane_id,
+            length,
+            occupation,
+            best_lane_offset,
+            allows_continuation,
+            continuation_lanes,
+        });
+    }
+    Ok(result)
+}
+
+// ============================================================================
+// Shared helper: internal edge id → junction id
+// ============================================================================
+
+/// Junction id of an internal edge id such as `:J1_0` (or the crossing and
+/// walking area edges `:J1_c0` / `:J1_w0`); `None` for normal edges. Junction
+/// ids may contain `_`, so only the last separator is split off.
+fn internal_edge_junction(edge_id: &str) -> Option<&str> {
+    let internal = edge_id.strip_prefix(':')?;
+    Some(internal.rsplit_once('_').map_or(internal, |(junction, _)| junction))
+}
+
+// ============================================================================
+// Shared helper: route index → split point
+// ============================================================================
+
+/// Clamp a route index (-1 before departure) into `0..=len`.
+fn route_split(route_index: i32, len: usize) -> usize {
+    (route_index.max(0) as usize).min(len)
+}
+
+// ============================================================================
+// Shared helper: stop compound for CMD_REPLACE_STOP / CMD_INSERT_STOP
+// ============================================================================
+
+/// Build the 8-element compound shared by `replace_stop` and `insert_stop`.
+#[allow(clippy::too_many_arguments)]
+fn stop_modification(
+    next_stop_index: i32,
+    edge_id: &str,
+    end_pos: f64,
+    lane_index: i32,
+    duration: f64,
+    flags: i32,
+    start_pos: f64,
+    until: f64,
+) -> Storage {
+    let mut add = Storage::new();
+    add.begin_compound(8);
+    add.write_u8(TYPE_STRING);  add.write_string(edge_id);
+    add.write_u8(TYPE_DOUBLE);  add.write_f64(end_pos);
+    add.write_u8(TYPE_BYTE);    add.write_u8(lane_index as u8);
+    add.write_u8(TYPE_DOUBLE);  add.write_f64(duration);
+    add.write_u8(TYPE_INTEGER); add.write_i32(flags);
+    add.write_u8(TYPE_DOUBLE);  add.write_f64(start_pos);
+    add.write_u8(TYPE_DOUBLE);  add.write_f64(until);
+    add.write_u8(TYPE_INTEGER); add.write_i32(next_stop_index);
+    add
+}
+
+// ============================================================================
+// Shared helper: read one TraciNextStopData compound from client.input
+// ============================================================================
+
+/// Read a stop-data compound (16 typed fields) from the input buffer.
+fn read_next_stop_data(client: &mut TraciClient) -> Result<TraciNextStopData, TraciError> {
+    client.read_ubyte_from_input()?; // TYPE_COMPOUND
+    client.read_int_from_input()?; // components count
+
+    client.read_ubyte_from_input()?;
+    let lane = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let end_pos = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let stopping_place_id = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let stop_flags = client.read_int_from_input()?;
+    client.read_ubyte_from_input()?;
+    let duration = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let until = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let start_pos = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let intended_arrival = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let arrival = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let depart = client.read_double_from_input()?;
+    client.read_ubyte_from_input()?;
+    let split = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let join = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let act_type = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let trip_id = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let line = client.read_string_from_input()?;
+    client.read_ubyte_from_input()?;
+    let speed = client.read_double_from_input()?;
+
+    Ok(TraciNextStopData {
+        lane,
+        start_pos,
+        end_pos,
+        stopping_place_id,
+        stop_flags,
+        duration,
+        until,
+        intended_arrival,
+        arrival,
+        depart,
+        split,
+        join,
+        act_type,
+        trip_id,
+        line,
+        speed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    fn stop(lane: &str, stopping_place_id: &str, stop_flags: i32) -> TraciNextStopData {
+        TraciNextStopData {
+            lane: lane.into(),
+            start_pos: 10.0,
+            end_pos: 25.0,
+            stopping_place_id: stopping_place_id.into(),
+            stop_flags,
+            duration: 30.0,
+            until: -1.0,
+            intended_arrival: -1.0,
+            arrival: 95.0,
+            depart: -1.0,
+            split: String::new(),
+            join: String::new(),
+            act_type: String::new(),
+            trip_id: String::new(),
+            line: "42".into(),
+            speed: 0.0,
+        }
+    }
+
+    /// Encode `stops` as SUMO sends `VAR_NEXT_STOPS2`.
+    fn stops_value(stops: &[TraciNextStopData]) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(stops.len() as i32);
+        for st in stops {
+            s.begin_compound(16);
+            s.write_u8(TYPE_STRING);
+            s.write_string(&st.lane);
+            s.write_u8(TYPE_DOUBLE);
+            s.write_f64(st.end_pos);
+            s.write_u8(TYPE_STRING);
+            s.write_string(&st.stopping_place_id);
+            s.write_u8(TYPE_INTEGER);
+            s.write_i32(st.stop_flags);
+            for d in [st.duration, st.until, st.start_pos, st.intended_arrival, st.arrival, st.depart] {
+                s.write_u8(TYPE_DOUBLE);
+                s.write_f64(d);
+            }
+            for text in [&st.split, &st.join, &st.act_type, &st.trip_id, &st.line] {
+                s.write_u8(TYPE_STRING);
+                s.write_string(text);
+            }
+            s.write_u8(TYPE_DOUBLE);
+            s.write_f64(st.speed);
+        }
+        s
+    }
+
+    #[test]
+    fn current_stop_of_a_moving_vehicle_is_none() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, "bus0", mock::int(0));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(VehicleScope::default().current_stop(&mut client, "bus0").unwrap(), None);
+        drop(client);
+        assert_eq!(server.requests().len(), 1, "no stop query for a moving vehicle");
+    }
+
+    #[test]
+    fn current_stop_of_a_stopped_vehicle_is_the_first_stop() {
+        let halted = stop("e1_0", "busStop1", i32::from(STOP_BUS_STOP));
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, "bus0", mock::int(StopState::STOPPED.bits() | StopState::BUS_STOP.bits())),
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_STOPS2, "bus0", stops_value(std::slice::from_ref(&halted))),
+        ]);
+
+        let scope = VehicleScope::default();
+        assert_eq!(scope.current_stop(&mut client, "bus0").unwrap(), Some(halted));
+        drop(client);
+        assert_eq!(
+            server.requests()[1],
+            mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_STOPS2, "bus0", Some(&mock::int(1)))
+        );
+    }
+
+    #[test]
+    fn release_speed_control_sends_minus_one() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        VehicleScope::default().release_speed_control(&mut client, "veh0").unwrap();
+        drop(client);
+
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEED, "veh0", Some(&mock::double(-1.0)))]
+        );
+    }
+
+    #[test]
+    fn get_geo_position_matches_the_server_conversion() {
+        // SUMO's convertGeo maps the network position below to 9.5°E 48.7°N:
+        // shifted by the net offset it is UTM 32N (536790.3393, 5394227.6773).
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_POSITION, "veh0", mock::pos_2d(790.339_273, 227.677_295)),
+            mock::get_reply(
+                CMD_GET_SIM_VARIABLE,
+                VAR_PARAMETER,
+                "",
+                mock::string("+proj=utm +zone=32 +ellps=WGS84 +datum=WGS84 +units=m +no_defs"),
+            ),
+            mock::get_reply(CMD_GET_SIM_VARIABLE, VAR_PARAMETER, "", mock::string("-536000.00,-5394000.00")),
+        ]);
+
+        let geo = VehicleScope::default().get_geo_position(&mut client, "veh0").unwrap();
+        // 1e-7 degrees is about a centimetre
+        assert!((geo.x - 9.5).abs() < 1e-7, "longitude {}", geo.x);
+        assert!((geo.y - 48.7).abs() < 1e-7, "latitude {}", geo.y);
+
+        drop(client);
+        assert_eq!(server.requests().len(), 3, "no server conversion for a UTM network");
+    }
+
+    #[test]
+    fn reset_all_colors_sends_one_combined_message() {
+        let fleet = ["veh0", "veh1", "veh2"];
+        let mut statuses = Storage::new();
+        for _ in fleet {
+            mock::write_status(&mut statuses, CMD_SET_VEHICLE_VARIABLE, RTYPE_OK, "");
+        }
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", mock::string_list(&fleet)),
+            statuses,
+        ]);
+
+        let yellow = TraciColor { r: 255, g: 255, b: 0, a: 255 };
+        VehicleScope::default().reset_all_colors(&mut client, &yellow).unwrap();
+
+        drop(client);
+        let mut color = Storage::new();
+        color.write_u8(TYPE_COLOR);
+        color.write_packet(&[255, 255, 0, 255]);
+        let combined: Vec<u8> = fleet
+            .iter()
+            .flat_map(|id| mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_COLOR, id, Some(&color)))
+            .collect();
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_VEHICLE_VARIABLE, TRACI_ID_LIST, "", None), combined]
+        );
+    }
+
+    #[test]
+    fn set_routing_mode_typed_sends_the_combined_bits() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        let mode = RoutingMode::default().aggregated().ignore_transient_permissions(true);
+        VehicleScope::default().set_routing_mode_typed(&mut client, "veh0", mode).unwrap();
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_ROUTING_MODE, "veh0", Some(&mock::int(0x09)))]
+        );
+    }
+
+    /// Encode `(lane_id, best_lane_offset)` entries as SUMO sends
+    /// `VAR_BEST_LANES`.
+    fn best_lanes_value(lanes: &[(&str, i8)]) -> Storage {
+        let mut s = Storage::new();
+        // The lane count plus six items per lane
+        s.begin_compound(1 + 6 * lanes.len() as i32);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(lanes.len() as i32);
+        for (lane_id, offset) in lanes {
+            s.write_u8(TYPE_STRING);
+            s.write_string(lane_id);
+            s.write_u8(TYPE_DOUBLE);
+            s.write_f64(250.0);
+            s.write_u8(TYPE_DOUBLE);
+            s.write_f64(0.0);
+            s.write_u8(TYPE_BYTE);
+            s.write_byte(*offset as i32).unwrap();
+            s.write_u8(TYPE_UBYTE);
+            s.write_u8(1);
+            s.write_u8(TYPE_STRINGLIST);
+            s.write_string_list(&[lane_id.to_string()]);
+        }
+        s
+    }
+
+    #[test]
+    fn recommended_lane_offset_over_three_lanes() {
+        // The middle lane leads to the vehicle's next edge
+        let three_lanes = || best_lanes_value(&[("e0_0", 1), ("e0_1", 0), ("e0_2", -1)]);
+        let mut script = Vec::new();
+        for lane in ["e0_0", "e0_2", ":J0_0_0"] {
+            script.push(mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_ID, "veh0", mock::string(lane)));
+            script.push(mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_BEST_LANES, "veh0", three_lanes()));
+        }
+        let (mut client, _server) = mock::connect_replies(script);
+        let scope = VehicleScope::default();
+
+        assert_eq!(scope.recommended_lane_offset(&mut client, "veh0").unwrap(), 1);
+        assert_eq!(scope.recommended_lane_offset(&mut client, "veh0").unwrap(), -1);
+        // Not on a listed lane: the entry nearest zero
+        assert_eq!(scope.recommended_lane_offset(&mut client, "veh0").unwrap(), 0);
+    }
+
+    #[test]
+    fn recommended_lane_offset_without_entries_is_zero() {
+        let (mut client, _server) = mock::connect_replies(vec![
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_LANE_ID, "veh0", mock::string("e0_0")),
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_BEST_LANES, "veh0", best_lanes_value(&[])),
+        ]);
+        assert_eq!(VehicleScope::default().recommended_lane_offset(&mut client, "veh0").unwrap(), 0);
+    }
+
+    #[test]
+    fn get_next_stops_decodes_every_field() {
+        let mut full = stop("e2_0", "parking0", StopState::PARKING.bits());
+        full.split = "veh0.split".into();
+        full.join = "veh9".into();
+        full.act_type = "unloading".into();
+        full.trip_id = "trip7".into();
+        full.until = 300.0;
+        full.speed = 2.5;
+        // The optional string fields of the first stop are empty
+        let stops = vec![stop("e1_0", "", 0), full];
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_NEXT_STOPS2,
+            "veh0",
+            stops_value(&stops),
+        )]);
+
+        assert_eq!(VehicleScope::default().get_next_stops(&mut client, "veh0").unwrap(), stops);
+
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_STOPS2, "veh0", Some(&mock::int(0)))]
+        );
+    }
+
+    #[test]
+    fn get_next_stops_without_stops_is_empty() {
+        let (mut client, _server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_NEXT_STOPS2,
+            "veh0",
+            stops_value(&[]),
+        )]);
+        assert!(VehicleScope::default().get_next_stops(&mut client, "veh0").unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_stops_sends_the_limit() {
+        let passed = vec![stop("e0_0", "busStop0", StopState::BUS_STOP.bits())];
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_NEXT_STOPS2,
+            "veh0",
+            stops_value(&passed),
+        )]);
+
+        assert_eq!(VehicleScope::default().get_stops(&mut client, "veh0", -1).unwrap(), passed);
+
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_NEXT_STOPS2, "veh0", Some(&mock::int(-1)))]
+        );
+    }
+
+    #[test]
+    fn resume_sends_an_empty_compound() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        VehicleScope::default().resume(&mut client, "veh0").unwrap();
+        drop(client);
+        let expected = vec![
+            16, CMD_SET_VEHICLE_VARIABLE, CMD_RESUME,
+            0, 0, 0, 4, b'v', b'e', b'h', b'0',
+            TYPE_COMPOUND, 0, 0, 0, 0,
+        ];
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn reroute_parking_area_sends_the_id_in_a_compound() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        VehicleScope::default().reroute_parking_area(&mut client, "veh0", "pa0").unwrap();
+        drop(client);
+
+        let mut add = Storage::new();
+        add.begin_compound(1);
+        add.write_u8(TYPE_STRING);
+        add.write_string("pa0");
+        let expected = mock::command(CMD_SET_VEHICLE_VARIABLE, CMD_REROUTE_TO_PARKING, "veh0", Some(&add));
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn reroute_to_an_unknown_parking_area_reports_the_server_error() {
+        let reply = mock::error_status(CMD_SET_VEHICLE_VARIABLE, "Parking area 'nope' is not known.");
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        match VehicleScope::default().reroute_parking_area(&mut client, "veh0", "nope") {
+            Err(TraciError::SimulationError { command, message, context }) => {
+                assert_eq!(command, CMD_SET_VEHICLE_VARIABLE);
+                assert_eq!(message, "Parking area 'nope' is not known.");
+                assert_eq!(context, "vehicle.reroute_parking_area('veh0')");
+            }
+            other => panic!("expected a simulation error, got {other:?}"),
+        }
+    }
+
+    /// The compound `replace_stop` and `insert_stop` send, written field by
+    /// field as SUMO reads it.
+    fn stop_modification_value(edge_id: &str, next_stop_index: i32) -> Storage {
+        let mut s = Storage::new();
+        s.write_u8(TYPE_COMPOUND);
+        s.write_i32(8);
+        s.write_u8(TYPE_STRING);
+        s.write_string(edge_id);
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(50.0);
+        s.write_u8(TYPE_BYTE);
+        s.write_u8(1);
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(20.0);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(0);
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(INVALID_DOUBLE_VALUE);
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(INVALID_DOUBLE_VALUE);
+        s.write_u8(TYPE_INTEGER);
+        s.write_i32(next_stop_index);
+        s
+    }
+
+    #[test]
+    fn replace_stop_with_an_empty_edge_sends_an_empty_string() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        VehicleScope::default()
+            .replace_stop(&mut client, "bus0", 1, "", 50.0, 1, 20.0, 0, INVALID_DOUBLE_VALUE, INVALID_DOUBLE_VALUE)
+            .unwrap();
+        drop(client);
+
+        let add = stop_modification_value("", 1);
+        // The edge id is a TYPE_STRING of length zero right after the compound header.
+        assert_eq!(&add.as_bytes()[5..10], &[TYPE_STRING, 0, 0, 0, 0]);
+        let expected = mock::command(CMD_SET_VEHICLE_VARIABLE, CMD_REPLACE_STOP, "bus0", Some(&add));
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn insert_stop_sends_the_stop_and_its_index() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        VehicleScope::default()
+            .insert_stop(&mut client, "bus0", 2, "e3", 50.0, 1, 20.0, 0, INVALID_DOUBLE_VALUE, INVALID_DOUBLE_VALUE)
+            .unwrap();
+        drop(client);
+
+        let add = stop_modification_value("e3", 2);
+        let expected = mock::command(CMD_SET_VEHICLE_VARIABLE, CMD_INSERT_STOP, "bus0", Some(&add));
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn get_time_loss_reads_var_timeloss() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_TIMELOSS, "veh0", mock::double(12.5));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(VehicleScope::default().get_time_loss(&mut client, "veh0").unwrap(), 12.5);
+        drop(client);
+        assert_eq!(server.requests(), vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_TIMELOSS, "veh0", None)]);
+    }
+
+    #[test]
+    fn get_taxi_fleet_sends_the_flag_as_an_integer() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_TAXI_FLEET, "", mock::string_list(&["taxi0", "taxi1"]));
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        assert_eq!(VehicleScope::default().get_taxi_fleet(&mut client, 0).unwrap(), vec!["taxi0", "taxi1"]);
+        drop(client);
+        let expected = mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_TAXI_FLEET, "", Some(&mock::int(0)));
+        assert_eq!(expected[expected.len() - 5..], [TYPE_INTEGER, 0, 0, 0, 0]);
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn dispatch_taxi_sends_the_reservations_as_a_string_list() {
+        let (mut client, server) = mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        let reservations = vec!["r0".to_string(), "r1".to_string(), "r0".to_string()];
+        VehicleScope::default().dispatch_taxi(&mut client, "taxi0", &reservations).unwrap();
+        drop(client);
+
+        let add = mock::string_list(&["r0", "r1", "r0"]);
+        assert_eq!(add.as_bytes()[0], TYPE_STRINGLIST);
+        let expected = mock::command(CMD_SET_VEHICLE_VARIABLE, CMD_TAXI_DISPATCH, "taxi0", Some(&add));
+        assert_eq!(server.requests(), vec![expected]);
+    }
+
+    #[test]
+    fn speed_mode_typed_round_trips_the_raw_bits() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, "veh0", mock::int(0b11110));
+        let (mut client, server) = mock::connect_replies(vec![reply, mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+
+        let mode = VehicleScope::default().get_speed_mode_typed(&mut client, "veh0").unwrap();
+        assert!(!mode.contains(SpeedMode::SAFE_SPEED));
+        assert!(mode.contains(SpeedMode::MAX_ACCEL | SpeedMode::BRAKE_AT_RED));
+        VehicleScope::default().set_speed_mode_typed(&mut client, "veh0", SpeedMode::SAFE_SPEED | SpeedMode::MAX_DECEL).unwrap();
+        drop(client);
+
+        let requests = server.requests();
+        assert_eq!(requests[1], mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_SPEEDSETMODE, "veh0", Some(&mock::int(5))));
+    }
+
+    #[test]
+    fn lane_change_mode_typed_round_trips_the_raw_bits() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, "veh0", mock::int(1621));
+        let (mut client, server) = mock::connect_replies(vec![reply, mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+
+        let mode = VehicleScope::default().get_lane_change_mode_typed(&mut client, "veh0").unwrap();
+        assert_eq!(mode, LaneChangeMode::default());
+        VehicleScope::default().set_lane_change_mode_typed(&mut client, "veh0", LaneChangeMode::from_bits(512)).unwrap();
+        drop(client);
+
+        let requests = server.requests();
+        assert_eq!(requests[1], mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_LANECHANGE_MODE, "veh0", Some(&mock::int(512))));
+    }
+
+    #[test]
+    fn get_stop_state_typed_decodes_a_parking_area_stop() {
+        let bits = 1 | ((STOP_PARKING | STOP_PARKING_AREA) as i32) << 1;
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_STOPSTATE, "veh0", mock::int(bits));
+        let (mut client, _server) = mock::connect_replies(vec![reply]);
+
+        let state = VehicleScope::default().get_stop_state_typed(&mut client, "veh0").unwrap();
+        assert!(state.is_stopped() && state.is_parking() && state.is_at_parking_area());
+        assert!(!state.is_at_bus_stop());
+        assert_eq!(state.bits(), bits);
+    }
+
+    #[test]
+    fn signals_typed_round_trips_the_raw_bits() {
+        let reply = mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_SIGNALS, "ambulance", mock::int(SIGNAL_BLINKER_EMERGENCY | SIGNAL_BRAKELIGHT));
+        let (mut client, server) = mock::connect_replies(vec![reply, mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+
+        let signals = VehicleScope::default().get_signals_typed(&mut client, "ambulance").unwrap();
+        assert!(signals.blinker_emergency() && signals.brake_light());
+        VehicleScope::default().set_signals_typed(&mut client, "ambulance", VehicleSignals::RESET).unwrap();
+        drop(client);
+
+        let requests = server.requests();
+        assert_eq!(requests[1], mock::command(CMD_SET_VEHICLE_VARIABLE, VAR_SIGNALS, "ambulance", Some(&mock::int(-1))));
+    }
+
+    fn route_replies(index: i32) -> Vec<Storage> {
+        vec![
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, "veh0", mock::string_list(&["e0", "e1", "e2", "e3"])),
+            mock::get_reply(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, "veh0", mock::int(index)),
+        ]
+    }
+
+    #[test]
+    fn remaining_route_starts_at_the_current_edge() {
+        let (mut client, server) = mock::connect_replies(route_replies(2));
+        assert_eq!(VehicleScope::default().get_remaining_route(&mut client, "veh0").unwrap(), ["e2", "e3"]);
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![
+                mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_EDGES, "veh0", None),
+                mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_ROUTE_INDEX, "veh0", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn completed_route_stops_before_the_current_edge() {
+        let (mut client, server) = mock::connect_replies(route_replies(2));
+        assert_eq!(VehicleScope::default().get_completed_route(&mut client, "veh0").unwrap(), ["e0", "e1"]);
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn route_split_clamps_the_index() {
+        assert_eq!(route_split(-1, 4), 0);
+        assert_eq!(route_split(2, 4), 2);
+        assert_eq!(route_split(9, 4), 4);
+    }
+
+    fn neighbor_value(id: &str, gap: f64) -> Storage {
+        let mut s = Storage::new();
+        s.begin_compound(2);
+        s.write_u8(TYPE_STRING);
+        s.write_string(id);
+        s.write_u8(TYPE_DOUBLE);
+        s.write_f64(gap);
+        s
+    }
+
+    #[test]
+    fn leader_opt_returns_the_leader_and_gap() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_LEADER,
+            "veh0",
+            neighbor_value("veh1", 12.5),
+        )]);
+        let leader = VehicleScope::default().get_leader_opt(&mut client, "veh0", 100.0).unwrap();
+        assert_eq!(leader, Some(("veh1".to_string(), 12.5)));
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_LEADER, "veh0", Some(&mock::double(100.0)))]
+        );
+    }
+
+    #[test]
+    fn leader_opt_is_none_for_an_empty_id() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_LEADER,
+            "veh0",
+            neighbor_value("", -1.0),
+        )]);
+        assert_eq!(VehicleScope::default().get_leader_opt(&mut client, "veh0", 100.0).unwrap(), None);
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn follower_opt_returns_the_follower_and_gap() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_FOLLOWER,
+            "veh1",
+            neighbor_value("veh0", 12.5),
+        )]);
+        let follower = VehicleScope::default().get_follower_opt(&mut client, "veh1", 50.0).unwrap();
+        assert_eq!(follower, Some(("veh0".to_string(), 12.5)));
+        drop(client);
+        assert_eq!(
+            server.requests(),
+            vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_FOLLOWER, "veh1", Some(&mock::double(50.0)))]
+        );
+    }
+
+    #[test]
+    fn follower_opt_is_none_for_an_empty_id() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_FOLLOWER,
+            "veh1",
+            neighbor_value("", -1.0),
+        )]);
+        assert_eq!(VehicleScope::default().get_follower_opt(&mut client, "veh1", 50.0).unwrap(), None);
+        drop(client);
+        server.requests();
+    }
+
+    #[test]
+    fn add_with_sends_the_same_payload_as_add() {
+        let (mut client, server) =
+            mock::connect_replies(vec![mock::status(CMD_SET_VEHICLE_VARIABLE), mock::status(CMD_SET_VEHICLE_VARIABLE)]);
+        let scope = VehicleScope::default();
+        scope
+            .add(
+                &mut client, "bus0", "r_line7", "bus", "120".to_string(), "best", "base", "max",
+                "current", "max", "current", "", "", "line7", 40, 3,
+            )
+            .unwrap();
+        let opts = VehicleAddOptions::default()
+            .route("r_line7")
+            .type_id("bus")
+            .depart("120")
+            .depart_lane("best")
+            .depart_speed("max")
+            .line("line7")
+            .person_capacity(40)
+            .person_number(3);
+        scope.add_with(&mut client, "bus0", &opts).unwrap();
+        drop(client);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], requests[1]);
+        assert_eq!(requests[0][1..3], [CMD_SET_VEHICLE_VARIABLE, ADD_FULL]);
+    }
+
+    #[test]
+    fn get_leader_sends_a_zero_distance_literally() {
+        let (mut client, server) = mock::connect_replies(vec![mock::get_reply(
+            CMD_GET_VEHICLE_VARIABLE,
+            VAR_LEADER,
+            "veh0",
+            neighbor_value("veh1", 4.0),
+        )]);
+        let leader = VehicleScope::default().get_leader(&mut client, "veh0", 0.0).unwrap();
+        assert_eq!(leader, ("veh1".to_string(), 4.0));
+        drop(client);
+        let requests = server.requests();
+        assert_eq!(requests, vec![mock::command(CMD_GET_VEHICLE_VARIABLE, VAR_LEADER, "veh0", Some(&mock::double(0.0)))]);
+        assert!(requests[0].ends_with(&[TYPE_DOUBLE, 0, 0, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn get_all_positions_batches_one_get_per_vehicle() {
+        let v = CMD_GET_VEHICLE_VARIABLE;
+        let (mut client, server) = mock::connect_replies(vec![
+            mock::get_reply(v, TRACI_ID_LIST, "", mock::string_list(&["veh0", "veh1", "veh2"])),
+            mock::batch_get_reply(&[
+                (v, VAR_POSITION, "veh0", mock::pos_2d(1.0, 2.0)),
+                (v, VAR_POSITION, "veh1", mock::pos_2d(3.5, -4.0)),
+                (v, VAR_POSITION, "veh2", mock::pos_2d(100.0, 0.25)),
+            ]),
+        ]);
+
+        let positions = VehicleScope::default().get_all_positions(&mut client).unwrap();
+        assert_eq!(
+            positions,
+            vec![
+                ("veh0".to_string(), TraciPosition::new_2d(1.0, 2.0)),
+                ("veh1".to_string(), TraciPosition::new_2d(3.5, -4.0)),
+                ("veh2".to_string(), TraciPosition::new_2d(100.0, 0.25)),
+            ]
+        );
+
+        drop(client);
+        let batch: Vec<u8> = ["veh0", "veh1", "veh2"]
+            .into_iter()
+            .flat_map(|id| mock::command(v, VAR_POSITION, id, None))
+            .collect();
+        assert_eq!(server.requests(), vec![mock::command(v, TRACI_ID_LIST, "", None), batch]);
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
     constants::*,
     error::TraciError,
     storage::Storage,
-    types::{ContextSubscriptionResults, SubscriptionResults, TraciColor},
+    types::{ContextSubscriptionResults, SubscriptionResults, TraciColor, VehicleTypeParams},
 };
 
 /// Scope for interacting with SUMO vehicle type objects.
@@ -20,37 +20,44 @@ impl VehicleTypeScope {
     crate::impl_scope_accessors!();
 
     pub fn get_id_list(&self, client: &mut TraciClient) -> Result<Vec<String>, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, TRACI_ID_LIST, "", None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRINGLIST))?;
-        client.read_string_list_from_input()
+        crate::with_context!("vehicle_type.get_id_list", {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, TRACI_ID_LIST, "", None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRINGLIST))?;
+            client.read_string_list_from_input()
+        })
     }
 
     pub fn get_id_count(&self, client: &mut TraciClient) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, ID_COUNT, "", None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle_type.get_id_count", {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, ID_COUNT, "", None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
     }
 
     pub fn get_parameter(&self, client: &mut TraciClient, type_id: &str, key: &str) -> Result<String, TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_PARAMETER, type_id, Some(&add));
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle_type.get_parameter", type_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_PARAMETER, type_id, Some(&add));
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn set_parameter(&self, client: &mut TraciClient, type_id: &str, key: &str, value: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COMPOUND);
-        add.write_i32(2);
-        add.write_u8(TYPE_STRING);
-        add.write_string(key);
-        add.write_u8(TYPE_STRING);
-        add.write_string(value);
-        client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, VAR_PARAMETER, type_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle_type.set_parameter", type_id, {
+            let mut add = Storage::new();
+            add.begin_compound(2);
+            add.write_u8(TYPE_STRING);
+            add.write_string(key);
+            add.write_u8(TYPE_STRING);
+            add.write_string(value);
+            client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, VAR_PARAMETER, type_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -58,129 +65,199 @@ impl VehicleTypeScope {
     // -----------------------------------------------------------------------
 
     pub fn get_length(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_LENGTH, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_length", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_LENGTH, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_max_speed(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MAXSPEED, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_max_speed", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MAXSPEED, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_speed_factor(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SPEED_FACTOR, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_speed_factor", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SPEED_FACTOR, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_speed_deviation(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SPEED_DEVIATION, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_speed_deviation", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SPEED_DEVIATION, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_accel(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_ACCEL, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_accel", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_ACCEL, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_decel(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_DECEL, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_decel", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_DECEL, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_emergency_decel(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_EMERGENCY_DECEL, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_emergency_decel", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_EMERGENCY_DECEL, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_apparent_decel(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_APPARENT_DECEL, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_apparent_decel", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_APPARENT_DECEL, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_imperfection(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_IMPERFECTION, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_imperfection", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_IMPERFECTION, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_tau(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_TAU, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_tau", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_TAU, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_vehicle_class(&self, client: &mut TraciClient, type_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_VEHICLECLASS, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle_type.get_vehicle_class", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_VEHICLECLASS, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_emission_class(&self, client: &mut TraciClient, type_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_EMISSIONCLASS, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle_type.get_emission_class", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_EMISSIONCLASS, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_shape_class(&self, client: &mut TraciClient, type_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SHAPECLASS, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle_type.get_shape_class", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_SHAPECLASS, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_min_gap(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MINGAP, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_min_gap", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MINGAP, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_width(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_WIDTH, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_width", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_WIDTH, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_height(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_HEIGHT, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_height", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_HEIGHT, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_color(&self, client: &mut TraciClient, type_id: &str) -> Result<TraciColor, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_COLOR, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_COLOR))?;
-        client.read_color_from_input()
+        crate::with_context!("vehicle_type.get_color", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_COLOR, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_COLOR))?;
+            client.read_color_from_input()
+        })
     }
 
     pub fn get_min_gap_lat(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MINGAP_LAT, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_min_gap_lat", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MINGAP_LAT, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_max_speed_lat(&self, client: &mut TraciClient, type_id: &str) -> Result<f64, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MAXSPEED_LAT, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
-        client.read_double_from_input()
+        crate::with_context!("vehicle_type.get_max_speed_lat", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_MAXSPEED_LAT, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_DOUBLE))?;
+            client.read_double_from_input()
+        })
     }
 
     pub fn get_lateral_alignment(&self, client: &mut TraciClient, type_id: &str) -> Result<String, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_LATALIGNMENT, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
-        client.read_string_from_input()
+        crate::with_context!("vehicle_type.get_lateral_alignment", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_LATALIGNMENT, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_STRING))?;
+            client.read_string_from_input()
+        })
     }
 
     pub fn get_person_capacity(&self, client: &mut TraciClient, type_id: &str) -> Result<i32, TraciError> {
-        client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_PERSON_CAPACITY, type_id, None);
-        client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_INTEGER))?;
-        client.read_int_from_input()
+        crate::with_context!("vehicle_type.get_person_capacity", type_id, {
+            client.create_command(CMD_GET_VEHICLETYPE_VARIABLE, VAR_PERSON_CAPACITY, type_id, None);
+            client.process_get(CMD_GET_VEHICLETYPE_VARIABLE, Some(TYPE_INTEGER))?;
+            client.read_int_from_input()
+        })
+    }
+
+    /// Return the common type attributes in one round trip.
+    pub fn get_all(&self, client: &mut TraciClient, type_id: &str) -> Result<VehicleTypeParams, TraciError> {
+        crate::with_context!("vehicle_type.get_all", type_id, {
+            use crate::scopes::helpers::{expect_double, expect_string};
+            let vars = [
+                VAR_LENGTH, VAR_MAXSPEED, VAR_ACCEL, VAR_DECEL, VAR_TAU, VAR_MINGAP,
+                VAR_WIDTH, VAR_HEIGHT, VAR_EMISSIONCLASS, VAR_VEHICLECLASS,
+            ];
+            let requests: Vec<(u8, u8, &str)> = vars.iter().map(|&var| (CMD_GET_VEHICLETYPE_VARIABLE, var, type_id)).collect();
+            let mut values = client.batch_get(&requests)?.into_iter();
+            let mut next = || {
+                values.next().ok_or_else(|| TraciError::Protocol("Incomplete vehicle type response".into()))
+            };
+            Ok(VehicleTypeParams {
+                length: expect_double(next()?)?,
+                max_speed: expect_double(next()?)?,
+                accel: expect_double(next()?)?,
+                decel: expect_double(next()?)?,
+                tau: expect_double(next()?)?,
+                min_gap: expect_double(next()?)?,
+                width: expect_double(next()?)?,
+                height: expect_double(next()?)?,
+                emission_class: expect_string(next()?)?,
+                vehicle_class: expect_string(next()?)?,
+            })
+        })
     }
 
     // -----------------------------------------------------------------------
@@ -206,108 +283,214 @@ impl VehicleTypeScope {
     }
 
     pub fn set_length(&self, client: &mut TraciClient, type_id: &str, length: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_LENGTH, length)
+        crate::with_context!("vehicle_type.set_length", type_id, {
+            self.set_double_var(client, type_id, VAR_LENGTH, length)
+        })
     }
 
     pub fn set_max_speed(&self, client: &mut TraciClient, type_id: &str, speed: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_MAXSPEED, speed)
+        crate::with_context!("vehicle_type.set_max_speed", type_id, {
+            self.set_double_var(client, type_id, VAR_MAXSPEED, speed)
+        })
     }
 
     pub fn set_vehicle_class(&self, client: &mut TraciClient, type_id: &str, clazz: &str) -> Result<(), TraciError> {
-        self.set_string_var(client, type_id, VAR_VEHICLECLASS, clazz)
+        crate::with_context!("vehicle_type.set_vehicle_class", type_id, {
+            self.set_string_var(client, type_id, VAR_VEHICLECLASS, clazz)
+        })
     }
 
     pub fn set_speed_factor(&self, client: &mut TraciClient, type_id: &str, factor: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_SPEED_FACTOR, factor)
+        crate::with_context!("vehicle_type.set_speed_factor", type_id, {
+            self.set_double_var(client, type_id, VAR_SPEED_FACTOR, factor)
+        })
     }
 
     pub fn set_speed_deviation(&self, client: &mut TraciClient, type_id: &str, deviation: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_SPEED_DEVIATION, deviation)
+        crate::with_context!("vehicle_type.set_speed_deviation", type_id, {
+            self.set_double_var(client, type_id, VAR_SPEED_DEVIATION, deviation)
+        })
     }
 
     pub fn set_emission_class(&self, client: &mut TraciClient, type_id: &str, clazz: &str) -> Result<(), TraciError> {
-        self.set_string_var(client, type_id, VAR_EMISSIONCLASS, clazz)
+        crate::with_context!("vehicle_type.set_emission_class", type_id, {
+            self.set_string_var(client, type_id, VAR_EMISSIONCLASS, clazz)
+        })
     }
 
     pub fn set_shape_class(&self, client: &mut TraciClient, type_id: &str, shape_class: &str) -> Result<(), TraciError> {
-        self.set_string_var(client, type_id, VAR_SHAPECLASS, shape_class)
+        crate::with_context!("vehicle_type.set_shape_class", type_id, {
+            self.set_string_var(client, type_id, VAR_SHAPECLASS, shape_class)
+        })
     }
 
     pub fn set_width(&self, client: &mut TraciClient, type_id: &str, width: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_WIDTH, width)
+        crate::with_context!("vehicle_type.set_width", type_id, {
+            self.set_double_var(client, type_id, VAR_WIDTH, width)
+        })
     }
 
     pub fn set_height(&self, client: &mut TraciClient, type_id: &str, height: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_HEIGHT, height)
+        crate::with_context!("vehicle_type.set_height", type_id, {
+            self.set_double_var(client, type_id, VAR_HEIGHT, height)
+        })
     }
 
     pub fn set_min_gap(&self, client: &mut TraciClient, type_id: &str, min_gap: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_MINGAP, min_gap)
+        crate::with_context!("vehicle_type.set_min_gap", type_id, {
+            self.set_double_var(client, type_id, VAR_MINGAP, min_gap)
+        })
     }
 
     pub fn set_accel(&self, client: &mut TraciClient, type_id: &str, accel: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_ACCEL, accel)
+        crate::with_context!("vehicle_type.set_accel", type_id, {
+            self.set_double_var(client, type_id, VAR_ACCEL, accel)
+        })
     }
 
     pub fn set_decel(&self, client: &mut TraciClient, type_id: &str, decel: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_DECEL, decel)
+        crate::with_context!("vehicle_type.set_decel", type_id, {
+            self.set_double_var(client, type_id, VAR_DECEL, decel)
+        })
     }
 
     pub fn set_emergency_decel(&self, client: &mut TraciClient, type_id: &str, decel: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_EMERGENCY_DECEL, decel)
+        crate::with_context!("vehicle_type.set_emergency_decel", type_id, {
+            self.set_double_var(client, type_id, VAR_EMERGENCY_DECEL, decel)
+        })
     }
 
     pub fn set_apparent_decel(&self, client: &mut TraciClient, type_id: &str, decel: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_APPARENT_DECEL, decel)
+        crate::with_context!("vehicle_type.set_apparent_decel", type_id, {
+            self.set_double_var(client, type_id, VAR_APPARENT_DECEL, decel)
+        })
     }
 
     pub fn set_imperfection(&self, client: &mut TraciClient, type_id: &str, imperfection: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_IMPERFECTION, imperfection)
+        crate::with_context!("vehicle_type.set_imperfection", type_id, {
+            self.set_double_var(client, type_id, VAR_IMPERFECTION, imperfection)
+        })
     }
 
     pub fn set_tau(&self, client: &mut TraciClient, type_id: &str, tau: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_TAU, tau)
+        crate::with_context!("vehicle_type.set_tau", type_id, {
+            self.set_double_var(client, type_id, VAR_TAU, tau)
+        })
     }
 
     pub fn set_color(&self, client: &mut TraciClient, type_id: &str, c: &TraciColor) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_COLOR);
-        add.write_u8(c.r);
-        add.write_u8(c.g);
-        add.write_u8(c.b);
-        add.write_u8(c.a);
-        client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, VAR_COLOR, type_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle_type.set_color", type_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_COLOR);
+            add.write_u8(c.r);
+            add.write_u8(c.g);
+            add.write_u8(c.b);
+            add.write_u8(c.a);
+            client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, VAR_COLOR, type_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn set_min_gap_lat(&self, client: &mut TraciClient, type_id: &str, min_gap_lat: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_MINGAP_LAT, min_gap_lat)
+        crate::with_context!("vehicle_type.set_min_gap_lat", type_id, {
+            self.set_double_var(client, type_id, VAR_MINGAP_LAT, min_gap_lat)
+        })
     }
 
     pub fn set_max_speed_lat(&self, client: &mut TraciClient, type_id: &str, speed: f64) -> Result<(), TraciError> {
-        self.set_double_var(client, type_id, VAR_MAXSPEED_LAT, speed)
+        crate::with_context!("vehicle_type.set_max_speed_lat", type_id, {
+            self.set_double_var(client, type_id, VAR_MAXSPEED_LAT, speed)
+        })
     }
 
     pub fn set_lateral_alignment(&self, client: &mut TraciClient, type_id: &str, lat_alignment: &str) -> Result<(), TraciError> {
-        self.set_string_var(client, type_id, VAR_LATALIGNMENT, lat_alignment)
+        crate::with_context!("vehicle_type.set_lateral_alignment", type_id, {
+            self.set_string_var(client, type_id, VAR_LATALIGNMENT, lat_alignment)
+        })
     }
 
     /// Copy a vehicle type under a new name.
     pub fn copy(&self, client: &mut TraciClient, orig_type_id: &str, new_type_id: &str) -> Result<(), TraciError> {
-        let mut add = Storage::new();
-        add.write_u8(TYPE_STRING);
-        add.write_string(new_type_id);
-        client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, COPY, orig_type_id, Some(&add));
-        client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
-        Ok(())
+        crate::with_context!("vehicle_type.copy", orig_type_id, {
+            let mut add = Storage::new();
+            add.write_u8(TYPE_STRING);
+            add.write_string(new_type_id);
+            client.create_command(CMD_SET_VEHICLETYPE_VARIABLE, COPY, orig_type_id, Some(&add));
+            client.process_set(CMD_SET_VEHICLETYPE_VARIABLE)?;
+            Ok(())
+        })
     }
 
     pub fn subscribe(&self, client: &mut TraciClient, type_id: &str, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLETYPE_VARIABLE, type_id, begin, end, vars)
+        crate::with_context!("vehicle_type.subscribe", type_id, {
+            client.subscribe_object_variable(CMD_SUBSCRIBE_VEHICLETYPE_VARIABLE, type_id, begin, end, vars)
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn subscribe_context(&self, client: &mut TraciClient, type_id: &str, domain: u8, range: f64, vars: &[u8], begin: f64, end: f64) -> Result<(), TraciError> {
-        client.subscribe_object_context(CMD_SUBSCRIBE_VEHICLETYPE_CONTEXT, type_id, begin, end, domain, range, vars)
+        crate::with_context!("vehicle_type.subscribe_context", type_id, {
+            client.subscribe_object_context(CMD_SUBSCRIBE_VEHICLETYPE_CONTEXT, type_id, begin, end, domain, range, vars)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn get_all_reads_one_batched_response() {
+        let t = CMD_GET_VEHICLETYPE_VARIABLE;
+        let reply = mock::batch_get_reply(&[
+            (t, VAR_LENGTH, "car", mock::double(4.5)),
+            (t, VAR_MAXSPEED, "car", mock::double(55.56)),
+            (t, VAR_ACCEL, "car", mock::double(2.6)),
+            (t, VAR_DECEL, "car", mock::double(4.5)),
+            (t, VAR_TAU, "car", mock::double(1.0)),
+            (t, VAR_MINGAP, "car", mock::double(2.5)),
+            (t, VAR_WIDTH, "car", mock::double(1.8)),
+            (t, VAR_HEIGHT, "car", mock::double(1.5)),
+            (t, VAR_EMISSIONCLASS, "car", mock::string("HBEFA3/PC_G_EU4")),
+            (t, VAR_VEHICLECLASS, "car", mock::string("passenger")),
+        ]);
+        let (mut client, server) = mock::connect_replies(vec![reply]);
+
+        let params = VehicleTypeScope::default().get_all(&mut client, "car").unwrap();
+        assert_eq!(
+            params,
+            VehicleTypeParams {
+                length: 4.5,
+                max_speed: 55.56,
+                accel: 2.6,
+                decel: 4.5,
+                tau: 1.0,
+                min_gap: 2.5,
+                width: 1.8,
+                height: 1.5,
+                emission_class: "HBEFA3/PC_G_EU4".into(),
+                vehicle_class: "passenger".into(),
+            }
+        );
+
+        drop(client);
+        assert_eq!(server.requests().len(), 1, "all attributes in one round trip");
+    }
+
+    #[test]
+    fn get_all_rejects_a_mistyped_attribute() {
+        let t = CMD_GET_VEHICLETYPE_VARIABLE;
+        let mut responses: Vec<_> = [VAR_LENGTH, VAR_MAXSPEED, VAR_ACCEL, VAR_DECEL, VAR_TAU, VAR_MINGAP, VAR_WIDTH, VAR_HEIGHT]
+            .into_iter()
+            .map(|var| (t, var, "car", mock::double(1.0)))
+            .collect();
+        responses.push((t, VAR_EMISSIONCLASS, "car", mock::int(3)));
+        responses.push((t, VAR_VEHICLECLASS, "car", mock::string("passenger")));
+        let (mut client, _server) = mock::connect_replies(vec![mock::batch_get_reply(&responses)]);
+
+        let err = VehicleTypeScope::default().get_all(&mut client, "car").unwrap_err();
+        assert!(matches!(err, TraciError::Protocol(_)), "{err}");
     }
 }
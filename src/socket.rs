@@ -12,6 +12,7 @@
 
 use std::io::{Read, Write};
 use std::net::TcpStream;
+use std::time::Duration;
 
 use crate::error::TraciError;
 use crate::storage::Storage;
@@ -42,6 +43,27 @@ impl TraciSocket {
         Ok(Self { stream })
     }
 
+    /// Connect like [`Self::connect`], retrying up to `retries` more times,
+    /// `delay` apart, while the server refuses the connection (e.g. because
+    /// SUMO is still starting up).
+    ///
+    /// Any other error is returned immediately. Once the retries are
+    /// exhausted the last connection error is returned.
+    pub fn connect_with_retries(host: &str, port: u16, retries: i32, delay: Duration) -> Result<Self, TraciError> {
+        let mut attempt = 0;
+        loop {
+            match Self::connect(host, port) {
+                Err(TraciError::Connection(e))
+                    if e.kind() == std::io::ErrorKind::ConnectionRefused && attempt < retries =>
+                {
+                    attempt += 1;
+                    std::thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Send the entire contents of `storage` as a length-framed TraCI message.
     ///
     /// The 4-byte big-endian `total_length` (header + payload) is prepended
@@ -50,8 +72,8 @@ impl TraciSocket {
         let payload = storage.as_bytes();
         let total_len = (LENGTH_LEN + payload.len()) as u32;
         let header = total_len.to_be_bytes();
-        self.stream.write_all(&header).map_err(TraciError::Connection)?;
-        self.stream.write_all(payload).map_err(TraciError::Connection)?;
+        self.stream.write_all(&header).map_err(io_error)?;
+        self.stream.write_all(payload).map_err(io_error)?;
         Ok(())
     }
 
@@ -63,7 +85,7 @@ impl TraciSocket {
     pub fn receive_exact(&mut self) -> Result<Storage, TraciError> {
         // Read the 4-byte length header.
         let mut header = [0u8; LENGTH_LEN];
-        self.stream.read_exact(&mut header).map_err(TraciError::Connection)?;
+        self.stream.read_exact(&mut header).map_err(io_error)?;
         let total_len = u32::from_be_bytes(header) as usize;
         if total_len < LENGTH_LEN {
             return Err(TraciError::Protocol(format!(
@@ -72,12 +94,112 @@ impl TraciSocket {
         }
         let payload_len = total_len - LENGTH_LEN;
         let mut payload = vec![0u8; payload_len];
-        self.stream.read_exact(&mut payload).map_err(TraciError::Connection)?;
+        self.stream.read_exact(&mut payload).map_err(io_error)?;
         Ok(Storage::from_bytes(payload))
     }
 
+    /// Set the read and write timeout of the underlying stream.
+    /// `None` blocks indefinitely; an expired timeout surfaces as
+    /// [`TraciError::Timeout`].
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), TraciError> {
+        self.stream.set_read_timeout(timeout).map_err(TraciError::Connection)?;
+        self.stream.set_write_timeout(timeout).map_err(TraciError::Connection)
+    }
+
+    /// Return the current read timeout of the underlying stream.
+    pub fn timeout(&self) -> Result<Option<Duration>, TraciError> {
+        self.stream.read_timeout().map_err(TraciError::Connection)
+    }
+
     /// Close the underlying TCP connection.
     pub fn close(&mut self) -> Result<(), TraciError> {
         self.stream.shutdown(std::net::Shutdown::Both).map_err(TraciError::Connection)
     }
 }
+
+/// Classify a stream I/O error: timeouts become [`TraciError::Timeout`],
+/// everything else [`TraciError::Connection`].
+fn io_error(e: std::io::Error) -> TraciError {
+    match e.kind() {
+        // Unix reports an expired socket timeout as WouldBlock, Windows as TimedOut.
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            TraciError::Timeout(format!("socket operation did not complete in time ({e})"))
+        }
+        _ => TraciError::Connection(e),
+    }
+}
+
+// ============================================================================
+// AsyncTraciSocket — tokio transport (feature `tokio`)
+// ============================================================================
+
+/// A connected TraCI TCP socket driven by tokio.
+///
+/// Frames messages exactly like [`TraciSocket`]; only the I/O is asynchronous.
+#[cfg(feature = "tokio")]
+pub struct AsyncTraciSocket {
+    stream: tokio::net::TcpStream,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncTraciSocket {
+    /// Connect to a SUMO server at `host:port` and return the connected socket.
+    pub async fn connect(host: &str, port: u16) -> Result<Self, TraciError> {
+        let stream = tokio::net::TcpStream::connect((host, port))
+            .await
+            .map_err(TraciError::Connection)?;
+        // See `TraciSocket::connect` for why Nagle's algorithm is disabled.
+        stream.set_nodelay(true).map_err(TraciError::Connection)?;
+        Ok(Self { stream })
+    }
+
+    /// Connect like [`Self::connect`], retrying up to `retries` more times,
+    /// `delay` apart, while the server refuses the connection; see
+    /// [`TraciSocket::connect_with_retries`].
+    pub async fn connect_with_retries(host: &str, port: u16, retries: i32, delay: Duration) -> Result<Self, TraciError> {
+        let mut attempt = 0;
+        loop {
+            match Self::connect(host, port).await {
+                Err(TraciError::Connection(e))
+                    if e.kind() == std::io::ErrorKind::ConnectionRefused && attempt < retries =>
+                {
+                    attempt += 1;
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send the entire contents of `storage` as a length-framed TraCI message.
+    pub async fn send_exact(&mut self, storage: &Storage) -> Result<(), TraciError> {
+        use tokio::io::AsyncWriteExt;
+        let payload = storage.as_bytes();
+        let total_len = (LENGTH_LEN + payload.len()) as u32;
+        self.stream.write_all(&total_len.to_be_bytes()).await.map_err(TraciError::Connection)?;
+        self.stream.write_all(payload).await.map_err(TraciError::Connection)?;
+        Ok(())
+    }
+
+    /// Receive exactly one length-framed TraCI message.
+    pub async fn receive_exact(&mut self) -> Result<Storage, TraciError> {
+        use tokio::io::AsyncReadExt;
+        let mut header = [0u8; LENGTH_LEN];
+        self.stream.read_exact(&mut header).await.map_err(TraciError::Connection)?;
+        let total_len = u32::from_be_bytes(header) as usize;
+        if total_len < LENGTH_LEN {
+            return Err(TraciError::Protocol(format!(
+                "Received message length {total_len} is smaller than header size {LENGTH_LEN}"
+            )));
+        }
+        let mut payload = vec![0u8; total_len - LENGTH_LEN];
+        self.stream.read_exact(&mut payload).await.map_err(TraciError::Connection)?;
+        Ok(Storage::from_bytes(payload))
+    }
+
+    /// Close the underlying TCP connection.
+    pub async fn close(&mut self) -> Result<(), TraciError> {
+        use tokio::io::AsyncWriteExt;
+        self.stream.shutdown().await.map_err(TraciError::Connection)
+    }
+}
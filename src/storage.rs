@@ -4,19 +4,26 @@
 //! All multi-byte integers and floating-point values are encoded in **big-endian**
 //! byte order, matching the SUMO TraCI wire format.
 
-use crate::error::TraciError;
+use crate::{
+    constants::{TYPE_COMPOUND, TYPE_DOUBLELIST},
+    error::TraciError,
+};
 
 /// A byte buffer that supports sequential writing and reading of TraCI wire-format
 /// primitive types.
 ///
 /// The buffer internally holds a `Vec<u8>` and keeps a separate read cursor so that
 /// write and read operations can be freely interleaved (just like the C++ `Storage`).
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Storage {
     buf: Vec<u8>,
     pos: usize,
 }
 
+/// A saved read-cursor position of a [`Storage`], see [`Storage::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
 impl Storage {
     // -----------------------------------------------------------------------
     // Construction
@@ -81,6 +88,42 @@ impl Storage {
         self.pos = 0;
     }
 
+    /// Return the byte at the read cursor without consuming it.
+    ///
+    /// ```
+    /// use traci_rs::storage::Storage;
+    /// let mut s = Storage::from_bytes(vec![7]);
+    /// assert_eq!(s.peek_u8().unwrap(), 7);
+    /// assert_eq!(s.read_u8().unwrap(), 7);
+    /// assert!(s.peek_u8().is_err());
+    /// ```
+    pub fn peek_u8(&self) -> Result<u8, TraciError> {
+        self.check_read(1)?;
+        Ok(self.buf[self.pos])
+    }
+
+    /// Save the read cursor, to [`restore`](Self::restore) it after a
+    /// speculative read of an optional field.
+    ///
+    /// ```
+    /// use traci_rs::storage::Storage;
+    /// let mut s = Storage::from_bytes(vec![0, 0, 0, 5, 9]);
+    /// let cp = s.checkpoint();
+    /// assert_eq!(s.read_i32().unwrap(), 5);
+    /// s.restore(cp);
+    /// assert_eq!(s.position(), 0);
+    /// ```
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.pos)
+    }
+
+    /// Move the read cursor back to a saved [`Checkpoint`].
+    #[inline]
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.pos = checkpoint.0;
+    }
+
     /// Append all bytes from `other` to this buffer.
     pub fn append_storage(&mut self, other: &Storage) {
         self.buf.extend_from_slice(&other.buf);
@@ -91,15 +134,31 @@ impl Storage {
     // -----------------------------------------------------------------------
 
     fn check_read(&self, n: usize) -> Result<(), TraciError> {
-        if self.pos + n > self.buf.len() {
-            Err(TraciError::Protocol(format!(
+        match self.pos.checked_add(n) {
+            Some(end) if end <= self.buf.len() => Ok(()),
+            _ => Err(TraciError::Protocol(format!(
                 "Storage: attempt to read {} bytes at position {} but buffer length is {}",
                 n,
                 self.pos,
                 self.buf.len()
-            )))
-        } else {
-            Ok(())
+            ))),
+        }
+    }
+
+    /// Read an i32 length or element count and validate it against the bytes
+    /// left in the buffer, assuming each element occupies at least
+    /// `min_item_len` bytes. Rejects negative values and counts that cannot
+    /// possibly fit, so callers can pre-allocate without risking a huge
+    /// allocation from a corrupt message.
+    fn read_count(&mut self, min_item_len: usize) -> Result<usize, TraciError> {
+        let count = self.read_i32()?;
+        let remaining = self.buf.len() - self.pos;
+        match usize::try_from(count) {
+            Ok(n) if n.saturating_mul(min_item_len) <= remaining => Ok(n),
+            _ => Err(TraciError::Protocol(format!(
+                "Storage: invalid length {count} at position {} with {remaining} bytes remaining",
+                self.pos - 4
+            ))),
         }
     }
 
@@ -139,8 +198,7 @@ impl Storage {
 
     /// Read one signed byte (−128..127), returned as `i32`.
     pub fn read_byte(&mut self) -> Result<i32, TraciError> {
-        let raw = self.read_u8()? as i32;
-        Ok(if raw < 128 { raw } else { raw - 256 })
+        Ok(self.read_u8()? as i8 as i32)
     }
 
     /// Write one signed byte value in −128..127.
@@ -150,7 +208,7 @@ impl Storage {
                 "Storage::write_byte: value {value} out of range [-128, 127]"
             )));
         }
-        self.write_u8(((value + 256) % 256) as u8);
+        self.write_u8(value as i8 as u8);
         Ok(())
     }
 
@@ -216,8 +274,7 @@ impl Storage {
 
     /// Read a length-prefixed string (4-byte big-endian length, then UTF-8 bytes).
     pub fn read_string(&mut self) -> Result<String, TraciError> {
-        let len = self.read_i32()? as usize;
-        self.check_read(len)?;
+        let len = self.read_count(1)?;
         let bytes = self.buf[self.pos..self.pos + len].to_vec();
         self.pos += len;
         String::from_utf8(bytes).map_err(|e| TraciError::Protocol(format!("Invalid UTF-8 in string: {e}")))
@@ -235,8 +292,9 @@ impl Storage {
 
     /// Read a list of length-prefixed strings.
     pub fn read_string_list(&mut self) -> Result<Vec<String>, TraciError> {
-        let count = self.read_i32()?;
-        let mut v = Vec::with_capacity(count as usize);
+        // every string carries at least its 4-byte length prefix
+        let count = self.read_count(4)?;
+        let mut v = Vec::with_capacity(count);
         for _ in 0..count {
             v.push(self.read_string()?);
         }
@@ -257,8 +315,8 @@ impl Storage {
 
     /// Read a list of big-endian f64 values.
     pub fn read_f64_list(&mut self) -> Result<Vec<f64>, TraciError> {
-        let count = self.read_i32()?;
-        let mut v = Vec::with_capacity(count as usize);
+        let count = self.read_count(8)?;
+        let mut v = Vec::with_capacity(count);
         for _ in 0..count {
             v.push(self.read_f64()?);
         }
@@ -273,6 +331,47 @@ impl Storage {
         }
     }
 
+    /// Write a `TYPE_DOUBLELIST` tag followed by the list.
+    pub fn write_double_list_typed(&mut self, list: &[f64]) {
+        self.write_u8(TYPE_DOUBLELIST);
+        self.write_f64_list(list);
+    }
+
+    // -----------------------------------------------------------------------
+    // Byte list  (u8 count + n bytes)
+    // -----------------------------------------------------------------------
+
+    /// Read a list of at most 255 bytes prefixed with a one-byte count.
+    pub fn read_u8_list(&mut self) -> Result<Vec<u8>, TraciError> {
+        let count = self.read_u8()? as usize;
+        let mut v = Vec::with_capacity(count);
+        for _ in 0..count {
+            v.push(self.read_u8()?);
+        }
+        Ok(v)
+    }
+
+    /// Write a list of bytes prefixed with a one-byte count. Lists longer than
+    /// 255 entries are a protocol error.
+    pub fn write_u8_list(&mut self, list: &[u8]) -> Result<(), TraciError> {
+        let count = u8::try_from(list.len())
+            .map_err(|_| TraciError::Protocol(format!("Storage::write_u8_list: {} entries exceed 255", list.len())))?;
+        self.write_u8(count);
+        self.buf.extend_from_slice(list);
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Compound header  (TYPE_COMPOUND tag + i32 component count)
+    // -----------------------------------------------------------------------
+
+    /// Start a compound value of `count` components; each component must
+    /// then be written with its own type tag.
+    pub fn begin_compound(&mut self, count: i32) {
+        self.write_u8(TYPE_COMPOUND);
+        self.write_i32(count);
+    }
+
     // -----------------------------------------------------------------------
     // Raw packet append
     // -----------------------------------------------------------------------
@@ -295,3 +394,169 @@ impl std::fmt::Display for Storage {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(write: impl FnOnce(&mut Storage)) -> Storage {
+        let mut s = Storage::new();
+        write(&mut s);
+        Storage::from_bytes(s.as_bytes().to_vec())
+    }
+
+    fn assert_protocol_error<T: std::fmt::Debug>(result: Result<T, TraciError>) {
+        assert!(matches!(result, Err(TraciError::Protocol(_))), "{result:?}");
+    }
+
+    #[test]
+    fn i32_is_big_endian_and_keeps_its_sign() {
+        let mut s = round_trip(|s| {
+            for v in [0, 1, -1, -2, i32::MIN, i32::MAX] {
+                s.write_i32(v);
+            }
+        });
+        assert_eq!(&s.as_bytes()[12..16], &[0xff, 0xff, 0xff, 0xfe]);
+        for v in [0, 1, -1, -2, i32::MIN, i32::MAX] {
+            assert_eq!(s.read_i32().unwrap(), v);
+        }
+        assert!(!s.valid_pos());
+    }
+
+    #[test]
+    fn i16_bounds() {
+        let mut s = round_trip(|s| {
+            s.write_i16(i16::MIN);
+            s.write_i16(i16::MAX);
+            s.write_i16(-1);
+        });
+        assert_eq!(s.as_bytes(), &[0x80, 0x00, 0x7f, 0xff, 0xff, 0xff]);
+        assert_eq!(s.read_i16().unwrap(), i16::MIN);
+        assert_eq!(s.read_i16().unwrap(), i16::MAX);
+        assert_eq!(s.read_i16().unwrap(), -1);
+    }
+
+    #[test]
+    fn signed_byte_wraps_through_u8() {
+        let mut s = round_trip(|s| {
+            for v in [-128, -1, 0, 127] {
+                s.write_byte(v).unwrap();
+            }
+        });
+        assert_eq!(s.as_bytes(), &[0x80, 0xff, 0x00, 0x7f]);
+        for v in [-128, -1, 0, 127] {
+            assert_eq!(s.read_byte().unwrap(), v);
+        }
+        s.reset_pos();
+        assert_eq!(s.read_u8().unwrap(), 0x80);
+
+        let mut s = Storage::new();
+        assert_protocol_error(s.write_byte(128));
+        assert_protocol_error(s.write_byte(-129));
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn f64_special_values() {
+        let mut s = round_trip(|s| {
+            for v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0, f64::MIN_POSITIVE, 1.0] {
+                s.write_f64(v);
+            }
+        });
+        assert_eq!(&s.as_bytes()[40..48], &[0x3f, 0xf0, 0, 0, 0, 0, 0, 0]);
+        assert!(s.read_f64().unwrap().is_nan());
+        assert_eq!(s.read_f64().unwrap(), f64::INFINITY);
+        assert_eq!(s.read_f64().unwrap(), f64::NEG_INFINITY);
+        let zero = s.read_f64().unwrap();
+        assert!(zero == 0.0 && zero.is_sign_negative());
+        assert_eq!(s.read_f64().unwrap(), f64::MIN_POSITIVE);
+        assert_eq!(s.read_f64().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn f32_special_values() {
+        let mut s = round_trip(|s| {
+            s.write_f32(f32::NAN);
+            s.write_f32(f32::NEG_INFINITY);
+            s.write_f32(-1.5);
+        });
+        assert!(s.read_f32().unwrap().is_nan());
+        assert_eq!(s.read_f32().unwrap(), f32::NEG_INFINITY);
+        assert_eq!(s.read_f32().unwrap(), -1.5);
+    }
+
+    #[test]
+    fn strings_carry_their_byte_length() {
+        let text = "Zürich → 東京";
+        let mut s = round_trip(|s| {
+            s.write_string("");
+            s.write_string(text);
+        });
+        assert_eq!(&s.as_bytes()[..4], &[0, 0, 0, 0]);
+        assert_eq!(&s.as_bytes()[4..8], &(text.len() as i32).to_be_bytes());
+        assert_eq!(s.read_string().unwrap(), "");
+        assert_eq!(s.read_string().unwrap(), text);
+        assert!(!s.valid_pos());
+    }
+
+    #[test]
+    fn string_lists_and_double_lists() {
+        let list = vec![String::new(), "veh0".to_string(), "Ώ".to_string()];
+        let mut s = round_trip(|s| {
+            s.write_string_list(&list);
+            s.write_string_list(&[]);
+            s.write_f64_list(&[-1.0, f64::INFINITY]);
+        });
+        assert_eq!(s.read_string_list().unwrap(), list);
+        assert!(s.read_string_list().unwrap().is_empty());
+        assert_eq!(s.read_f64_list().unwrap(), vec![-1.0, f64::INFINITY]);
+    }
+
+    #[test]
+    fn u8_lists_hold_at_most_255_entries() {
+        let bytes: Vec<u8> = (0..=254).collect();
+        let mut s = round_trip(|s| s.write_u8_list(&bytes).unwrap());
+        assert_eq!(s.read_u8_list().unwrap(), bytes);
+        assert_protocol_error(Storage::new().write_u8_list(&[0; 256]));
+    }
+
+    #[test]
+    fn invalid_utf8_is_a_protocol_error() {
+        let mut s = Storage::from_bytes(vec![0, 0, 0, 2, 0xc3, 0x28]);
+        assert_protocol_error(s.read_string());
+    }
+
+    #[test]
+    fn oversized_string_length_is_rejected() {
+        for len in [4, 100, i32::MAX, -1, i32::MIN] {
+            let mut bytes = len.to_be_bytes().to_vec();
+            bytes.extend_from_slice(b"abc");
+            let mut s = Storage::from_bytes(bytes);
+            assert_protocol_error(s.read_string());
+        }
+    }
+
+    #[test]
+    fn oversized_list_counts_are_rejected() {
+        // Too many elements for the remaining bytes, whatever their content
+        let mut s = Storage::from_bytes(vec![0x7f, 0xff, 0xff, 0xff, 0, 0, 0, 0]);
+        assert_protocol_error(s.read_string_list());
+        let mut s = Storage::from_bytes(vec![0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_protocol_error(s.read_f64_list());
+        let mut s = Storage::from_bytes(vec![3, 1, 2]);
+        assert_protocol_error(s.read_u8_list());
+    }
+
+    #[test]
+    fn short_reads_fail_without_moving_the_cursor() {
+        let mut s = Storage::from_bytes(vec![1, 2, 3]);
+        assert_protocol_error(s.read_i32());
+        assert_protocol_error(s.read_f64());
+        assert_eq!(s.position(), 0);
+        assert_eq!(s.read_i16().unwrap(), 0x0102);
+        assert_protocol_error(s.read_i16());
+        assert_eq!(s.read_u8().unwrap(), 3);
+        assert_protocol_error(s.read_u8());
+        assert_protocol_error(s.peek_u8());
+    }
+}
@@ -18,7 +18,12 @@ use crate::constants::INVALID_DOUBLE_VALUE;
 /// Variants map 1-to-1 to the TraCI `TYPE_*` / `POSITION_*` tags.  Where the
 /// C++ library had specialised wrapper structs (e.g. `TraCILogicVectorWrapped`)
 /// they are folded directly into the corresponding variant here.
+///
+/// With the `serde` feature the variant name is stored under `"type"` and its
+/// payload under `"value"`, e.g. `{"type":"Pos2D","value":{"x":1.0,"y":2.0}}`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum TraciValue {
     /// `TYPE_INTEGER` (0x09) — 32-bit signed integer.
     Int(i32),
@@ -34,14 +39,24 @@ pub enum TraciValue {
     Pos2D { x: f64, y: f64 },
     /// `POSITION_3D` (0x03) — 3-D Cartesian position.
     Pos3D { x: f64, y: f64, z: f64 },
+    /// `POSITION_LON_LAT` (0x00) — geographic position in degrees.
+    LonLat { lon: f64, lat: f64 },
+    /// `POSITION_LON_LAT_ALT` (0x02) — geographic position in degrees plus altitude.
+    LonLatAlt { lon: f64, lat: f64, alt: f64 },
     /// `TYPE_COLOR` (0x11) — RGBA colour.
     Color(TraciColor),
     /// `TYPE_POLYGON` (0x06) — list of 2-D positions.
     Polygon(Vec<TraciPosition>),
-    /// A complete traffic-light program logic (`TL_COMPLETE_DEFINITION_RYG`).
+    /// `TYPE_COMPOUND` (0x0f) — a sequence of typed values, decoded
+    /// element by element (e.g. a subscribed `VAR_NEXT_TLS`).
+    Compound(Vec<TraciValue>),
+    /// All program logics of a traffic light (`TL_COMPLETE_DEFINITION_RYG`).
     LogicList(Vec<TraciLogic>),
     /// A list of lane connections returned by `LANE_LINKS`.
     ConnectionList(Vec<Vec<TraciConnection>>),
+    /// Traffic-light controlled links, one group per signal index
+    /// (`TL_CONTROLLED_LINKS`).
+    LinkList(Vec<Vec<TraciLink>>),
     /// A `TraCIStage` structure (person stages, `FIND_ROUTE` result, …).
     Stage(TraciStage),
     /// Induction-loop per-vehicle data (`LAST_STEP_VEHICLE_DATA`).
@@ -50,10 +65,155 @@ pub enum TraciValue {
     NextTLSList(Vec<TraciNextTLSData>),
     /// Best-lane information for a vehicle (`VAR_BEST_LANES`).
     BestLanesList(Vec<TraciBestLanesData>),
-    /// A raw byte tag + bytes for forward-compatibility with unknown future types.
+    /// A type tag the client does not decode, with the exact payload bytes
+    /// that followed it (e.g. `POSITION_ROADMAP`, `TYPE_BOUNDINGBOX`).
     Unknown { type_id: u8, raw: Vec<u8> },
 }
 
+impl TraciValue {
+    /// Return the value of a `Double`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::TraciValue;
+    /// assert_eq!(TraciValue::Double(13.9).as_f64(), Some(13.9));
+    /// assert_eq!(TraciValue::Int(13).as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TraciValue::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Return the value of an `Int`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::TraciValue;
+    /// assert_eq!(TraciValue::Int(3).as_i32(), Some(3));
+    /// assert_eq!(TraciValue::Double(3.0).as_i32(), None);
+    /// ```
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            TraciValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Return the value of a `String`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::TraciValue;
+    /// assert_eq!(TraciValue::String("E0_0".into()).as_str(), Some("E0_0"));
+    /// assert_eq!(TraciValue::StringList(vec!["E0_0".into()]).as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TraciValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Return the value of a `StringList`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::TraciValue;
+    /// let ids = TraciValue::StringList(vec!["veh0".into(), "veh1".into()]);
+    /// assert_eq!(ids.as_string_list(), Some(&["veh0".to_string(), "veh1".to_string()][..]));
+    /// assert_eq!(TraciValue::String("veh0".into()).as_string_list(), None);
+    /// ```
+    pub fn as_string_list(&self) -> Option<&[String]> {
+        match self {
+            TraciValue::StringList(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Return the position of a `Pos2D`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::{TraciPosition, TraciValue};
+    /// let pos = TraciValue::Pos2D { x: 10.0, y: 5.0 };
+    /// assert_eq!(pos.as_pos2d(), Some(TraciPosition::new_2d(10.0, 5.0)));
+    /// assert_eq!(TraciValue::Pos3D { x: 10.0, y: 5.0, z: 0.0 }.as_pos2d(), None);
+    /// ```
+    pub fn as_pos2d(&self) -> Option<TraciPosition> {
+        match self {
+            TraciValue::Pos2D { x, y } => Some(TraciPosition::new_2d(*x, *y)),
+            _ => None,
+        }
+    }
+
+    /// Return the colour of a `Color`, or `None` for any other variant.
+    ///
+    /// ```
+    /// use traci_rs::{TraciColor, TraciValue};
+    /// assert_eq!(TraciValue::Color(TraciColor::RED).as_color(), Some(TraciColor::RED));
+    /// assert_eq!(TraciValue::Int(0xff0000).as_color(), None);
+    /// ```
+    pub fn as_color(&self) -> Option<TraciColor> {
+        match self {
+            TraciValue::Color(c) => Some(*c),
+            _ => None,
+        }
+    }
+}
+
+/// `TryFrom<TraciValue>` for the payload of a single variant. On a mismatch
+/// the original value is handed back as the error. `$value` and `$other`
+/// are a matching and a mismatching value for the doctest.
+macro_rules! impl_try_from_value {
+    ($target:ty, $variant:ident, $value:literal, $other:literal) => {
+        impl TryFrom<TraciValue> for $target {
+            type Error = TraciValue;
+
+            #[doc = concat!("Accepts `", stringify!($variant), "`.")]
+            ///
+            /// ```
+            /// use traci_rs::{TraciColor, TraciValue};
+            #[doc = concat!("let value: ", stringify!($target), " = TraciValue::", stringify!($variant), "(", $value, ").try_into().unwrap();")]
+            #[doc = concat!("assert_eq!(value, ", $value, ");")]
+            #[doc = concat!("let other = ", $other, ";")]
+            #[doc = concat!("assert_eq!(<", stringify!($target), ">::try_from(other.clone()), Err(other));")]
+            /// ```
+            fn try_from(value: TraciValue) -> Result<Self, Self::Error> {
+                match value {
+                    TraciValue::$variant(v) => Ok(v),
+                    other => Err(other),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_value!(f64, Double, "1.5", "TraciValue::Int(1)");
+impl_try_from_value!(i32, Int, "7", "TraciValue::Double(7.0)");
+impl_try_from_value!(String, String, r#""veh0".to_string()"#, r#"TraciValue::StringList(vec!["veh0".into()])"#);
+impl_try_from_value!(Vec<String>, StringList, r#"vec!["e0".to_string(), "e1".to_string()]"#, r#"TraciValue::String("e0".into())"#);
+impl_try_from_value!(TraciColor, Color, "TraciColor::BLUE", "TraciValue::Int(255)");
+
+impl TryFrom<TraciValue> for TraciPosition {
+    type Error = TraciValue;
+
+    /// Accepts `Pos2D` and `Pos3D`.
+    ///
+    /// ```
+    /// use traci_rs::{TraciPosition, TraciValue};
+    /// let flat = TraciPosition::try_from(TraciValue::Pos2D { x: 1.0, y: 2.0 }).unwrap();
+    /// assert_eq!(flat, TraciPosition::new_2d(1.0, 2.0));
+    /// let pos = TraciPosition::try_from(TraciValue::Pos3D { x: 1.0, y: 2.0, z: 3.0 }).unwrap();
+    /// assert_eq!(pos, TraciPosition::new_3d(1.0, 2.0, 3.0));
+    /// let geo = TraciValue::LonLat { lon: 2.1, lat: 41.4 };
+    /// assert_eq!(TraciPosition::try_from(geo.clone()), Err(geo));
+    /// ```
+    fn try_from(value: TraciValue) -> Result<Self, Self::Error> {
+        match value {
+            TraciValue::Pos2D { x, y } => Ok(TraciPosition::new_2d(x, y)),
+            TraciValue::Pos3D { x, y, z } => Ok(TraciPosition { x, y, z }),
+            other => Err(other),
+        }
+    }
+}
+
 // ============================================================================
 // Subscription result type aliases
 // ============================================================================
@@ -73,6 +233,7 @@ pub type ContextSubscriptionResults = HashMap<String, SubscriptionResults>;
 
 /// A 2-D or 3-D position. For 2-D positions `z` is [`INVALID_DOUBLE_VALUE`].
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciPosition {
     pub x: f64,
     pub y: f64,
@@ -91,6 +252,56 @@ impl TraciPosition {
     pub fn is_3d(&self) -> bool {
         self.z != INVALID_DOUBLE_VALUE
     }
+
+    /// Euclidean distance to `other` in the x/y plane.
+    pub fn distance_2d(&self, other: &TraciPosition) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+
+    /// Euclidean distance to `other` in space. Falls back to
+    /// [`distance_2d`](Self::distance_2d) unless both positions are 3-D.
+    ///
+    /// ```
+    /// use traci_rs::TraciPosition;
+    /// let a = TraciPosition::new_3d(0.0, 0.0, 0.0);
+    /// assert_eq!(a.distance_3d(&TraciPosition::new_3d(2.0, 3.0, 6.0)), 7.0);
+    /// assert_eq!(a.distance_3d(&TraciPosition::new_2d(3.0, 4.0)), 5.0);
+    /// ```
+    pub fn distance_3d(&self, other: &TraciPosition) -> f64 {
+        if self.is_3d() && other.is_3d() {
+            let dz = self.z - other.z;
+            (self.distance_2d(other).powi(2) + dz * dz).sqrt()
+        } else {
+            self.distance_2d(other)
+        }
+    }
+
+    /// Point halfway between `self` and `other`; 3-D only if both are.
+    pub fn midpoint(&self, other: &TraciPosition) -> TraciPosition {
+        let x = (self.x + other.x) / 2.0;
+        let y = (self.y + other.y) / 2.0;
+        if self.is_3d() && other.is_3d() {
+            TraciPosition::new_3d(x, y, (self.z + other.z) / 2.0)
+        } else {
+            TraciPosition::new_2d(x, y)
+        }
+    }
+
+    /// Length of the polyline through `shape`, summing the 2-D distances of
+    /// consecutive points (0 for fewer than two points).
+    ///
+    /// ```
+    /// use traci_rs::TraciPosition;
+    /// let shape = [
+    ///     TraciPosition::new_2d(0.0, 0.0),
+    ///     TraciPosition::new_2d(3.0, 4.0),
+    ///     TraciPosition::new_2d(3.0, 10.0),
+    /// ];
+    /// assert_eq!(TraciPosition::polyline_length(&shape), 11.0);
+    /// ```
+    pub fn polyline_length(shape: &[TraciPosition]) -> f64 {
+        shape.windows(2).map(|w| w[0].distance_2d(&w[1])).sum()
+    }
 }
 
 impl Default for TraciPosition {
@@ -105,18 +316,109 @@ impl Default for TraciPosition {
 
 /// A position on the road network (edge + lane + offset along edge).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciRoadPosition {
     pub edge_id: String,
     pub pos: f64,
     pub lane_index: i32,
 }
 
+// ============================================================================
+// Boundary
+// ============================================================================
+
+/// An axis-aligned rectangle given by its lower-left and upper-right corners,
+/// e.g. the network bounding box of `SimulationScope::get_net_boundary_rect`.
+///
+/// ```
+/// use traci_rs::{Boundary, TraciPosition};
+/// let b = Boundary::new(TraciPosition::new_2d(0.0, 0.0), TraciPosition::new_2d(100.0, 50.0));
+/// assert!(b.contains(&TraciPosition::new_2d(100.0, 0.0)));
+/// assert!(!b.contains(&TraciPosition::new_2d(100.1, 25.0)));
+/// assert_eq!((b.width(), b.height()), (100.0, 50.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Boundary {
+    pub min: TraciPosition,
+    pub max: TraciPosition,
+}
+
+impl Boundary {
+    pub fn new(min: TraciPosition, max: TraciPosition) -> Self {
+        Self { min, max }
+    }
+
+    /// Build a boundary from the two corners of a `TYPE_POLYGON` bounding
+    /// box, or `None` if fewer than two points are given.
+    pub fn from_corners(corners: &[TraciPosition]) -> Option<Self> {
+        match corners {
+            [min, max, ..] => Some(Self::new(min.clone(), max.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// Return `true` if `pos` lies inside the boundary or on its edge; `z`
+    /// is ignored.
+    pub fn contains(&self, pos: &TraciPosition) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+    }
+}
+
+// ============================================================================
+// SimTime
+// ============================================================================
+
+/// A simulation time in whole milliseconds, as returned by
+/// `SimulationScope::get_sim_time`.
+///
+/// Unlike the `i32` of `SimulationScope::get_current_time`, it does not
+/// overflow after about 24.8 days of simulated time:
+///
+/// ```
+/// use traci_rs::SimTime;
+/// let t = SimTime::from_secs_f64(2_147_483.648);
+/// assert_eq!(t.as_millis(), i32::MAX as i64 + 1);
+/// assert_eq!(t.as_secs_f64(), 2_147_483.648);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimTime(i64);
+
+impl SimTime {
+    pub fn from_millis(millis: i64) -> Self {
+        Self(millis)
+    }
+
+    /// Convert seconds, rounded to the nearest millisecond.
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self((secs * 1000.0).round() as i64)
+    }
+
+    pub fn as_millis(&self) -> i64 {
+        self.0
+    }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+}
+
 // ============================================================================
 // Colour
 // ============================================================================
 
 /// An RGBA colour (each channel 0–255).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciColor {
     pub r: u8,
     pub g: u8,
@@ -125,11 +427,63 @@ pub struct TraciColor {
 }
 
 impl TraciColor {
+    pub const RED: Self = Self { r: 255, g: 0, b: 0, a: 255 };
+    pub const GREEN: Self = Self { r: 0, g: 255, b: 0, a: 255 };
+    pub const BLUE: Self = Self { r: 0, g: 0, b: 255, a: 255 };
+    pub const YELLOW: Self = Self { r: 255, g: 255, b: 0, a: 255 };
+    pub const WHITE: Self = Self { r: 255, g: 255, b: 255, a: 255 };
+    pub const BLACK: Self = Self { r: 0, g: 0, b: 0, a: 255 };
+
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self { r, g, b, a }
     }
+
+    /// Parse `#rrggbb` or `#rrggbbaa` (the `#` is optional). Alpha defaults
+    /// to 255 for the 6-digit form.
+    ///
+    /// ```
+    /// use traci_rs::TraciColor;
+    /// assert_eq!(TraciColor::from_hex("#ff8800").unwrap(), TraciColor::new(255, 136, 0, 255));
+    /// assert_eq!(TraciColor::from_hex("ff880080").unwrap().a, 128);
+    /// assert!(TraciColor::from_hex("#ff88").is_err());
+    /// assert!(TraciColor::from_hex("#gg8800").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, ParseColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(digits.len(), 6 | 8) || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseColorError(hex.to_string()));
+        }
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or(0);
+        let a = if digits.len() == 8 { channel(6) } else { 255 };
+        Ok(Self { r: channel(0), g: channel(2), b: channel(4), a })
+    }
+
+    /// Format as `#rrggbbaa`.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
 }
 
+impl std::str::FromStr for TraciColor {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+/// Error returned by [`TraciColor::from_hex`] for a malformed colour string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid hex colour '{}', expected #rrggbb or #rrggbbaa", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
 impl Default for TraciColor {
     fn default() -> Self {
         Self { r: 0, g: 0, b: 0, a: 255 }
@@ -142,6 +496,7 @@ impl Default for TraciColor {
 
 /// One phase within a traffic light programme.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciPhase {
     pub duration: f64,
     pub state: String,
@@ -166,6 +521,7 @@ impl Default for TraciPhase {
 
 /// A complete traffic-light programme.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciLogic {
     pub program_id: String,
     /// Programme type (0 = static, 3 = actuated, …).
@@ -181,6 +537,7 @@ pub struct TraciLogic {
 
 /// One lane-to-lane connection (output of `LANE_LINKS`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciConnection {
     pub approached_lane: String,
     pub has_prio: bool,
@@ -194,6 +551,7 @@ pub struct TraciConnection {
 
 /// One lane-to-lane link (output of traffic-light controlled link queries).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciLink {
     pub from_lane: String,
     pub via_lane: String,
@@ -206,20 +564,31 @@ pub struct TraciLink {
 
 /// Per-vehicle data from an induction-loop detector (`LAST_STEP_VEHICLE_DATA`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciVehicleData {
     pub id: String,
     pub length: f64,
     pub entry_time: f64,
+    /// `-1.0` while the vehicle is still on the detector.
     pub leave_time: f64,
     pub type_id: String,
 }
 
+impl TraciVehicleData {
+    /// Return `true` if the vehicle had not left the detector at the end of
+    /// the step (`leave_time == -1`).
+    pub fn is_on_detector(&self) -> bool {
+        self.leave_time < 0.0
+    }
+}
+
 // ============================================================================
 // Upcoming traffic light data
 // ============================================================================
 
 /// One upcoming traffic light for a vehicle (`VAR_NEXT_TLS`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciNextTLSData {
     pub id: String,
     pub tl_index: i32,
@@ -234,6 +603,7 @@ pub struct TraciNextTLSData {
 
 /// Best-lane information for a vehicle (`VAR_BEST_LANES`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciBestLanesData {
     pub lane_id: String,
     pub length: f64,
@@ -249,6 +619,7 @@ pub struct TraciBestLanesData {
 
 /// A person journey stage, or a found route (`VAR_STAGE`, `FIND_ROUTE`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciStage {
     pub type_: i32,
     pub v_type: String,
@@ -291,6 +662,7 @@ impl Default for TraciStage {
 
 /// Detailed data for an upcoming or past vehicle stop.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciNextStopData {
     pub lane: String,
     pub start_pos: f64,
@@ -316,6 +688,7 @@ pub struct TraciNextStopData {
 
 /// A taxi reservation as returned by `VAR_TAXI_RESERVATIONS`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciReservation {
     pub id: String,
     pub persons: Vec<String>,
@@ -335,6 +708,7 @@ pub struct TraciReservation {
 
 /// Data about a single collision event.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciCollision {
     pub collider: String,
     pub victim: String,
@@ -353,12 +727,15 @@ pub struct TraciCollision {
 
 /// A rail signal constraint.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraciSignalConstraint {
     pub signal_id: String,
     pub trip_id: String,
     pub foe_id: String,
     pub foe_signal: String,
     pub limit: i32,
+    /// Constraint kind: 0 = predecessor, 1 = insertion predecessor,
+    /// 2 = foe insertion, 3 = insertion order, 4 = bidi predecessor.
     pub type_: i32,
     pub must_wait: bool,
     pub active: bool,
@@ -374,6 +751,7 @@ pub struct TraciSignalConstraint {
 /// All fields are updated automatically on every `simulation_step()` call once
 /// a subscription has been set up.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubscribedKinematics {
     /// 2-D Cartesian position in the SUMO network coordinate system (metres).
     pub position: TraciPosition,
@@ -384,3 +762,1148 @@ pub struct SubscribedKinematics {
     /// Heading angle (degrees, 0 = North, clockwise).
     pub angle: f64,
 }
+
+// ============================================================================
+// Emissions — aggregated pollutant values for one object
+// ============================================================================
+
+/// Emission and consumption values of a lane or edge during the last
+/// simulation step, as fetched in one round trip by
+/// `LaneScope::get_all_emissions` and `EdgeScope::get_all_emissions`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Emissions {
+    /// CO2 emission (mg).
+    pub co2: f64,
+    /// CO emission (mg).
+    pub co: f64,
+    /// HC emission (mg).
+    pub hc: f64,
+    /// PMx emission (mg).
+    pub pmx: f64,
+    /// NOx emission (mg).
+    pub nox: f64,
+    /// Fuel consumption (mg).
+    pub fuel: f64,
+    /// Noise emission (dBA).
+    pub noise: f64,
+}
+
+// ============================================================================
+// StepEvents — vehicle and person lifecycle changes during one simulation step
+// ============================================================================
+
+/// Vehicles (and optionally persons) whose lifecycle changed during the last
+/// simulation step, as returned by `TraciClient::simulation_step_with_events`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepEvents {
+    /// Vehicles that entered the network.
+    pub departed: Vec<String>,
+    /// Vehicles that reached their destination and left the network.
+    pub arrived: Vec<String>,
+    /// Vehicles that started teleporting.
+    pub teleport_started: Vec<String>,
+    /// Vehicles that finished teleporting.
+    pub teleport_ended: Vec<String>,
+    /// Vehicles involved in a collision.
+    pub collisions: Vec<String>,
+    /// Persons that entered the network. Only filled when person events are
+    /// enabled with `TraciClient::set_person_step_events`.
+    pub departed_persons: Vec<String>,
+    /// Persons that finished their plan. Only filled when person events are
+    /// enabled with `TraciClient::set_person_step_events`.
+    pub arrived_persons: Vec<String>,
+}
+
+// ============================================================================
+// Version — TraCI API and SUMO version of the server
+// ============================================================================
+
+/// The versions reported by `CMD_GETVERSION`, cached by
+/// `TraciClient::version` when connecting.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Version {
+    /// TraCI API version, e.g. [`TRACI_VERSION`](crate::constants::TRACI_VERSION).
+    pub api: i32,
+    /// SUMO version string, e.g. `"SUMO v1_20_0"`.
+    pub sumo: String,
+}
+
+// ============================================================================
+// VehicleTypeParams — common vehicle type attributes
+// ============================================================================
+
+/// The commonly used attributes of a vehicle type, as fetched in one round
+/// trip by `VehicleTypeScope::get_all`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VehicleTypeParams {
+    /// Vehicle length (m).
+    pub length: f64,
+    /// Maximum speed (m/s).
+    pub max_speed: f64,
+    /// Maximum acceleration (m/s²).
+    pub accel: f64,
+    /// Maximum deceleration (m/s²).
+    pub decel: f64,
+    /// Desired headway (s).
+    pub tau: f64,
+    /// Minimum gap to the leader when standing (m).
+    pub min_gap: f64,
+    /// Vehicle width (m).
+    pub width: f64,
+    /// Vehicle height (m).
+    pub height: f64,
+    /// Emission class, e.g. `"HBEFA3/PC_G_EU4"`.
+    pub emission_class: String,
+    /// Vehicle class, e.g. `"passenger"`.
+    pub vehicle_class: String,
+}
+
+// ============================================================================
+// VehicleAddOptions — builder for VehicleScope::add_with
+// ============================================================================
+
+/// The optional attributes of a vehicle added with `VehicleScope::add_with`.
+///
+/// Every attribute starts at SUMO's default, so only the ones that differ
+/// need to be set:
+///
+/// ```
+/// use traci_rs::VehicleAddOptions;
+/// let opts = VehicleAddOptions::default()
+///     .route("r0")
+///     .type_id("bus")
+///     .depart_lane("best")
+///     .person_capacity(40);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VehicleAddOptions {
+    pub(crate) route_id: String,
+    pub(crate) type_id: String,
+    pub(crate) depart: String,
+    pub(crate) depart_lane: String,
+    pub(crate) depart_pos: String,
+    pub(crate) depart_speed: String,
+    pub(crate) arrival_lane: String,
+    pub(crate) arrival_pos: String,
+    pub(crate) arrival_speed: String,
+    pub(crate) from_taz: String,
+    pub(crate) to_taz: String,
+    pub(crate) line: String,
+    pub(crate) person_capacity: i32,
+    pub(crate) person_number: i32,
+}
+
+impl Default for VehicleAddOptions {
+    fn default() -> Self {
+        Self {
+            route_id: "".to_string(),
+            type_id: "DEFAULT_VEHTYPE".to_string(),
+            depart: "now".to_string(),
+            depart_lane: "first".to_string(),
+            depart_pos: "base".to_string(),
+            depart_speed: "0".to_string(),
+            arrival_lane: "current".to_string(),
+            arrival_pos: "max".to_string(),
+            arrival_speed: "current".to_string(),
+            from_taz: "".to_string(),
+            to_taz: "".to_string(),
+            line: "".to_string(),
+            person_capacity: 0,
+            person_number: 0,
+        }
+    }
+}
+
+impl VehicleAddOptions {
+    /// Route to follow; empty picks the route of the first matching trip.
+    pub fn route(mut self, route_id: impl Into<String>) -> Self {
+        self.route_id = route_id.into();
+        self
+    }
+
+    /// Vehicle type.
+    pub fn type_id(mut self, type_id: impl Into<String>) -> Self {
+        self.type_id = type_id.into();
+        self
+    }
+
+    /// Departure time: seconds, `"now"`, or `"-1"` for the current time.
+    pub fn depart(mut self, depart: impl Into<String>) -> Self {
+        self.depart = depart.into();
+        self
+    }
+
+    /// Departure lane: an index or `"random"`, `"free"`, `"best"`, `"first"`, …
+    pub fn depart_lane(mut self, depart_lane: impl Into<String>) -> Self {
+        self.depart_lane = depart_lane.into();
+        self
+    }
+
+    /// Departure position: metres or `"random"`, `"free"`, `"base"`, `"last"`, …
+    pub fn depart_pos(mut self, depart_pos: impl Into<String>) -> Self {
+        self.depart_pos = depart_pos.into();
+        self
+    }
+
+    /// Departure speed: m/s or `"random"`, `"max"`, `"desired"`, …
+    pub fn depart_speed(mut self, depart_speed: impl Into<String>) -> Self {
+        self.depart_speed = depart_speed.into();
+        self
+    }
+
+    /// Arrival lane: an index or `"current"`.
+    pub fn arrival_lane(mut self, arrival_lane: impl Into<String>) -> Self {
+        self.arrival_lane = arrival_lane.into();
+        self
+    }
+
+    /// Arrival position: metres or `"random"`, `"max"`.
+    pub fn arrival_pos(mut self, arrival_pos: impl Into<String>) -> Self {
+        self.arrival_pos = arrival_pos.into();
+        self
+    }
+
+    /// Arrival speed: m/s or `"current"`.
+    pub fn arrival_speed(mut self, arrival_speed: impl Into<String>) -> Self {
+        self.arrival_speed = arrival_speed.into();
+        self
+    }
+
+    /// Origin traffic assignment zone.
+    pub fn from_taz(mut self, from_taz: impl Into<String>) -> Self {
+        self.from_taz = from_taz.into();
+        self
+    }
+
+    /// Destination traffic assignment zone.
+    pub fn to_taz(mut self, to_taz: impl Into<String>) -> Self {
+        self.to_taz = to_taz.into();
+        self
+    }
+
+    /// Public transport line.
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.line = line.into();
+        self
+    }
+
+    /// Number of persons the vehicle can carry.
+    pub fn person_capacity(mut self, person_capacity: i32) -> Self {
+        self.person_capacity = person_capacity;
+        self
+    }
+
+    /// Number of persons on board at departure.
+    pub fn person_number(mut self, person_number: i32) -> Self {
+        self.person_number = person_number;
+        self
+    }
+}
+
+// ============================================================================
+// PersonAddOptions — builder for PersonScope::add_with
+// ============================================================================
+
+/// One stage queued by [`PersonAddOptions`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PersonPlanStage {
+    Walking { edges: Vec<String>, arrival_pos: f64 },
+    Driving { to_edge: String, lines: String },
+    Waiting { duration: f64 },
+    Full(TraciStage),
+}
+
+/// A person added with `PersonScope::add_with`, together with the stages of
+/// its plan.
+///
+/// Stages are appended in the order they are queued:
+///
+/// ```
+/// use traci_rs::PersonAddOptions;
+/// let opts = PersonAddOptions::new("e0")
+///     .pos(5.0)
+///     .walk(&["e0", "e1"], 20.0)
+///     .drive("e3", "bus42");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonAddOptions {
+    pub(crate) edge_id: String,
+    pub(crate) pos: f64,
+    pub(crate) depart: f64,
+    pub(crate) type_id: String,
+    pub(crate) stages: Vec<PersonPlanStage>,
+}
+
+impl PersonAddOptions {
+    /// Start a person on `edge_id`, departing now with the default pedestrian
+    /// type.
+    pub fn new(edge_id: impl Into<String>) -> Self {
+        Self {
+            edge_id: edge_id.into(),
+            pos: 0.0,
+            depart: crate::constants::DEPARTFLAG_NOW as f64,
+            type_id: "DEFAULT_PEDTYPE".to_string(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// Departure position on the start edge.
+    pub fn pos(mut self, pos: f64) -> Self {
+        self.pos = pos;
+        self
+    }
+
+    /// Departure time in seconds.
+    pub fn depart(mut self, depart: f64) -> Self {
+        self.depart = depart;
+        self
+    }
+
+    /// Person type.
+    pub fn type_id(mut self, type_id: impl Into<String>) -> Self {
+        self.type_id = type_id.into();
+        self
+    }
+
+    /// Queue a walk along `edges`, ending at `arrival_pos` on the last edge.
+    pub fn walk<S: AsRef<str>>(mut self, edges: &[S], arrival_pos: f64) -> Self {
+        let edges = edges.iter().map(|e| e.as_ref().to_string()).collect();
+        self.stages.push(PersonPlanStage::Walking { edges, arrival_pos });
+        self
+    }
+
+    /// Queue a ride to `to_edge` with any of the space-separated `lines`.
+    pub fn drive(mut self, to_edge: impl Into<String>, lines: impl Into<String>) -> Self {
+        self.stages.push(PersonPlanStage::Driving { to_edge: to_edge.into(), lines: lines.into() });
+        self
+    }
+
+    /// Queue a wait of `duration` seconds.
+    pub fn wait(mut self, duration: f64) -> Self {
+        self.stages.push(PersonPlanStage::Waiting { duration });
+        self
+    }
+
+    /// Queue a fully specified stage.
+    pub fn stage(mut self, stage: TraciStage) -> Self {
+        self.stages.push(PersonPlanStage::Full(stage));
+        self
+    }
+}
+
+// ============================================================================
+// RoutingMode — typed builder for VAR_ROUTING_MODE
+// ============================================================================
+
+/// Builder for the `ROUTING_MODE_*` bit set passed to
+/// `VehicleScope::set_routing_mode_typed`.
+///
+/// The base mode (default, aggregated, effort or combined) is combined with
+/// the `ROUTING_MODE_IGNORE_TRANSIENT_PERMISSIONS` flag by [`RoutingMode::bits`]:
+///
+/// ```
+/// use traci_rs::RoutingMode;
+/// let mode = RoutingMode::default().aggregated().ignore_transient_permissions(true);
+/// assert_eq!(mode.bits(), 0x09);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RoutingMode {
+    base: i32,
+    ignore_transient_permissions: bool,
+}
+
+impl RoutingMode {
+    /// Route on aggregated travel times.
+    pub fn aggregated(mut self) -> Self {
+        self.base = crate::constants::ROUTING_MODE_AGGREGATED;
+        self
+    }
+
+    /// Route on efforts instead of travel times.
+    pub fn effort(mut self) -> Self {
+        self.base = crate::constants::ROUTING_MODE_EFFORT;
+        self
+    }
+
+    /// Route on aggregated travel times and efforts.
+    pub fn combined(mut self) -> Self {
+        self.base = crate::constants::ROUTING_MODE_COMBINED;
+        self
+    }
+
+    /// Ignore temporary edge permission changes (e.g. closed lanes) when routing.
+    pub fn ignore_transient_permissions(mut self, ignore: bool) -> Self {
+        self.ignore_transient_permissions = ignore;
+        self
+    }
+
+    /// Return the raw value accepted by `VehicleScope::set_routing_mode`.
+    pub fn bits(&self) -> i32 {
+        if self.ignore_transient_permissions {
+            self.base | crate::constants::ROUTING_MODE_IGNORE_TRANSIENT_PERMISSIONS
+        } else {
+            self.base
+        }
+    }
+}
+
+// ============================================================================
+// SpeedMode — typed bit set for VAR_SPEEDSETMODE
+// ============================================================================
+
+/// The speed mode bit set of `VehicleScope::set_speed_mode_typed`.
+///
+/// Each constant is one bit of SUMO's speed mode; a set bit makes the vehicle
+/// respect the corresponding constraint when its speed is controlled via
+/// TraCI. [`SpeedMode::default`] is SUMO's default of all checks enabled:
+///
+/// ```
+/// use traci_rs::SpeedMode;
+/// assert_eq!(SpeedMode::default().bits(), 31);
+/// let mode = SpeedMode::SAFE_SPEED | SpeedMode::MAX_DECEL;
+/// assert!(mode.contains(SpeedMode::MAX_DECEL));
+/// assert!(!mode.contains(SpeedMode::RIGHT_OF_WAY));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpeedMode(i32);
+
+impl SpeedMode {
+    /// No checks at all.
+    pub const NONE: Self = Self(0);
+    /// Bit 0: regard the safe speed towards the leader.
+    pub const SAFE_SPEED: Self = Self(1);
+    /// Bit 1: regard the maximum acceleration.
+    pub const MAX_ACCEL: Self = Self(1 << 1);
+    /// Bit 2: regard the maximum deceleration.
+    pub const MAX_DECEL: Self = Self(1 << 2);
+    /// Bit 3: regard the right of way towards vehicles approaching a junction.
+    pub const RIGHT_OF_WAY: Self = Self(1 << 3);
+    /// Bit 4: brake hard to avoid passing a red light.
+    pub const BRAKE_AT_RED: Self = Self(1 << 4);
+    /// Bit 5: disregard the right of way towards vehicles already inside a
+    /// junction.
+    pub const IGNORE_JUNCTION_FOES: Self = Self(1 << 5);
+
+    /// Wrap a raw speed mode value.
+    pub fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Return the raw value accepted by `VehicleScope::set_speed_mode`.
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    /// Return `true` if every bit of `other` is set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for SpeedMode {
+    fn default() -> Self {
+        Self::SAFE_SPEED | Self::MAX_ACCEL | Self::MAX_DECEL | Self::RIGHT_OF_WAY | Self::BRAKE_AT_RED
+    }
+}
+
+impl std::ops::BitOr for SpeedMode {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+// ============================================================================
+// KeepRoute — typed bit set for MOVE_TO_XY
+// ============================================================================
+
+/// The `keepRoute` bit set of `VehicleScope::move_to_xy_opts`.
+///
+/// Without any bit set the vehicle is mapped onto the closest lane of the
+/// whole network and its route is replaced by that edge.
+/// [`KeepRoute::default`] is [`KeepRoute::KEEP_ROUTE`], the default of the
+/// SUMO clients:
+///
+/// ```
+/// use traci_rs::KeepRoute;
+/// assert_eq!(KeepRoute::default().bits(), 1);
+/// let mode = KeepRoute::FREE | KeepRoute::STRICT;
+/// assert!(mode.contains(KeepRoute::STRICT));
+/// assert!(!mode.contains(KeepRoute::KEEP_ROUTE));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeepRoute(i32);
+
+impl KeepRoute {
+    /// Map onto any edge of the network and replace the route.
+    pub const NONE: Self = Self(0);
+    /// Bit 0: only map onto the edges of the current route.
+    pub const KEEP_ROUTE: Self = Self(1);
+    /// Bit 1: allow placing the vehicle off the road network.
+    pub const FREE: Self = Self(1 << 1);
+    /// Bit 2: map strictly onto the current route, never onto other edges.
+    pub const STRICT: Self = Self(1 << 2);
+
+    /// Wrap a raw `keepRoute` value.
+    pub fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Return the raw value accepted by `VehicleScope::move_to_xy`.
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    /// Return `true` if every bit of `other` is set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for KeepRoute {
+    fn default() -> Self {
+        Self::KEEP_ROUTE
+    }
+}
+
+impl std::ops::BitOr for KeepRoute {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+// ============================================================================
+// VehicleVar — typed variable ids for VehicleScope::subscribe_vars
+// ============================================================================
+
+/// A vehicle variable that can be subscribed to without parameters, for
+/// `VehicleScope::subscribe_vars`. Each discriminant is the matching `VAR_*`
+/// constant, so a duplicated id fails to compile.
+///
+/// ```
+/// use traci_rs::{constants::VAR_SPEED, VehicleVar};
+/// assert_eq!(VehicleVar::Speed.as_u8(), VAR_SPEED);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum VehicleVar {
+    Position = crate::constants::VAR_POSITION,
+    Position3D = crate::constants::VAR_POSITION3D,
+    Speed = crate::constants::VAR_SPEED,
+    SpeedLat = crate::constants::VAR_SPEED_LAT,
+    Acceleration = crate::constants::VAR_ACCELERATION,
+    Angle = crate::constants::VAR_ANGLE,
+    Slope = crate::constants::VAR_SLOPE,
+    RoadId = crate::constants::VAR_ROAD_ID,
+    LaneId = crate::constants::VAR_LANE_ID,
+    LaneIndex = crate::constants::VAR_LANE_INDEX,
+    LanePosition = crate::constants::VAR_LANEPOSITION,
+    LanePositionLat = crate::constants::VAR_LANEPOSITION_LAT,
+    RouteId = crate::constants::VAR_ROUTE_ID,
+    RouteIndex = crate::constants::VAR_ROUTE_INDEX,
+    Edges = crate::constants::VAR_EDGES,
+    TypeId = crate::constants::VAR_TYPE,
+    Distance = crate::constants::VAR_DISTANCE,
+    WaitingTime = crate::constants::VAR_WAITING_TIME,
+    AccumulatedWaitingTime = crate::constants::VAR_ACCUMULATED_WAITING_TIME,
+    TimeLoss = crate::constants::VAR_TIMELOSS,
+    Signals = crate::constants::VAR_SIGNALS,
+    StopState = crate::constants::VAR_STOPSTATE,
+    Co2Emission = crate::constants::VAR_CO2EMISSION,
+    FuelConsumption = crate::constants::VAR_FUELCONSUMPTION,
+    ElectricityConsumption = crate::constants::VAR_ELECTRICITYCONSUMPTION,
+    NoiseEmission = crate::constants::VAR_NOISEEMISSION,
+    BestLanes = crate::constants::VAR_BEST_LANES,
+    Length = crate::constants::VAR_LENGTH,
+    Width = crate::constants::VAR_WIDTH,
+    MaxSpeed = crate::constants::VAR_MAXSPEED,
+    AllowedSpeed = crate::constants::VAR_ALLOWED_SPEED,
+    PersonNumber = crate::constants::VAR_PERSON_NUMBER,
+}
+
+impl VehicleVar {
+    /// Return the `VAR_*` id sent on the wire.
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+// ============================================================================
+// StopState — typed bit set for VAR_STOPSTATE
+// ============================================================================
+
+/// The stop state returned by `VehicleScope::get_stop_state_typed`.
+///
+/// Bit 0 is set while the vehicle is stopped; the remaining bits are the
+/// `STOP_*` flags of the stop, shifted up by one:
+///
+/// ```
+/// use traci_rs::{constants::STOP_PARKING_AREA, StopState};
+/// let state = StopState::from_bits(1 | (STOP_PARKING_AREA as i32) << 1);
+/// assert!(state.is_stopped() && state.is_at_parking_area());
+/// assert!(!state.is_parking());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StopState(i32);
+
+impl StopState {
+    /// The vehicle is stopped.
+    pub const STOPPED: Self = Self(1);
+    /// The stop is a parking stop (`STOP_PARKING`).
+    pub const PARKING: Self = Self::flag(crate::constants::STOP_PARKING);
+    /// The stop ends when a person boards (`STOP_TRIGGERED`).
+    pub const TRIGGERED: Self = Self::flag(crate::constants::STOP_TRIGGERED);
+    /// The stop ends when a container is loaded (`STOP_CONTAINER_TRIGGERED`).
+    pub const CONTAINER_TRIGGERED: Self = Self::flag(crate::constants::STOP_CONTAINER_TRIGGERED);
+    /// The stop is at a bus stop (`STOP_BUS_STOP`).
+    pub const BUS_STOP: Self = Self::flag(crate::constants::STOP_BUS_STOP);
+    /// The stop is at a container stop (`STOP_CONTAINER_STOP`).
+    pub const CONTAINER_STOP: Self = Self::flag(crate::constants::STOP_CONTAINER_STOP);
+    /// The stop is at a charging station (`STOP_CHARGING_STATION`).
+    pub const CHARGING_STATION: Self = Self::flag(crate::constants::STOP_CHARGING_STATION);
+    /// The stop is at a parking area (`STOP_PARKING_AREA`).
+    pub const PARKING_AREA: Self = Self::flag(crate::constants::STOP_PARKING_AREA);
+    /// The stop is at an overhead wire (`STOP_OVERHEAD_WIRE`).
+    pub const OVERHEAD_WIRE: Self = Self::flag(crate::constants::STOP_OVERHEAD_WIRE);
+
+    const fn flag(stop_flag: u8) -> Self {
+        Self((stop_flag as i32) << 1)
+    }
+
+    /// Wrap a raw stop state value.
+    pub fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Return the raw value returned by `VehicleScope::get_stop_state`.
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    /// Return `true` if every bit of `other` is set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.contains(Self::STOPPED)
+    }
+
+    pub fn is_parking(&self) -> bool {
+        self.contains(Self::PARKING)
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.contains(Self::TRIGGERED)
+    }
+
+    pub fn is_container_triggered(&self) -> bool {
+        self.contains(Self::CONTAINER_TRIGGERED)
+    }
+
+    pub fn is_at_bus_stop(&self) -> bool {
+        self.contains(Self::BUS_STOP)
+    }
+
+    pub fn is_at_container_stop(&self) -> bool {
+        self.contains(Self::CONTAINER_STOP)
+    }
+
+    pub fn is_at_charging_station(&self) -> bool {
+        self.contains(Self::CHARGING_STATION)
+    }
+
+    pub fn is_at_parking_area(&self) -> bool {
+        self.contains(Self::PARKING_AREA)
+    }
+
+    pub fn is_at_overhead_wire(&self) -> bool {
+        self.contains(Self::OVERHEAD_WIRE)
+    }
+}
+
+impl std::ops::BitOr for StopState {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+// ============================================================================
+// VehicleSignals — typed bit set for VAR_SIGNALS
+// ============================================================================
+
+/// The signal bit set of `VehicleScope::get_signals_typed` and
+/// `VehicleScope::set_signals_typed`.
+///
+/// ```
+/// use traci_rs::VehicleSignals;
+/// let signals = VehicleSignals::BLINKER_EMERGENCY | VehicleSignals::BRAKELIGHT;
+/// assert_eq!(signals.bits(), 12);
+/// assert!(signals.blinker_emergency() && signals.brake_light());
+/// assert!(!signals.blinker_left());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VehicleSignals(i32);
+
+impl VehicleSignals {
+    /// No signal is active.
+    pub const NONE: Self = Self(0);
+    /// The right blinker is on.
+    pub const BLINKER_RIGHT: Self = Self(crate::scopes::vehicle::SIGNAL_BLINKER_RIGHT);
+    /// The left blinker is on.
+    pub const BLINKER_LEFT: Self = Self(crate::scopes::vehicle::SIGNAL_BLINKER_LEFT);
+    /// The hazard lights are on.
+    pub const BLINKER_EMERGENCY: Self = Self(crate::scopes::vehicle::SIGNAL_BLINKER_EMERGENCY);
+    /// The brake light is on.
+    pub const BRAKELIGHT: Self = Self(crate::scopes::vehicle::SIGNAL_BRAKELIGHT);
+    /// The front light is on.
+    pub const FRONTLIGHT: Self = Self(crate::scopes::vehicle::SIGNAL_FRONTLIGHT);
+    /// The fog lights are on.
+    pub const FOGLIGHT: Self = Self(crate::scopes::vehicle::SIGNAL_FOGLIGHT);
+    /// The high beam is on.
+    pub const HIGHBEAM: Self = Self(crate::scopes::vehicle::SIGNAL_HIGHBEAM);
+    /// The vehicle is driving backwards.
+    pub const BACKDRIVE: Self = Self(crate::scopes::vehicle::SIGNAL_BACKDRIVE);
+    /// The wipers are on.
+    pub const WIPER: Self = Self(crate::scopes::vehicle::SIGNAL_WIPER);
+    /// The left door is open.
+    pub const DOOR_OPEN_LEFT: Self = Self(crate::scopes::vehicle::SIGNAL_DOOR_OPEN_LEFT);
+    /// The right door is open.
+    pub const DOOR_OPEN_RIGHT: Self = Self(crate::scopes::vehicle::SIGNAL_DOOR_OPEN_RIGHT);
+    /// The blue emergency light is on.
+    pub const EMERGENCY_BLUE: Self = Self(crate::scopes::vehicle::SIGNAL_EMERGENCY_BLUE);
+    /// The red emergency light is on.
+    pub const EMERGENCY_RED: Self = Self(crate::scopes::vehicle::SIGNAL_EMERGENCY_RED);
+    /// The yellow emergency light is on.
+    pub const EMERGENCY_YELLOW: Self = Self(crate::scopes::vehicle::SIGNAL_EMERGENCY_YELLOW);
+    /// Passed to `set_signals_typed`, hands the signals back to SUMO.
+    pub const RESET: Self = Self(crate::scopes::vehicle::SIGNAL_RESET);
+
+    /// Wrap a raw signal value.
+    pub fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Return the raw value accepted by `VehicleScope::set_signals`.
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    /// Return `true` if every bit of `other` is set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set the bits of `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Clear the bits of `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    pub fn blinker_right(&self) -> bool {
+        self.contains(Self::BLINKER_RIGHT)
+    }
+
+    pub fn blinker_left(&self) -> bool {
+        self.contains(Self::BLINKER_LEFT)
+    }
+
+    pub fn blinker_emergency(&self) -> bool {
+        self.contains(Self::BLINKER_EMERGENCY)
+    }
+
+    pub fn brake_light(&self) -> bool {
+        self.contains(Self::BRAKELIGHT)
+    }
+
+    pub fn front_light(&self) -> bool {
+        self.contains(Self::FRONTLIGHT)
+    }
+
+    pub fn fog_light(&self) -> bool {
+        self.contains(Self::FOGLIGHT)
+    }
+
+    pub fn high_beam(&self) -> bool {
+        self.contains(Self::HIGHBEAM)
+    }
+
+    pub fn back_drive(&self) -> bool {
+        self.contains(Self::BACKDRIVE)
+    }
+
+    pub fn wiper(&self) -> bool {
+        self.contains(Self::WIPER)
+    }
+
+    pub fn door_open_left(&self) -> bool {
+        self.contains(Self::DOOR_OPEN_LEFT)
+    }
+
+    pub fn door_open_right(&self) -> bool {
+        self.contains(Self::DOOR_OPEN_RIGHT)
+    }
+
+    pub fn emergency_blue(&self) -> bool {
+        self.contains(Self::EMERGENCY_BLUE)
+    }
+
+    pub fn emergency_red(&self) -> bool {
+        self.contains(Self::EMERGENCY_RED)
+    }
+
+    pub fn emergency_yellow(&self) -> bool {
+        self.contains(Self::EMERGENCY_YELLOW)
+    }
+}
+
+impl std::ops::BitOr for VehicleSignals {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+// ============================================================================
+// LaneChangeMode — typed fields for VAR_LANECHANGE_MODE
+// ============================================================================
+
+/// How one lane-change motivation interacts with TraCI lane-change requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LaneChangeBehavior {
+    /// Never change lanes for this motivation.
+    Off = 0,
+    /// Change lanes unless this conflicts with a TraCI request.
+    UnlessTraci = 1,
+    /// Change lanes even if this overrides a TraCI request.
+    OverrideTraci = 2,
+}
+
+impl LaneChangeBehavior {
+    /// Decode a 2-bit field; the undefined value 3 decodes as `OverrideTraci`.
+    fn from_bits(bits: i32) -> Self {
+        match bits & 0b11 {
+            0 => Self::Off,
+            1 => Self::UnlessTraci,
+            _ => Self::OverrideTraci,
+        }
+    }
+}
+
+/// How a vehicle executing a TraCI lane-change request treats other traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraciLaneChangeRequest {
+    /// Ignore other drivers and adapt the speed to fulfil the request.
+    IgnoreOthers = 0,
+    /// Avoid immediate collisions and adapt the speed to fulfil the request.
+    AvoidCollisions = 1,
+    /// Respect the speed and brake gaps of others and adapt the speed.
+    RespectGapsAdaptSpeed = 2,
+    /// Respect the speed and brake gaps of others without adapting the speed.
+    RespectGaps = 3,
+}
+
+/// The lane change mode of `VehicleScope::set_lane_change_mode_typed`.
+///
+/// SUMO packs six 2-bit fields into one integer, lowest bits first.
+/// [`LaneChangeMode::default`] is SUMO's default mode:
+///
+/// ```
+/// use traci_rs::{LaneChangeBehavior, LaneChangeMode};
+/// assert_eq!(LaneChangeMode::default().to_bits(), 0b0110_0101_0101);
+/// let mode = LaneChangeMode { speed_gain: LaneChangeBehavior::Off, ..Default::default() };
+/// assert_eq!(LaneChangeMode::from_bits(mode.to_bits()), mode);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LaneChangeMode {
+    /// Bits 0–1: strategic changes needed to follow the route.
+    pub strategic: LaneChangeBehavior,
+    /// Bits 2–3: cooperative changes that let others merge.
+    pub cooperative: LaneChangeBehavior,
+    /// Bits 4–5: changes to drive faster.
+    pub speed_gain: LaneChangeBehavior,
+    /// Bits 6–7: changes to keep to the right.
+    pub keep_right: LaneChangeBehavior,
+    /// Bits 8–9: handling of TraCI lane-change requests.
+    pub traci_request: TraciLaneChangeRequest,
+    /// Bits 10–11: lateral changes within a lane (sublane model).
+    pub sublane: LaneChangeBehavior,
+}
+
+impl LaneChangeMode {
+    /// Decode a raw lane change mode as returned by
+    /// `VehicleScope::get_lane_change_mode`.
+    pub fn from_bits(bits: i32) -> Self {
+        Self {
+            strategic: LaneChangeBehavior::from_bits(bits),
+            cooperative: LaneChangeBehavior::from_bits(bits >> 2),
+            speed_gain: LaneChangeBehavior::from_bits(bits >> 4),
+            keep_right: LaneChangeBehavior::from_bits(bits >> 6),
+            traci_request: match (bits >> 8) & 0b11 {
+                0 => TraciLaneChangeRequest::IgnoreOthers,
+                1 => TraciLaneChangeRequest::AvoidCollisions,
+                2 => TraciLaneChangeRequest::RespectGapsAdaptSpeed,
+                _ => TraciLaneChangeRequest::RespectGaps,
+            },
+            sublane: LaneChangeBehavior::from_bits(bits >> 10),
+        }
+    }
+
+    /// Return the raw value accepted by `VehicleScope::set_lane_change_mode`.
+    pub fn to_bits(&self) -> i32 {
+        self.strategic as i32
+            | (self.cooperative as i32) << 2
+            | (self.speed_gain as i32) << 4
+            | (self.keep_right as i32) << 6
+            | (self.traci_request as i32) << 8
+            | (self.sublane as i32) << 10
+    }
+}
+
+impl Default for LaneChangeMode {
+    fn default() -> Self {
+        Self {
+            strategic: LaneChangeBehavior::UnlessTraci,
+            cooperative: LaneChangeBehavior::UnlessTraci,
+            speed_gain: LaneChangeBehavior::UnlessTraci,
+            keep_right: LaneChangeBehavior::UnlessTraci,
+            traci_request: TraciLaneChangeRequest::RespectGapsAdaptSpeed,
+            sublane: LaneChangeBehavior::UnlessTraci,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    #[test]
+    fn routing_mode_base_modes() {
+        assert_eq!(RoutingMode::default().bits(), ROUTING_MODE_DEFAULT);
+        assert_eq!(RoutingMode::default().aggregated().bits(), ROUTING_MODE_AGGREGATED);
+        assert_eq!(RoutingMode::default().effort().bits(), ROUTING_MODE_EFFORT);
+        assert_eq!(RoutingMode::default().combined().bits(), ROUTING_MODE_COMBINED);
+        // The last base mode wins
+        assert_eq!(RoutingMode::default().effort().aggregated().bits(), ROUTING_MODE_AGGREGATED);
+    }
+
+    #[test]
+    fn routing_mode_adds_the_ignore_transient_permissions_flag() {
+        let ignore = |mode: RoutingMode| mode.ignore_transient_permissions(true).bits();
+        assert_eq!(ignore(RoutingMode::default()), 0x08);
+        assert_eq!(ignore(RoutingMode::default().aggregated()), 0x09);
+        assert_eq!(ignore(RoutingMode::default().effort()), 0x0a);
+        assert_eq!(ignore(RoutingMode::default().combined()), 0x0b);
+        // Set before or after the base mode
+        assert_eq!(RoutingMode::default().ignore_transient_permissions(true).effort().bits(), 0x0a);
+        // And cleared again
+        let mode = RoutingMode::default().combined().ignore_transient_permissions(true);
+        assert_eq!(mode.ignore_transient_permissions(false).bits(), ROUTING_MODE_COMBINED);
+    }
+
+    #[test]
+    fn speed_mode_bits_match_the_sumo_documentation() {
+        assert_eq!(SpeedMode::NONE.bits(), 0);
+        assert_eq!(SpeedMode::SAFE_SPEED.bits(), 1);
+        assert_eq!(SpeedMode::MAX_ACCEL.bits(), 2);
+        assert_eq!(SpeedMode::MAX_DECEL.bits(), 4);
+        assert_eq!(SpeedMode::RIGHT_OF_WAY.bits(), 8);
+        assert_eq!(SpeedMode::BRAKE_AT_RED.bits(), 16);
+        assert_eq!(SpeedMode::IGNORE_JUNCTION_FOES.bits(), 32);
+        assert_eq!(SpeedMode::default().bits(), 31);
+        assert_eq!((SpeedMode::MAX_ACCEL | SpeedMode::RIGHT_OF_WAY).bits(), 10);
+        assert_eq!(SpeedMode::from_bits(31), SpeedMode::default());
+    }
+
+    #[test]
+    fn lane_change_mode_default_is_the_documented_integer() {
+        assert_eq!(LaneChangeMode::default().to_bits(), 0b0110_0101_0101);
+        assert_eq!(LaneChangeMode::default().to_bits(), 1621);
+        assert_eq!(LaneChangeMode::from_bits(1621), LaneChangeMode::default());
+    }
+
+    #[test]
+    fn lane_change_mode_places_each_field_in_its_two_bits() {
+        let off = LaneChangeMode {
+            strategic: LaneChangeBehavior::Off,
+            cooperative: LaneChangeBehavior::Off,
+            speed_gain: LaneChangeBehavior::Off,
+            keep_right: LaneChangeBehavior::Off,
+            traci_request: TraciLaneChangeRequest::IgnoreOthers,
+            sublane: LaneChangeBehavior::Off,
+        };
+        assert_eq!(off.to_bits(), 0);
+        assert_eq!(LaneChangeMode { strategic: LaneChangeBehavior::OverrideTraci, ..off }.to_bits(), 0b10);
+        assert_eq!(LaneChangeMode { cooperative: LaneChangeBehavior::UnlessTraci, ..off }.to_bits(), 0b01 << 2);
+        assert_eq!(LaneChangeMode { speed_gain: LaneChangeBehavior::OverrideTraci, ..off }.to_bits(), 0b10 << 4);
+        assert_eq!(LaneChangeMode { keep_right: LaneChangeBehavior::UnlessTraci, ..off }.to_bits(), 0b01 << 6);
+        assert_eq!(LaneChangeMode { traci_request: TraciLaneChangeRequest::RespectGaps, ..off }.to_bits(), 0b11 << 8);
+        assert_eq!(LaneChangeMode { sublane: LaneChangeBehavior::OverrideTraci, ..off }.to_bits(), 0b10 << 10);
+        // 512 is the commonly used "no autonomous changes, respect others"
+        let mode = LaneChangeMode::from_bits(512);
+        assert_eq!(mode, LaneChangeMode { traci_request: TraciLaneChangeRequest::RespectGapsAdaptSpeed, ..off });
+    }
+
+    #[test]
+    fn stop_state_maps_each_stop_flag() {
+        type Check = fn(&StopState) -> bool;
+        let cases: [(u8, StopState, Check); 8] = [
+            (STOP_PARKING, StopState::PARKING, StopState::is_parking),
+            (STOP_TRIGGERED, StopState::TRIGGERED, StopState::is_triggered),
+            (STOP_CONTAINER_TRIGGERED, StopState::CONTAINER_TRIGGERED, StopState::is_container_triggered),
+            (STOP_BUS_STOP, StopState::BUS_STOP, StopState::is_at_bus_stop),
+            (STOP_CONTAINER_STOP, StopState::CONTAINER_STOP, StopState::is_at_container_stop),
+            (STOP_CHARGING_STATION, StopState::CHARGING_STATION, StopState::is_at_charging_station),
+            (STOP_PARKING_AREA, StopState::PARKING_AREA, StopState::is_at_parking_area),
+            (STOP_OVERHEAD_WIRE, StopState::OVERHEAD_WIRE, StopState::is_at_overhead_wire),
+        ];
+        for (stop_flag, flag, is_set) in cases {
+            let state = StopState::from_bits(1 | (stop_flag as i32) << 1);
+            assert!(state.is_stopped(), "0x{stop_flag:02x}");
+            assert!(state.contains(flag), "0x{stop_flag:02x}");
+            assert!(is_set(&state), "0x{stop_flag:02x}");
+            // No other flag is reported
+            let others = cases.iter().filter(|(_, _, other)| other(&state)).count();
+            assert_eq!(others, 1, "0x{stop_flag:02x}");
+        }
+        assert!(!StopState::from_bits(0).is_stopped());
+    }
+
+    #[test]
+    fn vehicle_signals_combine_emergency_and_brake_lights() {
+        let mut signals = VehicleSignals::BLINKER_EMERGENCY | VehicleSignals::BRAKELIGHT | VehicleSignals::EMERGENCY_BLUE;
+        assert_eq!(signals.bits(), 4 | 8 | 2048);
+        assert!(signals.blinker_emergency() && signals.brake_light() && signals.emergency_blue());
+        assert!(!signals.blinker_left() && !signals.blinker_right() && !signals.emergency_red());
+
+        signals.remove(VehicleSignals::BRAKELIGHT);
+        assert!(!signals.brake_light());
+        assert!(signals.blinker_emergency());
+        signals.insert(VehicleSignals::DOOR_OPEN_RIGHT);
+        assert_eq!(signals, VehicleSignals::from_bits(4 | 1024 | 2048));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pos_2d_value_round_trips_through_json() {
+        let value = TraciValue::Pos2D { x: 1.0, y: 2.0 };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"type":"Pos2D","value":{"x":1.0,"y":2.0}}"#);
+        assert_eq!(serde_json::from_str::<TraciValue>(&json).unwrap(), value);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn logic_round_trips_through_json() {
+        let phase = |duration, state: &str| TraciPhase {
+            duration,
+            state: state.to_string(),
+            min_dur: duration,
+            max_dur: duration,
+            next: vec![],
+            name: String::new(),
+        };
+        let logic = TraciLogic {
+            program_id: "0".to_string(),
+            type_: 0,
+            current_phase_index: 1,
+            phases: vec![phase(31.0, "GGrr"), phase(4.0, "yyrr"), phase(31.0, "rrGG")],
+            sub_parameter: HashMap::from([("offset".to_string(), "5".to_string())]),
+        };
+        let json = serde_json::to_string(&logic).unwrap();
+        assert_eq!(serde_json::from_str::<TraciLogic>(&json).unwrap(), logic);
+    }
+
+    /// One value of every `TraciValue` variant.
+    fn every_variant() -> Vec<TraciValue> {
+        vec![
+            TraciValue::Int(7),
+            TraciValue::Double(13.9),
+            TraciValue::String("veh0".into()),
+            TraciValue::StringList(vec!["e0".into(), "e1".into()]),
+            TraciValue::DoubleList(vec![1.0, 2.0]),
+            TraciValue::Pos2D { x: 1.0, y: 2.0 },
+            TraciValue::Pos3D { x: 1.0, y: 2.0, z: 3.0 },
+            TraciValue::LonLat { lon: 2.1, lat: 41.4 },
+            TraciValue::LonLatAlt { lon: 2.1, lat: 41.4, alt: 12.0 },
+            TraciValue::Color(TraciColor::GREEN),
+            TraciValue::Polygon(vec![TraciPosition::new_2d(0.0, 0.0)]),
+            TraciValue::Compound(vec![TraciValue::Int(1)]),
+            TraciValue::LogicList(vec![]),
+            TraciValue::ConnectionList(vec![]),
+            TraciValue::LinkList(vec![]),
+            TraciValue::Stage(TraciStage::default()),
+            TraciValue::VehicleDataList(vec![]),
+            TraciValue::NextTLSList(vec![]),
+            TraciValue::BestLanesList(vec![]),
+            TraciValue::Unknown { type_id: 0x0a, raw: vec![1, 2] },
+        ]
+    }
+
+    #[test]
+    fn accessors_match_only_their_own_variant() {
+        let values = every_variant();
+        let hits = |f: &dyn Fn(&TraciValue) -> bool| values.iter().filter(|v| f(v)).count();
+        assert_eq!(hits(&|v| v.as_f64().is_some()), 1);
+        assert_eq!(hits(&|v| v.as_i32().is_some()), 1);
+        assert_eq!(hits(&|v| v.as_str().is_some()), 1);
+        assert_eq!(hits(&|v| v.as_string_list().is_some()), 1);
+        assert_eq!(hits(&|v| v.as_pos2d().is_some()), 1);
+        assert_eq!(hits(&|v| v.as_color().is_some()), 1);
+
+        assert_eq!(values[0].as_i32(), Some(7));
+        assert_eq!(values[1].as_f64(), Some(13.9));
+        assert_eq!(values[2].as_str(), Some("veh0"));
+        assert_eq!(values[3].as_string_list(), Some(&["e0".to_string(), "e1".to_string()][..]));
+        assert_eq!(values[5].as_pos2d(), Some(TraciPosition::new_2d(1.0, 2.0)));
+        assert_eq!(values[9].as_color(), Some(TraciColor::GREEN));
+    }
+
+    #[test]
+    fn try_from_accepts_its_variant_and_hands_back_the_rest() {
+        for value in every_variant() {
+            let expected = value.clone();
+            match f64::try_from(value.clone()) {
+                Ok(v) => assert_eq!(TraciValue::Double(v), expected),
+                Err(back) => assert_eq!(back, expected),
+            }
+            match i32::try_from(value.clone()) {
+                Ok(v) => assert_eq!(TraciValue::Int(v), expected),
+                Err(back) => assert_eq!(back, expected),
+            }
+            match String::try_from(value.clone()) {
+                Ok(v) => assert_eq!(TraciValue::String(v), expected),
+                Err(back) => assert_eq!(back, expected),
+            }
+            match Vec::<String>::try_from(value.clone()) {
+                Ok(v) => assert_eq!(TraciValue::StringList(v), expected),
+                Err(back) => assert_eq!(back, expected),
+            }
+            match TraciColor::try_from(value.clone()) {
+                Ok(v) => assert_eq!(TraciValue::Color(v), expected),
+                Err(back) => assert_eq!(back, expected),
+            }
+            match TraciPosition::try_from(value) {
+                Ok(v) => assert!(matches!(expected, TraciValue::Pos2D { .. } | TraciValue::Pos3D { .. }), "{v:?}"),
+                Err(back) => assert_eq!(back, expected),
+            }
+        }
+        assert!(f64::try_from(TraciValue::Int(7)).is_err());
+        assert_eq!(TraciPosition::try_from(every_variant()[6].clone()).unwrap(), TraciPosition::new_3d(1.0, 2.0, 3.0));
+    }
+}